@@ -0,0 +1,180 @@
+//!
+//! IEEE 802.15.4 Addressing Types
+//! 
+
+/// 64-bit IEEE extended address of a node, stored in the byte order it is
+/// written into the CC2420's RAM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct IEEEAddress(pub [u8; 8]);
+
+impl IEEEAddress {
+    /// Create an address from its canonical EUI-64 value, where the most
+    /// significant byte is the first byte of the OUI (e.g. AC:DE:48:00:00:00:00:01
+    /// is 0xACDE480000000001)
+    pub fn from_u64_be(v: u64) -> IEEEAddress {
+        Self::from(v)
+    }
+
+    /// Create an address from a u64 holding the EUI-64 in reverse (over the
+    /// air) byte order, where the least significant byte is the first byte of
+    /// the OUI (e.g. AC:DE:48:00:00:00:00:01 is 0x01000000_0048DEAC)
+    pub fn from_u64_le(v: u64) -> IEEEAddress {
+        Self(v.to_be_bytes())
+    }
+
+    /// The canonical EUI-64 value of the address (see from_u64_be)
+    pub fn to_u64_be(&self) -> u64 {
+        u64::from(*self)
+    }
+
+    /// The EUI-64 value of the address in reverse byte order (see
+    /// from_u64_le)
+    pub fn to_u64_le(&self) -> u64 {
+        u64::from_be_bytes(self.0)
+    }
+}
+
+impl From<[u8; 8]> for IEEEAddress {
+    fn from(value: [u8; 8]) -> Self {
+        Self(value)
+    }
+}
+
+impl From<IEEEAddress> for [u8; 8] {
+    fn from(value: IEEEAddress) -> Self {
+        value.0
+    }
+}
+
+impl From<u64> for IEEEAddress {
+    fn from(value: u64) -> Self {
+        Self(u64_to_ieee_address(value))
+    }
+}
+
+impl From<IEEEAddress> for u64 {
+    fn from(value: IEEEAddress) -> Self {
+        ieee_address_to_u64(value.0)
+    }
+}
+
+/// Interpret an IEEE address (in over-the-air byte order, least significant
+/// byte first) as a u64
+pub fn ieee_address_to_u64(addr: [u8; 8]) -> u64 {
+    u64::from_le_bytes(addr)
+}
+
+/// Convert a u64 IEEE address (EUI-64) into over-the-air byte order, least
+/// significant byte first
+pub fn u64_to_ieee_address(v: u64) -> [u8; 8] {
+    v.to_le_bytes()
+}
+
+/// 16-bit short address of a node, assigned by the PAN coordinator during
+/// association.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct ShortAddress(pub u16);
+
+impl ShortAddress {
+    /// Broadcast short address (accepted by every node)
+    pub const BROADCAST: ShortAddress = ShortAddress(0xFFFF);
+}
+
+impl From<u16> for ShortAddress {
+    fn from(value: u16) -> Self {
+        Self(value)
+    }
+}
+
+impl From<ShortAddress> for u16 {
+    fn from(value: ShortAddress) -> Self {
+        value.0
+    }
+}
+
+/// 16-bit PAN identifier
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct PanId(pub u16);
+
+impl PanId {
+    /// Broadcast PAN identifier (accepted by every PAN)
+    pub const BROADCAST: PanId = PanId(0xFFFF);
+}
+
+impl From<u16> for PanId {
+    fn from(value: u16) -> Self {
+        Self(value)
+    }
+}
+
+impl From<PanId> for u16 {
+    fn from(value: PanId) -> Self {
+        value.0
+    }
+}
+
+/// Address of a node, either short or extended (IEEE)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Address {
+    Short(ShortAddress),
+    Extended(IEEEAddress),
+}
+
+impl Default for Address {
+    fn default() -> Self {
+        Self::Short(ShortAddress::BROADCAST)
+    }
+}
+
+impl From<ShortAddress> for Address {
+    fn from(value: ShortAddress) -> Self {
+        Self::Short(value)
+    }
+}
+
+impl From<IEEEAddress> for Address {
+    fn from(value: IEEEAddress) -> Self {
+        Self::Extended(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ieee_address_u64_round_trip() {
+        let eui = 0x0011223344556677;
+        let address = u64_to_ieee_address(eui);
+
+        assert_eq!(address, [0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11, 0x00]);
+        assert_eq!(ieee_address_to_u64(address), eui);
+    }
+
+    #[test]
+    fn test_ieee_address_from_u64_be() {
+        // Example EUI-64 using the documentation OUI AC-DE-48
+        let address = IEEEAddress::from_u64_be(0xACDE_4800_0000_0001);
+
+        assert_eq!(address.0, [0x01, 0x00, 0x00, 0x00, 0x00, 0x48, 0xDE, 0xAC]);
+        assert_eq!(address.to_u64_be(), 0xACDE_4800_0000_0001);
+        assert_eq!(address, IEEEAddress::from(0xACDE_4800_0000_0001));
+        assert_eq!(address.to_u64_be(), u64::from(address));
+    }
+
+    #[test]
+    fn test_ieee_address_from_u64_le() {
+        let address = IEEEAddress::from_u64_le(0x0100_0000_0048_DEAC);
+
+        assert_eq!(address, IEEEAddress::from_u64_be(0xACDE_4800_0000_0001));
+        assert_eq!(address.to_u64_le(), 0x0100_0000_0048_DEAC);
+    }
+
+    #[test]
+    fn test_ieee_address_from_u64() {
+        let address: IEEEAddress = 0x0011223344556677.into();
+
+        assert_eq!(address.0, [0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11, 0x00]);
+        assert_eq!(u64::from(address), 0x0011223344556677);
+    }
+}
@@ -0,0 +1,20 @@
+//!
+//! Battery Monitor Helpers
+//! 
+
+/// Estimate the battery monitor toggle voltage (in volts) for a
+/// BATTMON_VOLTAGE code (0-31), V = 1.25V * (72 - BATTMON_VOLTAGE) / 27
+pub fn battery_voltage_estimate(voltage_code: u8) -> f32 {
+    1.25 * (72.0 - voltage_code as f32) / 27.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_battery_voltage_estimate() {
+        assert!((battery_voltage_estimate(0) - 3.333).abs() < 0.001);
+        assert!((battery_voltage_estimate(31) - 1.898).abs() < 0.001);
+    }
+}
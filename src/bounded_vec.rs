@@ -0,0 +1,157 @@
+//!
+//! Fixed Capacity, Stack Allocated Vector
+//! 
+
+use core::ops::{Deref, DerefMut};
+
+/// Error returned when an operation would exceed the capacity of a
+/// fixed-size container.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapacityError;
+
+/// Vector with a fixed capacity of N elements stored inline (i.e. without
+/// heap allocation).
+#[derive(Clone, Copy, Debug)]
+pub struct BoundedVec<T: Copy + Default, const N: usize> {
+    // Backing storage (only the first len elements are valid)
+    data: [T; N],
+    // Number of elements in the vector
+    len: usize,
+}
+
+impl<T: Copy + Default, const N: usize> BoundedVec<T, N> {
+    /// Create a new, empty vector
+    pub fn new() -> Self {
+        Self {
+            data: [T::default(); N],
+            len: 0,
+        }
+    }
+
+    /// The maximum number of elements the vector can hold
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Whether the vector has reached its capacity
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Append an element to the end of the vector, returning the element
+    /// if the vector is full
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        self.data[self.len] = value;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Append all elements of a slice to the end of the vector.  Nothing is
+    /// appended if the slice does not fit.
+    pub fn extend_from_slice(&mut self, values: &[T]) -> Result<(), CapacityError> {
+        if self.len + values.len() > N {
+            return Err(CapacityError);
+        }
+        self.data[self.len..(self.len + values.len())].copy_from_slice(values);
+        self.len += values.len();
+        Ok(())
+    }
+
+    /// Remove and return the last element of the vector
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(self.data[self.len])
+    }
+
+    /// Remove all elements from the vector
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// The valid elements of the vector as a slice
+    pub fn as_slice(&self) -> &[T] {
+        &self.data[..self.len]
+    }
+
+    /// The valid elements of the vector as a mutable slice
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data[..self.len]
+    }
+}
+
+impl<T: Copy + Default, const N: usize> Default for BoundedVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + Default, const N: usize> Deref for BoundedVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: Copy + Default, const N: usize> DerefMut for BoundedVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: Copy + Default, const N: usize> AsRef<[T]> for BoundedVec<T, N> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: Copy + Default + PartialEq, const N: usize> PartialEq for BoundedVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Copy + Default + Eq, const N: usize> Eq for BoundedVec<T, N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_until_full() {
+        let mut vec: BoundedVec<u8, 2> = BoundedVec::new();
+
+        assert_eq!(vec.push(1), Ok(()));
+        assert_eq!(vec.push(2), Ok(()));
+        assert_eq!(vec.push(3), Err(3));
+        assert_eq!(vec.as_slice(), &[1, 2]);
+    }
+
+    #[test]
+    fn test_extend_from_slice() {
+        let mut vec: BoundedVec<u8, 4> = BoundedVec::new();
+
+        assert_eq!(vec.extend_from_slice(&[1, 2, 3]), Ok(()));
+        assert_eq!(vec.extend_from_slice(&[4, 5]), Err(CapacityError));
+        assert_eq!(vec.as_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_pop_and_clear() {
+        let mut vec: BoundedVec<u8, 4> = BoundedVec::new();
+        vec.extend_from_slice(&[1, 2, 3]).unwrap();
+
+        assert_eq!(vec.pop(), Some(3));
+        assert_eq!(vec.len(), 2);
+
+        vec.clear();
+        assert!(vec.is_empty());
+        assert_eq!(vec.pop(), None);
+    }
+}
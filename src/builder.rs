@@ -0,0 +1,126 @@
+//!
+//! Builder for a configured CC2420 Radio
+//!
+
+use core::marker::PhantomData;
+
+use embedded_hal::spi::SpiDevice;
+use embedded_hal::digital::InputPin;
+use embedded_hal::delay::DelayNs;
+
+use crate::{NoPin, Radio, RadioError, XOSC_STABLE_TIMEOUT_US};
+use crate::config::{Configuration, ConfigurationBuilder};
+
+/// Result of building a Radio
+pub type BuildResult<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP = NoPin<GPIOE>> = Result<Radio<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP>, RadioError<SPIE, GPIOE>>;
+
+/// Builder that constructs a Radio and applies its configuration in one step.
+pub struct RadioBuilder<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP = NoPin<GPIOE>> where
+    SPI: SpiDevice<u8, Error=SPIE>,
+    SFD: InputPin<Error=GPIOE>,
+    FIFO: InputPin<Error=GPIOE>,
+    FIFOP: InputPin<Error=GPIOE> {
+    spi: Option<SPI>,
+    sfd: Option<SFD>,
+    fifo: Option<FIFO>,
+    fifop: FIFOP,
+    config: Option<Configuration>,
+    timeout_us: u32,
+    _errors: PhantomData<(SPIE, GPIOE)>,
+}
+
+impl<SPI, SPIE, SFD, GPIOE, FIFO> Default for RadioBuilder<SPI, SPIE, SFD, GPIOE, FIFO> where
+    SPI: SpiDevice<u8, Error=SPIE>,
+    SFD: InputPin<Error=GPIOE>,
+    FIFO: InputPin<Error=GPIOE>,
+    GPIOE: embedded_hal::digital::Error {
+    fn default() -> Self {
+        Self {
+            spi: None,
+            sfd: None,
+            fifo: None,
+            fifop: NoPin::new(),
+            config: None,
+            timeout_us: XOSC_STABLE_TIMEOUT_US,
+            _errors: PhantomData,
+        }
+    }
+}
+
+impl<SPI, SPIE, SFD, GPIOE, FIFO> RadioBuilder<SPI, SPIE, SFD, GPIOE, FIFO> where
+    SPI: SpiDevice<u8, Error=SPIE>,
+    SFD: InputPin<Error=GPIOE>,
+    FIFO: InputPin<Error=GPIOE>,
+    GPIOE: embedded_hal::digital::Error {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The pin connected to the radio's FIFOP output (optional, without it
+    /// the radio uses NoPin)
+    pub fn fifop<FIFOP: InputPin<Error=GPIOE>>(self, fifop: FIFOP) -> RadioBuilder<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP> {
+        RadioBuilder {
+            spi: self.spi,
+            sfd: self.sfd,
+            fifo: self.fifo,
+            fifop,
+            config: self.config,
+            timeout_us: self.timeout_us,
+            _errors: PhantomData,
+        }
+    }
+}
+
+impl<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP> RadioBuilder<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP> where
+    SPI: SpiDevice<u8, Error=SPIE>,
+    SFD: InputPin<Error=GPIOE>,
+    FIFO: InputPin<Error=GPIOE>,
+    FIFOP: InputPin<Error=GPIOE> {
+
+    /// The SPI device connected to the radio
+    pub fn spi(mut self, spi: SPI) -> Self {
+        self.spi = Some(spi);
+        self
+    }
+
+    /// The pin connected to the radio's SFD output
+    pub fn sfd(mut self, sfd: SFD) -> Self {
+        self.sfd = Some(sfd);
+        self
+    }
+
+    /// The pin connected to the radio's FIFO output
+    pub fn fifo(mut self, fifo: FIFO) -> Self {
+        self.fifo = Some(fifo);
+        self
+    }
+
+    /// The configuration to apply to the radio (defaults to the default
+    /// Configuration)
+    pub fn configuration(mut self, config: Configuration) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// How long to wait, in microseconds, for the crystal oscillator to
+    /// stabilise while building (defaults to 5ms)
+    pub fn timeout_us(mut self, timeout_us: u32) -> Self {
+        self.timeout_us = timeout_us;
+        self
+    }
+
+    /// Construct the radio and apply its configuration
+    pub fn build<D: DelayNs>(self, delay: &mut D) -> BuildResult<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP> {
+        let spi = self.spi.ok_or(RadioError::InvalidConfiguration("Missing SPI device"))?;
+        let sfd = self.sfd.ok_or(RadioError::InvalidConfiguration("Missing SFD pin"))?;
+        let fifo = self.fifo.ok_or(RadioError::InvalidConfiguration("Missing FIFO pin"))?;
+        let config = match self.config {
+            Some(config) => config,
+            None => ConfigurationBuilder::default().build().unwrap(),
+        };
+
+        let mut radio = Radio::new_with_fifop(spi, sfd, fifo, self.fifop);
+        radio.configure(config, delay, self.timeout_us)?;
+        Ok(radio)
+    }
+}
@@ -0,0 +1,105 @@
+//!
+//! Cache of the CC2420 register values, used to skip the SPI read of
+//! read-modify-write operations
+//! 
+
+// First register address (MAIN)
+const FIRST_REGISTER: u8 = 0x10;
+// MAIN.RESETn, clearing it resets every register to its default value
+const MAIN_RESET_N: u16 = 1 << 15;
+// Number of registers (MAIN at 0x10 to RESERVED at 0x30)
+const REGISTER_COUNT: usize = 0x21;
+
+/// Last known value of each configuration register, keyed by register address
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RegisterCache {
+    values: [Option<u16>; REGISTER_COUNT],
+}
+
+impl Default for RegisterCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegisterCache {
+    /// Create an empty cache
+    pub const fn new() -> Self {
+        Self { values: [None; REGISTER_COUNT] }
+    }
+
+    /// The cached value of the register at address (if any)
+    pub fn get(&self, address: u8) -> Option<u16> {
+        Self::index(address).and_then(|index| self.values[index])
+    }
+
+    /// Store the value of the register at address (addresses outside of the
+    /// register space are ignored).  Storing MAIN with RESETn low resets the
+    /// radio, so forgets every cached value instead.
+    pub fn store(&mut self, address: u8, value: u16) {
+        if address == FIRST_REGISTER && value & MAIN_RESET_N == 0 {
+            self.invalidate();
+        } else if let Some(index) = Self::index(address) {
+            self.values[index] = Some(value);
+        }
+    }
+
+    /// Forget every cached value
+    pub fn invalidate(&mut self) {
+        self.values = [None; REGISTER_COUNT];
+    }
+
+    fn index(address: u8) -> Option<usize> {
+        let index = address.checked_sub(FIRST_REGISTER)? as usize;
+        (index < REGISTER_COUNT).then_some(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_and_get() {
+        let mut cache = RegisterCache::new();
+        assert_eq!(cache.get(0x19), None);
+
+        cache.store(0x19, 0x03C4);
+        cache.store(0x30, 0x0001);
+        assert_eq!(cache.get(0x19), Some(0x03C4));
+        assert_eq!(cache.get(0x30), Some(0x0001));
+        assert_eq!(cache.get(0x1A), None);
+    }
+
+    #[test]
+    fn test_out_of_range_addresses_ignored() {
+        let mut cache = RegisterCache::new();
+        cache.store(0x0F, 0x1234);
+        cache.store(0x31, 0x1234);
+
+        assert_eq!(cache, RegisterCache::new());
+        assert_eq!(cache.get(0x0F), None);
+    }
+
+    #[test]
+    fn test_reset_invalidates() {
+        let mut cache = RegisterCache::new();
+        cache.store(0x19, 0x03C4);
+
+        cache.store(0x10, 0x7800);
+
+        assert_eq!(cache, RegisterCache::new());
+        cache.store(0x10, 0xF800);
+        assert_eq!(cache.get(0x10), Some(0xF800));
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let mut cache = RegisterCache::new();
+        cache.store(0x10, 0xF800);
+
+        cache.invalidate();
+
+        assert_eq!(cache.get(0x10), None);
+    }
+}
@@ -0,0 +1,255 @@
+//!
+//! IEEE 802.15.4 Channels (2.4 GHz Band)
+//! 
+
+/// Error returned when a channel number is outside of the 2.4 GHz
+/// IEEE 802.15.4 channels (11-26)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidChannel(pub u8);
+
+/// One of the 16 IEEE 802.15.4 channels (11-26) in the 2.4 GHz band
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Channel(u8);
+
+impl Channel {
+    /// The lowest 2.4 GHz channel number
+    pub const MIN: u8 = 11;
+    /// The highest 2.4 GHz channel number
+    pub const MAX: u8 = 26;
+    /// The number of 2.4 GHz channels
+    pub const COUNT: usize = (Self::MAX - Self::MIN + 1) as usize;
+
+    /// The IEEE 802.15.4 channel number
+    pub fn number(&self) -> u8 {
+        self.0
+    }
+
+    /// The FSCTRL.FREQ word for the channel.  Per the datasheet:
+    /// FREQ = 357 + 5 * (k - 11)
+    pub fn frequency_word(&self) -> u16 {
+        357 + 5 * (self.0 - Self::MIN) as u16
+    }
+
+    /// Iterate over all 16 channels in order
+    pub fn all() -> impl Iterator<Item = Channel> {
+        (Self::MIN..=Self::MAX).map(Channel)
+    }
+}
+
+impl Default for Channel {
+    fn default() -> Self {
+        Self(Self::MIN)
+    }
+}
+
+impl TryFrom<u8> for Channel {
+    type Error = InvalidChannel;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        if !(Self::MIN..=Self::MAX).contains(&value) {
+            return Err(InvalidChannel(value));
+        }
+        Ok(Self(value))
+    }
+}
+
+impl From<Channel> for u8 {
+    fn from(value: Channel) -> Self {
+        value.0
+    }
+}
+
+/// One of the 16 IEEE 802.15.4 channels (11-26) in the 2.4 GHz band, as an
+/// enum for matching on specific channels
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Ieee802154Channel {
+    Channel11 = 11,
+    Channel12 = 12,
+    Channel13 = 13,
+    Channel14 = 14,
+    Channel15 = 15,
+    Channel16 = 16,
+    Channel17 = 17,
+    Channel18 = 18,
+    Channel19 = 19,
+    Channel20 = 20,
+    Channel21 = 21,
+    Channel22 = 22,
+    Channel23 = 23,
+    Channel24 = 24,
+    Channel25 = 25,
+    Channel26 = 26,
+}
+
+impl TryFrom<u8> for Ieee802154Channel {
+    type Error = InvalidChannel;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Ok(match value {
+            11 => Self::Channel11,
+            12 => Self::Channel12,
+            13 => Self::Channel13,
+            14 => Self::Channel14,
+            15 => Self::Channel15,
+            16 => Self::Channel16,
+            17 => Self::Channel17,
+            18 => Self::Channel18,
+            19 => Self::Channel19,
+            20 => Self::Channel20,
+            21 => Self::Channel21,
+            22 => Self::Channel22,
+            23 => Self::Channel23,
+            24 => Self::Channel24,
+            25 => Self::Channel25,
+            26 => Self::Channel26,
+            _ => return Err(InvalidChannel(value)),
+        })
+    }
+}
+
+impl From<Ieee802154Channel> for Channel {
+    fn from(value: Ieee802154Channel) -> Self {
+        Self(value as u8)
+    }
+}
+
+impl From<Channel> for Ieee802154Channel {
+    fn from(value: Channel) -> Self {
+        // A Channel is always in the range 11-26
+        Self::try_from(value.0).unwrap()
+    }
+}
+
+/// The FSCTRL.FREQ word for the channel
+impl From<Ieee802154Channel> for u16 {
+    fn from(value: Ieee802154Channel) -> Self {
+        Channel::from(value).frequency_word()
+    }
+}
+
+/// Error returned when a frequency control word does not fit in FSCTRL.FREQ
+/// (0-1023)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidFrequency(pub u16);
+
+/// Frequency control word (FSCTRL.FREQ), the RF operating frequency is
+/// 2048 + FREQ MHz
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Frequency(u16);
+
+impl Frequency {
+    /// The largest frequency control word
+    pub const MAX: u16 = (1 << 10) - 1;
+
+    /// The frequency control word of an IEEE 802.15.4 channel
+    pub fn from_channel(ch: Channel) -> Frequency {
+        Self(ch.frequency_word())
+    }
+
+    /// The RF operating frequency in MHz
+    pub fn to_mhz(&self) -> u16 {
+        2048 + self.0
+    }
+
+    /// The IEEE 802.15.4 channel at this frequency, if any
+    pub fn to_channel(&self) -> Option<Channel> {
+        let offset = self.0.checked_sub(Channel::default().frequency_word())?;
+        if offset % 5 != 0 {
+            return None;
+        }
+        let number = Channel::MIN as u16 + offset / 5;
+        Channel::try_from(u8::try_from(number).ok()?).ok()
+    }
+
+    /// The raw frequency control word
+    pub fn raw(&self) -> u16 {
+        self.0
+    }
+}
+
+impl Default for Frequency {
+    /// The reset value, 2405 MHz (channel 11)
+    fn default() -> Self {
+        Self::from_channel(Channel::default())
+    }
+}
+
+impl From<Channel> for Frequency {
+    fn from(value: Channel) -> Self {
+        Self::from_channel(value)
+    }
+}
+
+impl TryFrom<u16> for Frequency {
+    type Error = InvalidFrequency;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        if value > Self::MAX {
+            return Err(InvalidFrequency(value));
+        }
+        Ok(Self(value))
+    }
+}
+
+impl From<Frequency> for u16 {
+    fn from(value: Frequency) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frequency_try_from_bounds() {
+        assert_eq!(Frequency::try_from(1023).map(|f| f.raw()), Ok(1023));
+        assert_eq!(Frequency::try_from(1024), Err(InvalidFrequency(1024)));
+    }
+
+    #[test]
+    fn test_frequency_channel_conversions() {
+        for channel in Channel::all() {
+            let frequency = Frequency::from_channel(channel);
+            assert_eq!(frequency.to_mhz(), 2405 + 5 * (channel.number() as u16 - 11));
+            assert_eq!(frequency.to_channel(), Some(channel));
+        }
+
+        assert_eq!(Frequency::try_from(356).unwrap().to_channel(), None);
+        assert_eq!(Frequency::try_from(358).unwrap().to_channel(), None);
+        assert_eq!(Frequency::try_from(437).unwrap().to_channel(), None);
+    }
+
+    #[test]
+    fn test_channel_try_from_bounds() {
+        assert_eq!(Channel::try_from(10), Err(InvalidChannel(10)));
+        assert_eq!(Channel::try_from(11).map(|c| c.number()), Ok(11));
+        assert_eq!(Channel::try_from(26).map(|c| c.number()), Ok(26));
+        assert_eq!(Channel::try_from(27), Err(InvalidChannel(27)));
+    }
+
+    #[test]
+    fn test_channel_frequency_word() {
+        assert_eq!(Channel::try_from(11).unwrap().frequency_word(), 357);
+        assert_eq!(Channel::try_from(18).unwrap().frequency_word(), 392);
+        assert_eq!(Channel::try_from(26).unwrap().frequency_word(), 432);
+    }
+
+    #[test]
+    fn test_ieee_802154_channel() {
+        assert_eq!(Ieee802154Channel::try_from(10), Err(InvalidChannel(10)));
+        assert_eq!(Ieee802154Channel::try_from(11), Ok(Ieee802154Channel::Channel11));
+        assert_eq!(Ieee802154Channel::try_from(26), Ok(Ieee802154Channel::Channel26));
+        assert_eq!(Ieee802154Channel::try_from(27), Err(InvalidChannel(27)));
+        assert_eq!(u16::from(Ieee802154Channel::Channel18), 392);
+        for channel in Channel::all() {
+            assert_eq!(Channel::from(Ieee802154Channel::from(channel)), channel);
+        }
+    }
+
+    #[test]
+    fn test_channel_all() {
+        assert_eq!(Channel::all().count(), 16);
+        assert_eq!(Channel::all().last().map(|c| c.number()), Some(26));
+    }
+}
@@ -4,6 +4,10 @@
 
 use derive_builder::Builder;
 
+use crate::address::{IEEEAddress, PanId, ShortAddress};
+use crate::channel::Channel;
+use crate::security::{EncryptionKey, SecurityLevel, SecurityMode};
+
 /// Ease-of-use configuration for the CC2420 Radio Module
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
 #[builder(no_std, build_fn(error(validation_error = false)))]
@@ -11,6 +15,9 @@ pub struct Configuration {
     // Should the module be a PAN Coordinator
     #[builder(default = "false")]
     pub pan_coordinator: bool,
+    // Check the device is a CC2420 (verify_chip_id) before configuring it
+    #[builder(default = "false")]
+    pub verify_chip_id: bool,
     // Address Decoding Enabled
     #[builder(default = "true")]
     pub address_decoding: bool,
@@ -20,29 +27,337 @@ pub struct Configuration {
     // Auto Acknowledge Enabled
     #[builder(default = "false")]
     pub auto_acknowledge: bool,
+    // Clear Channel Assessment mode (1-3, see MDMCTRL0.CCA_MODE)
+    #[builder(default = "3")]
+    pub cca_mode: u8,
     // Preamble Length (n+1 bytes)
     #[builder(default = "2")]
     pub preamble_length: u8,
+    // Demodulator correlation threshold required before SFD search (0-31,
+    // see MDMCTRL1.CORR_THR)
+    #[builder(default = "20")]
+    pub correlation_threshold: u8,
+    // Number of bytes in the RX FIFO for FIFOP to go active (0-127, see
+    // IOCFG0.FIFOP_THR)
+    #[builder(default = "64")]
+    pub fifop_threshold: u8,
     // Sync Word
     #[builder(default = "[0xA7, 0x0F]")]
     pub sync_word: [u8; 2],
-    // 16-bit short address
+    // 16-bit short address (little endian, as stored in RAM)
     #[builder(default = "[0x12, 0x34]")]
     pub short_address: [u8; 2],
-    // 16-bit PAN identifier
+    // 16-bit PAN identifier (little endian, as stored in RAM)
     #[builder(default = "[0x12, 0x34]")]
     pub pan_identifier: [u8; 2],
     // 64-bit IEEE Address
     #[builder(default = "[0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0]")]
     pub ieee_address: [u8; 8],
-    // 128-bit Encryption Key (Tx)
-    #[builder(
-        default = "[0x00u8; 16]"
-    )]
-    pub tx_encryption_key: [u8; 16],
-    // 128-bit Encryption Key (Rx)
-    #[builder(
-        default = "[0x00u8; 16]"
-    )]
-    pub rx_decryption_key: [u8; 16],
-}
\ No newline at end of file
+    // In-line security mode, keys (TX key in slot 1, RX key in slot 0) and
+    // cleartext lengths, only applied along with the keys
+    #[builder(default = "SecureConfiguration::default()")]
+    pub security: SecureConfiguration,
+    // Nonce used for TX in-line security
+    #[builder(default = "[0x00u8; 16]")]
+    pub tx_nonce: [u8; 16],
+    // Nonce used for RX in-line security
+    #[builder(default = "[0x00u8; 16]")]
+    pub rx_nonce: [u8; 16],
+}
+
+/// In-line security configuration, applied separately from the RF
+/// configuration by Radio::apply_security_configuration (or with the keys of
+/// a Configuration)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct SecureConfiguration {
+    // Key used for TX in-line security (stored in key slot 1)
+    pub tx_key: EncryptionKey,
+    // Key used for RX in-line security (stored in key slot 0)
+    pub rx_key: EncryptionKey,
+    // In-line security mode
+    pub security_mode: SecurityMode,
+    // MIC length used by CBC-MAC and CCM
+    pub security_level: SecurityLevel,
+    // Number of cleartext bytes preceding the secured region of TX frames
+    pub tx_cleartext_bytes: u8,
+    // Number of cleartext bytes preceding the secured region of RX frames
+    pub rx_cleartext_bytes: u8,
+}
+
+/// Parts of a configuration stored in the radio's RAM, selecting which are
+/// written when applying a configuration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConfigureParts {
+    // Short address, PAN ID and IEEE address
+    pub addresses: bool,
+    // TX and RX encryption keys and nonces, and the in-line security mode
+    pub keys: bool,
+}
+
+impl ConfigureParts {
+    /// Write every part of the configuration
+    pub const ALL: ConfigureParts = ConfigureParts { addresses: true, keys: true };
+    /// Only write the configuration registers
+    pub const REGISTERS_ONLY: ConfigureParts = ConfigureParts { addresses: false, keys: false };
+}
+
+impl Default for ConfigureParts {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// Address recognition settings, applied together by
+/// Radio::apply_receive_filter
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct ReceiveFilter {
+    // PAN identifier of the node
+    pub pan_id: PanId,
+    // Short address of the node
+    pub short_addr: ShortAddress,
+    // IEEE address of the node
+    pub ieee_addr: IEEEAddress,
+    // Whether address recognition is enabled
+    pub address_decode: bool,
+    // Whether beacons from every PAN are accepted (IOCFG0.BCN_ACCEPT), the
+    // PAN ID of the node is still used for every other frame
+    pub accept_beacons: bool,
+}
+
+/// Transmit output power, the programmed PA_LEVEL values from the output
+/// power table of the datasheet
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum OutputPower {
+    #[default]
+    Dbm0 = 31,
+    DbmMinus1 = 27,
+    DbmMinus3 = 23,
+    DbmMinus5 = 19,
+    DbmMinus7 = 15,
+    DbmMinus10 = 11,
+    DbmMinus15 = 7,
+    DbmMinus25 = 3,
+}
+
+impl OutputPower {
+    /// The TXCTRL.PA_LEVEL value for this output power
+    pub fn pa_level(&self) -> u8 {
+        *self as u8
+    }
+
+    /// The typical output power (in dBm) from the datasheet
+    pub fn dbm(&self) -> i8 {
+        match self {
+            Self::Dbm0 => 0,
+            Self::DbmMinus1 => -1,
+            Self::DbmMinus3 => -3,
+            Self::DbmMinus5 => -5,
+            Self::DbmMinus7 => -7,
+            Self::DbmMinus10 => -10,
+            Self::DbmMinus15 => -15,
+            Self::DbmMinus25 => -25,
+        }
+    }
+
+    /// The highest output power in the table that does not exceed the power
+    /// of a given PA_LEVEL value
+    pub fn from_pa_level(pa_level: u8) -> Self {
+        match pa_level {
+            31.. => Self::Dbm0,
+            27..=30 => Self::DbmMinus1,
+            23..=26 => Self::DbmMinus3,
+            19..=22 => Self::DbmMinus5,
+            15..=18 => Self::DbmMinus7,
+            11..=14 => Self::DbmMinus10,
+            7..=10 => Self::DbmMinus15,
+            _ => Self::DbmMinus25,
+        }
+    }
+}
+
+/// Signals output on the High Speed Serial Data (HSSD) diagnostic module
+/// (IOCFG1.HSSD_SRC, values 4 and 5 are reserved)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum HssdSource {
+    #[default]
+    Off = 0,
+    // AGC status (gain setting / peak detector status / accumulator value)
+    AgcStatus = 1,
+    // ADC I and Q values
+    AdcIQ = 2,
+    // I and Q after digital down mixing and channel filtering
+    ChannelFilterIQ = 3,
+    // Input ADC I and Q values
+    InputAdcIQ = 6,
+    // Input DAC I and Q values
+    InputDacIQ = 7,
+}
+
+/// LNA / mixer gain mode (AGCCTRL.LNAMIX_GAINMODE_O when overriding the AGC,
+/// AGCCTRL.LNAMIX_GAINMODE when read back)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LnaGainMode {
+    // Gain mode is set by the AGC algorithm
+    #[default]
+    Automatic = 0,
+    Low = 1,
+    Medium = 2,
+    High = 3,
+}
+
+impl From<u8> for LnaGainMode {
+    fn from(value: u8) -> Self {
+        match value & 0b11 {
+            1 => Self::Low,
+            2 => Self::Medium,
+            3 => Self::High,
+            _ => Self::Automatic,
+        }
+    }
+}
+
+/// Settings that are frequently changed at run time, applied together by
+/// Radio::apply_radio_config without re-applying the full Configuration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RadioConfig {
+    // Channel the radio operates on
+    pub channel: Channel,
+    // Transmit output power
+    pub output_power: OutputPower,
+    // Clear Channel Assessment threshold (RSSI.CCA_THR)
+    pub cca_threshold: i8,
+    // Clear Channel Assessment mode (1-3, see MDMCTRL0.CCA_MODE)
+    pub cca_mode: u8,
+}
+
+impl Default for RadioConfig {
+    fn default() -> Self {
+        Self {
+            channel: Channel::default(),
+            output_power: OutputPower::default(),
+            cca_threshold: -32,
+            cca_mode: 3,
+        }
+    }
+}
+
+/// Check that the fields of a configuration are consistent with each other.
+/// 
+/// Checks:
+/// - auto_acknowledge requires enable_crc (acknowledgements are only sent
+///   for frames with a valid CRC)
+/// - cca_mode must not be 0 (reserved)
+/// - the security cleartext lengths must fit in SECCTRL1 (0-127)
+/// - in-line security requires non-zero TX and RX keys
+/// - correlation_threshold must fit in MDMCTRL1.CORR_THR (0-31)
+/// - fifop_threshold must fit in IOCFG0.FIFOP_THR (0-127)
+pub fn validate_configuration(config: &Configuration) -> Result<(), &'static str> {
+    if config.auto_acknowledge && !config.enable_crc {
+        return Err("Invalid Configuration. auto_acknowledge requires enable_crc");
+    }
+
+    if config.cca_mode == 0 {
+        return Err("Invalid Configuration. 1<=cca_mode<=3");
+    }
+
+    if config.security.tx_cleartext_bytes > 127 || config.security.rx_cleartext_bytes > 127 {
+        return Err("Invalid Configuration. 0<=tx_cleartext_bytes,rx_cleartext_bytes<=127");
+    }
+
+    if config.security.security_mode != SecurityMode::Disabled
+        && (config.security.tx_key == EncryptionKey::default() || config.security.rx_key == EncryptionKey::default()) {
+        return Err("Invalid Configuration. security_mode requires non-zero keys");
+    }
+
+    if config.correlation_threshold > 31 {
+        return Err("Invalid Configuration. 0<=correlation_threshold<=31");
+    }
+
+    if config.fifop_threshold > 127 {
+        return Err("Invalid Configuration. 0<=fifop_threshold<=127");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_power_from_pa_level() {
+        assert_eq!(OutputPower::from_pa_level(31), OutputPower::Dbm0);
+        assert_eq!(OutputPower::from_pa_level(28), OutputPower::DbmMinus1);
+        assert_eq!(OutputPower::from_pa_level(11), OutputPower::DbmMinus10);
+        assert_eq!(OutputPower::from_pa_level(0), OutputPower::DbmMinus25);
+        assert_eq!(OutputPower::DbmMinus7.pa_level(), 15);
+    }
+
+    #[test]
+    fn test_output_power_dbm() {
+        assert_eq!(OutputPower::Dbm0.dbm(), 0);
+        assert_eq!(OutputPower::DbmMinus10.dbm(), -10);
+        assert_eq!(OutputPower::DbmMinus25.dbm(), -25);
+    }
+
+    #[test]
+    fn test_validate_default_configuration() {
+        let config = ConfigurationBuilder::default().build().unwrap();
+
+        assert_eq!(validate_configuration(&config), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_auto_acknowledge_without_crc() {
+        let config = ConfigurationBuilder::default()
+            .auto_acknowledge(true)
+            .enable_crc(false)
+            .build()
+            .unwrap();
+
+        assert!(validate_configuration(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_cca_mode() {
+        let config = ConfigurationBuilder::default().cca_mode(0).build().unwrap();
+
+        assert!(validate_configuration(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_security_keys() {
+        let security = SecureConfiguration {
+            security_mode: SecurityMode::Ccm,
+            ..Default::default()
+        };
+        let config = ConfigurationBuilder::default().security(security).build().unwrap();
+        assert!(validate_configuration(&config).is_err());
+
+        let security = SecureConfiguration {
+            tx_key: EncryptionKey([0x01; 16]),
+            rx_key: EncryptionKey([0x02; 16]),
+            ..security
+        };
+        let config = ConfigurationBuilder::default().security(security).build().unwrap();
+        assert!(validate_configuration(&config).is_ok());
+    }
+
+    #[test]
+    fn test_validate_fifop_threshold() {
+        let config = ConfigurationBuilder::default().fifop_threshold(127).build().unwrap();
+        assert!(validate_configuration(&config).is_ok());
+
+        let config = ConfigurationBuilder::default().fifop_threshold(128).build().unwrap();
+        assert!(validate_configuration(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_correlation_threshold() {
+        let config = ConfigurationBuilder::default().correlation_threshold(31).build().unwrap();
+        assert!(validate_configuration(&config).is_ok());
+
+        let config = ConfigurationBuilder::default().correlation_threshold(32).build().unwrap();
+        assert!(validate_configuration(&config).is_err());
+    }
+}
@@ -0,0 +1,45 @@
+//!
+//! Snapshot of the CC2420 configuration registers for debugging
+//! 
+
+use crate::register::main_control::MainControlRegister;
+use crate::register::modem_control::{ModemControlRegister0, ModemControlRegister1};
+use crate::register::rssi::RSSIRegister;
+use crate::register::transmit_control::TransmitControlRegister;
+use crate::register::receive_control::{ReceiveControlRegister0, ReceiveControlRegister1};
+use crate::register::frequency_synthesis::FrequencySynthesizerRegister;
+use crate::register::security_control::{SecurityControlRegister0, SecurityControlRegister1};
+use crate::register::battery_monitor::BatteryMonitorRegister;
+use crate::register::io_configuration::{IOConfigurationRegister, IOConfigurationRegister1};
+
+/// Contents of every readable configuration register, as read by
+/// Radio::dump_registers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RegisterSnapshot {
+    // MAIN (0x10)
+    pub main_control: MainControlRegister,
+    // MDMCTRL0 (0x11)
+    pub modem_control_0: ModemControlRegister0,
+    // MDMCTRL1 (0x12)
+    pub modem_control_1: ModemControlRegister1,
+    // RSSI (0x13)
+    pub rssi: RSSIRegister,
+    // TXCTRL (0x15)
+    pub transmit_control: TransmitControlRegister,
+    // RXCTRL0 (0x16)
+    pub receive_control_0: ReceiveControlRegister0,
+    // RXCTRL1 (0x17)
+    pub receive_control_1: ReceiveControlRegister1,
+    // FSCTRL (0x18)
+    pub frequency_synthesizer: FrequencySynthesizerRegister,
+    // SECCTRL0 (0x19)
+    pub security_control_0: SecurityControlRegister0,
+    // SECCTRL1 (0x1A)
+    pub security_control_1: SecurityControlRegister1,
+    // BATTMON (0x1B)
+    pub battery_monitor: BatteryMonitorRegister,
+    // IOCFG0 (0x1C)
+    pub io_configuration: IOConfigurationRegister,
+    // IOCFG1 (0x1D)
+    pub io_configuration_1: IOConfigurationRegister1,
+}
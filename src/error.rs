@@ -2,13 +2,235 @@
 //! Error when operating the CC2420 Module
 //! 
 
-use alloc::string::String;
+use core::fmt;
+
+use derive_builder::UninitializedFieldError;
+
+use crate::frame::FrameError;
+use crate::state::RadioState;
 
 /// Error that occurs during the operation of the CC2420 Module.
+#[derive(Debug)]
 pub enum RadioError<SPIE, GPIOE> {
     InvalidBufferLenth{expected: usize, found: usize},
-    InvalidConfiguration(String),
+    // A received frame does not fit in the caller's buffer
+    BufferTooSmall{needed: usize, capacity: usize},
+    InvalidConfiguration(&'static str),
+    InvalidChannel(u8),
     FailedConfiguration(&'static str),
+    SecurityError(&'static str),
+    // A bounded wait (oscillator, PLL, calibration, ...) expired
+    Timeout,
+    // The channel was busy for every CSMA-CA attempt
+    ChannelBusy,
+    FifoOverflow,
+    FifoUnderflow,
+    // The device on the bus is not a CC2420 (its part number or manufacturer
+    // ID does not match)
+    WrongDevice{found_part: u16, found_manufacturer: u16},
+    InvalidFrame(FrameError),
+    // The operation is not valid in the current state of the radio
+    InvalidState{current: RadioState, required: RadioState},
     GpioError(GPIOE),
     SpiError(SPIE),
-}
\ No newline at end of file
+}
+
+impl<SPIE, GPIOE> RadioError<SPIE, GPIOE> {
+    /// Whether retrying the failed operation might succeed.  Transient
+    /// failures (FIFO overflow / underflow, timeouts, a busy channel and
+    /// configuration readback mismatches) are recoverable, while invalid input and hardware
+    /// (SPI / GPIO) failures are not.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            Self::FifoOverflow |
+            Self::FifoUnderflow |
+            Self::Timeout |
+            Self::ChannelBusy |
+            Self::FailedConfiguration(_) => true,
+            Self::InvalidBufferLenth { .. } |
+            Self::BufferTooSmall { .. } |
+            Self::InvalidConfiguration(_) |
+            Self::InvalidChannel(_) |
+            Self::SecurityError(_) |
+            Self::WrongDevice { .. } |
+            Self::InvalidFrame(_) |
+            Self::InvalidState { .. } |
+            Self::GpioError(_) |
+            Self::SpiError(_) => false,
+        }
+    }
+}
+
+impl<SPIE: fmt::Debug, GPIOE: fmt::Debug> fmt::Display for RadioError<SPIE, GPIOE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidBufferLenth { expected, found } => {
+                write!(f, "invalid buffer length: expected {}, found {}", expected, found)
+            },
+            Self::BufferTooSmall { needed, capacity } => {
+                write!(f, "buffer too small: needed {}, capacity {}", needed, capacity)
+            },
+            Self::InvalidConfiguration(message) => write!(f, "invalid configuration: {}", message),
+            Self::InvalidChannel(channel) => write!(f, "invalid channel: {} (expected 11-26)", channel),
+            Self::FailedConfiguration(message) => write!(f, "failed configuration: {}", message),
+            Self::SecurityError(message) => write!(f, "security error: {}", message),
+            Self::Timeout => write!(f, "timed out"),
+            Self::ChannelBusy => write!(f, "channel busy"),
+            Self::FifoOverflow => write!(f, "rx fifo overflow"),
+            Self::FifoUnderflow => write!(f, "tx fifo underflow"),
+            Self::WrongDevice { found_part, found_manufacturer } => {
+                write!(
+                    f,
+                    "wrong device: expected part number {:#05x} and manufacturer id {:#05x}, found {:#05x} and {:#05x}",
+                    crate::CC2420_PART_NUMBER, crate::CC2420_MANUFACTURER_ID, found_part, found_manufacturer,
+                )
+            },
+            Self::InvalidFrame(error) => write!(f, "invalid frame: {:?}", error),
+            Self::InvalidState { current, required } => {
+                write!(f, "invalid state: radio is {:?}, operation requires {:?}", current, required)
+            },
+            Self::GpioError(error) => write!(f, "gpio error: {:?}", error),
+            Self::SpiError(error) => write!(f, "spi error: {:?}", error),
+        }
+    }
+}
+
+/// The inner SPI and GPIO errors are returned from `source()`, so they must be errors themselves.
+impl<SPIE, GPIOE> core::error::Error for RadioError<SPIE, GPIOE>
+where
+    SPIE: core::error::Error + 'static,
+    GPIOE: core::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::GpioError(error) => Some(error),
+            Self::SpiError(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Error returned when building a register with an invalid field value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuildError(&'static str);
+
+impl BuildError {
+    /// The message describing why the build failed
+    pub fn message(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl From<&'static str> for BuildError {
+    fn from(value: &'static str) -> Self {
+        Self(value)
+    }
+}
+
+impl From<UninitializedFieldError> for BuildError {
+    fn from(value: UninitializedFieldError) -> Self {
+        // Every register field has a default so this is only reachable if a
+        // default is removed, in which case the field name is the best message
+        Self(value.field_name())
+    }
+}
+
+impl core::error::Error for BuildError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+    use core::error::Error;
+
+    #[derive(Debug)]
+    struct BusError;
+
+    impl fmt::Display for BusError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("bus error")
+        }
+    }
+
+    impl Error for BusError {}
+
+    #[test]
+    fn test_radio_error_display() {
+        let error: RadioError<BusError, BusError> = RadioError::InvalidBufferLenth { expected: 3, found: 2 };
+        assert_eq!(error.to_string(), "invalid buffer length: expected 3, found 2");
+
+        let error: RadioError<BusError, BusError> = RadioError::BufferTooSmall { needed: 20, capacity: 16 };
+        assert_eq!(error.to_string(), "buffer too small: needed 20, capacity 16");
+
+        let error: RadioError<BusError, BusError> = RadioError::SpiError(BusError);
+        assert_eq!(error.to_string(), "spi error: BusError");
+
+        let error: RadioError<BusError, BusError> = RadioError::InvalidState { current: RadioState::Receiving, required: RadioState::Idle };
+        assert_eq!(error.to_string(), "invalid state: radio is Receiving, operation requires Idle");
+
+        let error: RadioError<BusError, BusError> = RadioError::WrongDevice { found_part: 0x002, found_manufacturer: 0x049 };
+        assert_eq!(error.to_string(), "wrong device: expected part number 0x002 and manufacturer id 0x33d, found 0x002 and 0x049");
+    }
+
+    #[test]
+    fn test_radio_error_source() {
+        let error: RadioError<BusError, BusError> = RadioError::SpiError(BusError);
+        assert_eq!(error.source().unwrap().to_string(), "bus error");
+
+        let error: RadioError<BusError, BusError> = RadioError::GpioError(BusError);
+        assert_eq!(error.source().unwrap().to_string(), "bus error");
+
+        let error: RadioError<BusError, BusError> = RadioError::FailedConfiguration("Configuration of Modem Failed");
+        assert!(error.source().is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_radio_error_into_std_error() {
+        fn read() -> Result<(), RadioError<BusError, BusError>> {
+            Err(RadioError::SpiError(BusError))
+        }
+
+        fn run() -> Result<(), std::boxed::Box<dyn std::error::Error>> {
+            read()?;
+            Ok(())
+        }
+
+        let error = run().unwrap_err();
+        assert_eq!(error.to_string(), "spi error: BusError");
+        assert_eq!(error.source().unwrap().to_string(), "bus error");
+    }
+
+    #[test]
+    fn test_radio_error_is_recoverable() {
+        let recoverable: [RadioError<BusError, BusError>; 5] = [
+            RadioError::FifoOverflow,
+            RadioError::FifoUnderflow,
+            RadioError::Timeout,
+            RadioError::ChannelBusy,
+            RadioError::FailedConfiguration("Configuration of Modem Failed"),
+        ];
+        assert!(recoverable.iter().all(|error| error.is_recoverable()));
+
+        let unrecoverable: [RadioError<BusError, BusError>; 10] = [
+            RadioError::InvalidBufferLenth { expected: 16, found: 8 },
+            RadioError::BufferTooSmall { needed: 20, capacity: 16 },
+            RadioError::InvalidConfiguration("Invalid SEC_M. Expected 1<=SEC_M<=7"),
+            RadioError::InvalidChannel(27),
+            RadioError::SecurityError("Frame counter exhausted"),
+            RadioError::WrongDevice { found_part: 0x000, found_manufacturer: 0x33D },
+            RadioError::InvalidFrame(FrameError::FrameTooLong),
+            RadioError::InvalidState { current: RadioState::Receiving, required: RadioState::Idle },
+            RadioError::GpioError(BusError),
+            RadioError::SpiError(BusError),
+        ];
+        assert!(unrecoverable.iter().all(|error| !error.is_recoverable()));
+    }
+}
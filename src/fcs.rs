@@ -0,0 +1,45 @@
+//!
+//! Software computation of the IEEE 802.15.4 Frame Check Sequence
+//! 
+
+/// CRC-16/CCITT polynomial (x^16 + x^12 + x^5 + 1), bit reversed as the bytes
+/// are processed LSB first
+const FCS_POLYNOMIAL: u16 = 0x8408;
+
+/// Compute the Frame Check Sequence (ITU-T CRC-16, polynomial 0x1021 with an
+/// initial value of 0) over the MAC header and payload of a frame, as the
+/// CC2420 does in hardware when AUTOCRC is enabled.
+/// 
+/// Note: The FCS is sent least significant byte first.
+pub fn compute_fcs(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for byte in data {
+        crc ^= *byte as u16;
+        for _ in 0..8 {
+            if crc & 1 != 0 {
+                crc = (crc >> 1) ^ FCS_POLYNOMIAL;
+            } else {
+                crc >>= 1;
+            }
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_fcs_acknowledgement() {
+        // Example acknowledgement frame from IEEE 802.15.4-2006 §7.2.1.9
+        // (r0..r15 = 0010 0111 1001 1110)
+        assert_eq!(compute_fcs(&[0x02, 0x00, 0x6A]), 0x79E4);
+    }
+
+    #[test]
+    fn test_compute_fcs_check_value() {
+        assert_eq!(compute_fcs(b"123456789"), 0x2189);
+        assert_eq!(compute_fcs(&[]), 0x0000);
+    }
+}
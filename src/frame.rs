@@ -0,0 +1,1081 @@
+//!
+//! IEEE 802.15.4 MAC Frame Construction and Parsing
+//! 
+//! Frames are built in the format expected by the CC2420's TX FIFO: a
+//! length byte (counting the 2 FCS bytes appended by the radio when
+//! AUTOCRC is enabled) followed by the MAC header and payload.
+//! 
+
+use core::ops::Deref;
+
+use crate::address::{Address, IEEEAddress, PanId, ShortAddress};
+use crate::bounded_vec::{BoundedVec, CapacityError};
+use crate::channel::Channel;
+use crate::fcs::compute_fcs;
+use crate::mac::{GtsSlotList, PendingAddressList, SuperframeSpec};
+use crate::metadata::parse_lqi_crc_byte;
+
+/// Maximum size of a PHY payload (MAC header, MAC payload and FCS)
+pub const MAX_FRAME_LENGTH: usize = 127;
+
+/// Number of bytes in the Frame Check Sequence appended by the radio
+pub const FCS_LENGTH: usize = 2;
+
+/// Buffer holding a frame ready to be written into the TX FIFO
+pub type FrameBuffer = BoundedVec<u8, 128>;
+
+/// Error when building a frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameError {
+    // The encoded frame is longer than the maximum frame length
+    FrameTooLong,
+    // The received frame is too short to hold the two bytes appended by the
+    // radio
+    FrameTooShort,
+    // An address was given without the PAN identifier it belongs to
+    MissingPanId,
+}
+
+impl From<CapacityError> for FrameError {
+    fn from(_: CapacityError) -> Self {
+        Self::FrameTooLong
+    }
+}
+
+/// Frame Type (Frame Control Field bits `[2:0]`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameType {
+    Beacon = 0,
+    Data = 1,
+    Acknowledgement = 2,
+    MacCommand = 3,
+    // Frame types 4-7 are reserved
+    Reserved = 4,
+}
+
+impl From<u8> for FrameType {
+    fn from(value: u8) -> Self {
+        match value & 0b111 {
+            0 => Self::Beacon,
+            1 => Self::Data,
+            2 => Self::Acknowledgement,
+            3 => Self::MacCommand,
+            _ => Self::Reserved,
+        }
+    }
+}
+
+/// MAC Command Frame Identifiers (802.15.4 §7.3)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MacCommand {
+    AssociationRequest = 0x01,
+    AssociationResponse = 0x02,
+    DisassociationNotification = 0x03,
+    DataRequest = 0x04,
+    PanIdConflictNotification = 0x05,
+    OrphanNotification = 0x06,
+    BeaconRequest = 0x07,
+    CoordinatorRealignment = 0x08,
+    GtsRequest = 0x09,
+}
+
+// Addressing mode values of the Frame Control Field
+const ADDRESS_MODE_NONE: u16 = 0;
+const ADDRESS_MODE_SHORT: u16 = 2;
+const ADDRESS_MODE_EXTENDED: u16 = 3;
+
+/// Encode a frame (length byte, MAC header and payload) into a buffer for
+/// the TX FIFO.  The source PAN identifier is elided (PAN ID compression)
+/// when both addresses are present and in the same PAN.
+pub(crate) fn encode_frame(
+    frame_type: FrameType,
+    ack_request: bool,
+    sequence_number: u8,
+    destination: Option<(PanId, Address)>,
+    source: Option<(PanId, Address)>,
+    payload: &[u8],
+) -> Result<FrameBuffer, CapacityError> {
+    let pan_id_compression = match (destination, source) {
+        (Some((dst_pan, _)), Some((src_pan, _))) => dst_pan == src_pan,
+        _ => false,
+    };
+
+    let mut frame_control = frame_type as u16;
+    if ack_request {
+        frame_control |= 1 << 5;
+    }
+    if pan_id_compression {
+        frame_control |= 1 << 6;
+    }
+    frame_control |= address_mode(destination.map(|(_, address)| address)) << 10;
+    frame_control |= address_mode(source.map(|(_, address)| address)) << 14;
+
+    let mut buffer = FrameBuffer::new();
+    // Length byte (filled in once the frame is complete)
+    buffer.extend_from_slice(&[0])?;
+    buffer.extend_from_slice(&frame_control.to_le_bytes())?;
+    buffer.extend_from_slice(&[sequence_number])?;
+    if let Some((pan, address)) = destination {
+        buffer.extend_from_slice(&pan.0.to_le_bytes())?;
+        encode_address(&mut buffer, address)?;
+    }
+    if let Some((pan, address)) = source {
+        if !pan_id_compression {
+            buffer.extend_from_slice(&pan.0.to_le_bytes())?;
+        }
+        encode_address(&mut buffer, address)?;
+    }
+    buffer.extend_from_slice(payload)?;
+
+    let length = buffer.len() - 1 + FCS_LENGTH;
+    if length > MAX_FRAME_LENGTH {
+        return Err(CapacityError);
+    }
+    buffer[0] = length as u8;
+    Ok(buffer)
+}
+
+fn address_mode(address: Option<Address>) -> u16 {
+    match address {
+        None => ADDRESS_MODE_NONE,
+        Some(Address::Short(_)) => ADDRESS_MODE_SHORT,
+        Some(Address::Extended(_)) => ADDRESS_MODE_EXTENDED,
+    }
+}
+
+fn encode_address(buffer: &mut FrameBuffer, address: Address) -> Result<(), CapacityError> {
+    match address {
+        Address::Short(address) => buffer.extend_from_slice(&address.0.to_le_bytes()),
+        Address::Extended(address) => buffer.extend_from_slice(&address.0),
+    }
+}
+
+/// Acknowledgement frame (802.15.4 §7.2.2.3) for the frame with a given
+/// sequence number
+pub fn ack_frame(sequence_number: u8, frame_pending: bool) -> FrameBuffer {
+    let mut frame_control = FrameType::Acknowledgement as u16;
+    if frame_pending {
+        frame_control |= 1 << 4;
+    }
+
+    let mut buffer = FrameBuffer::new();
+    let [low, high] = frame_control.to_le_bytes();
+    buffer.extend_from_slice(&[(3 + FCS_LENGTH) as u8, low, high, sequence_number])
+        .expect("Acknowledgement Frame Exceeds Maximum Frame Length");
+    buffer
+}
+
+/// Beacon request command frame (802.15.4 §7.3.7), broadcast to all PANs
+pub fn beacon_request_frame(sequence_number: u8) -> FrameBuffer {
+    encode_frame(
+        FrameType::MacCommand,
+        false,
+        sequence_number,
+        Some((PanId::BROADCAST, ShortAddress::BROADCAST.into())),
+        None,
+        &[MacCommand::BeaconRequest as u8],
+    ).expect("Beacon Request Frame Exceeds Maximum Frame Length")
+}
+
+/// Association request command frame (802.15.4 §7.3.1) sent by a device to
+/// the coordinator it wishes to join
+pub fn association_request_frame(sequence_number: u8, coordinator_pan: PanId, coordinator: Address, source: IEEEAddress, capability_information: u8) -> FrameBuffer {
+    encode_frame(
+        FrameType::MacCommand,
+        true,
+        sequence_number,
+        Some((coordinator_pan, coordinator)),
+        Some((PanId::BROADCAST, source.into())),
+        &[MacCommand::AssociationRequest as u8, capability_information],
+    ).expect("Association Request Frame Exceeds Maximum Frame Length")
+}
+
+/// Data request command frame (802.15.4 §7.3.4) used to poll the coordinator
+/// for pending data
+pub fn data_request_frame(sequence_number: u8, coordinator_pan: PanId, coordinator: Address, source: IEEEAddress) -> FrameBuffer {
+    encode_frame(
+        FrameType::MacCommand,
+        true,
+        sequence_number,
+        Some((coordinator_pan, coordinator)),
+        Some((coordinator_pan, source.into())),
+        &[MacCommand::DataRequest as u8],
+    ).expect("Data Request Frame Exceeds Maximum Frame Length")
+}
+
+/// Orphan notification command frame (802.15.4 §7.3.6) broadcast by a
+/// device that has lost synchronisation with its coordinator
+pub fn orphan_notification_frame(sequence_number: u8, source: IEEEAddress) -> FrameBuffer {
+    encode_frame(
+        FrameType::MacCommand,
+        false,
+        sequence_number,
+        Some((PanId::BROADCAST, ShortAddress::BROADCAST.into())),
+        Some((PanId::BROADCAST, source.into())),
+        &[MacCommand::OrphanNotification as u8],
+    ).expect("Orphan Notification Frame Exceeds Maximum Frame Length")
+}
+
+/// Coordinator realignment command frame (802.15.4 §7.3.8) sent by a
+/// coordinator, either broadcast to its PAN when its parameters change or
+/// directly to an orphaned device (given its IEEE address and short address)
+/// in response to an orphan notification.
+pub fn coordinator_realignment_frame(
+    sequence_number: u8,
+    channel: Channel,
+    pan_id: PanId,
+    coordinator: IEEEAddress,
+    coordinator_short: ShortAddress,
+    orphan: Option<(IEEEAddress, ShortAddress)>,
+) -> FrameBuffer {
+    let (destination, ack_request, short_address) = match orphan {
+        Some((orphan, short_address)) => (Address::Extended(orphan), true, short_address),
+        None => (ShortAddress::BROADCAST.into(), false, ShortAddress::BROADCAST),
+    };
+
+    let mut payload = [0u8; 8];
+    payload[0] = MacCommand::CoordinatorRealignment as u8;
+    payload[1..3].copy_from_slice(&pan_id.0.to_le_bytes());
+    payload[3..5].copy_from_slice(&coordinator_short.0.to_le_bytes());
+    payload[5] = channel.number();
+    payload[6..8].copy_from_slice(&short_address.0.to_le_bytes());
+
+    encode_frame(
+        FrameType::MacCommand,
+        ack_request,
+        sequence_number,
+        Some((PanId::BROADCAST, destination)),
+        Some((pan_id, coordinator.into())),
+        &payload,
+    ).expect("Coordinator Realignment Frame Exceeds Maximum Frame Length")
+}
+
+/// Contents of a beacon frame (802.15.4 §7.2.2.1) sent by a coordinator
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct BeaconFrameBuilder<'a> {
+    // Superframe specification of the PAN
+    pub superframe_spec: SuperframeSpec,
+    // GTSs allocated by the coordinator
+    pub gts_slots: GtsSlotList,
+    // Devices the coordinator has pending data for
+    pub pending_addresses: PendingAddressList,
+    // Higher layer payload appended to the beacon
+    pub beacon_payload: &'a [u8],
+}
+
+impl BeaconFrameBuilder<'_> {
+    /// Serialise the beacon frame for the TX FIFO
+    pub fn build(self, seq_num: u8, src_pan: PanId, src_addr: Address) -> Result<FrameBuffer, FrameError> {
+        let gts = self.gts_slots.encode_for_beacon();
+        let pending = self.pending_addresses.encode_for_beacon();
+
+        let mut payload = BoundedVec::<u8, MAX_FRAME_LENGTH>::new();
+        payload.extend_from_slice(&u16::from(self.superframe_spec).to_le_bytes())?;
+        payload.extend_from_slice(&gts)?;
+        payload.extend_from_slice(&pending)?;
+        payload.extend_from_slice(self.beacon_payload)?;
+
+        Ok(encode_frame(FrameType::Beacon, false, seq_num, None, Some((src_pan, src_addr)), &payload)?)
+    }
+}
+
+/// Create a builder for a beacon frame with an empty superframe
+/// specification, GTS list, pending address list and payload
+pub fn beacon_frame_builder<'a>() -> BeaconFrameBuilder<'a> {
+    BeaconFrameBuilder::default()
+}
+
+/// Parse the bytes read from the RX FIFO (starting with the length byte)
+/// into a frame, checking that its MAC header is well formed
+impl TryFrom<&[u8]> for RxFrame {
+    type Error = FrameParseError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let (length, bytes) = value.split_first().ok_or(FrameParseError::Empty)?;
+        let bytes = bytes.get(..((length & 0x7F) as usize)).ok_or(FrameParseError::Truncated)?;
+        // The length is masked to 7 bits so the frame can not be too long
+        let frame = RxFrame::from_bytes(bytes).map_err(|_| FrameParseError::FrameTooShort)?;
+        if MacHeader::parse(frame.data()).is_none() {
+            return Err(FrameParseError::MalformedHeader);
+        }
+        Ok(frame)
+    }
+}
+
+/// A frame (length byte, MAC header and payload) ready to be passed to
+/// send_frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TxFrame(FrameBuffer);
+
+impl AsRef<[u8]> for TxFrame {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
+impl Deref for TxFrame {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_slice()
+    }
+}
+
+/// Builder for an IEEE 802.15.4 MAC frame (a data frame with no addressing
+/// fields by default)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TxFrameBuilder<'a> {
+    frame_type: FrameType,
+    ack_request: bool,
+    sequence_number: Option<u8>,
+    dest_pan: Option<PanId>,
+    dest_address: Option<Address>,
+    src_pan: Option<PanId>,
+    src_address: Option<Address>,
+    payload: &'a [u8],
+}
+
+impl Default for TxFrameBuilder<'_> {
+    fn default() -> Self {
+        Self {
+            frame_type: FrameType::Data,
+            ack_request: false,
+            sequence_number: None,
+            dest_pan: None,
+            dest_address: None,
+            src_pan: None,
+            src_address: None,
+            payload: &[],
+        }
+    }
+}
+
+impl<'a> TxFrameBuilder<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The type of the frame
+    pub fn frame_type(mut self, frame_type: FrameType) -> Self {
+        self.frame_type = frame_type;
+        self
+    }
+
+    /// Request an acknowledgement from the recipient
+    pub fn ack_request(mut self, ack_request: bool) -> Self {
+        self.ack_request = ack_request;
+        self
+    }
+
+    /// The sequence number of the frame (defaults to 0, or to the radio's
+    /// next sequence number when built with Radio::build_frame)
+    pub fn sequence_number(mut self, sequence_number: u8) -> Self {
+        self.sequence_number = Some(sequence_number);
+        self
+    }
+
+    /// Whether the sequence number has been set explicitly
+    pub(crate) fn has_sequence_number(&self) -> bool {
+        self.sequence_number.is_some()
+    }
+
+    /// The PAN identifier of the recipient
+    pub fn dest_pan(mut self, pan_id: u16) -> Self {
+        self.dest_pan = Some(PanId(pan_id));
+        self
+    }
+
+    /// The short address of the recipient
+    pub fn dest_short_address(mut self, address: u16) -> Self {
+        self.dest_address = Some(Address::Short(ShortAddress(address)));
+        self
+    }
+
+    /// The IEEE address of the recipient
+    pub fn dest_extended_address(mut self, address: [u8; 8]) -> Self {
+        self.dest_address = Some(Address::Extended(IEEEAddress(address)));
+        self
+    }
+
+    /// The PAN identifier of the sender (defaults to the destination PAN
+    /// identifier, in which case it is elided from the frame)
+    pub fn src_pan(mut self, pan_id: u16) -> Self {
+        self.src_pan = Some(PanId(pan_id));
+        self
+    }
+
+    /// The short address of the sender
+    pub fn src_short_address(mut self, address: u16) -> Self {
+        self.src_address = Some(Address::Short(ShortAddress(address)));
+        self
+    }
+
+    /// The IEEE address of the sender
+    pub fn src_extended_address(mut self, address: [u8; 8]) -> Self {
+        self.src_address = Some(Address::Extended(IEEEAddress(address)));
+        self
+    }
+
+    /// The MAC payload of the frame
+    pub fn payload(mut self, payload: &'a [u8]) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    /// Encode the frame for the TX FIFO
+    pub fn build(self) -> Result<TxFrame, FrameError> {
+        let destination = match self.dest_address {
+            Some(address) => Some((self.dest_pan.ok_or(FrameError::MissingPanId)?, address)),
+            None => None,
+        };
+        let source = match self.src_address {
+            Some(address) => Some((self.src_pan.or(self.dest_pan).ok_or(FrameError::MissingPanId)?, address)),
+            None => None,
+        };
+
+        Ok(TxFrame(encode_frame(
+            self.frame_type,
+            self.ack_request,
+            self.sequence_number.unwrap_or(0),
+            destination,
+            source,
+            self.payload,
+        )?))
+    }
+}
+
+/// Addressing mode of the Frame Control Field (bits `[11:10]` and `[15:14]`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressMode {
+    // PAN identifier and address fields are not present
+    None = 0,
+    Reserved = 1,
+    // 16-bit short address
+    Short = 2,
+    // 64-bit extended (IEEE) address
+    Extended = 3,
+}
+
+impl From<u16> for AddressMode {
+    fn from(value: u16) -> Self {
+        match value & 0b11 {
+            ADDRESS_MODE_NONE => Self::None,
+            ADDRESS_MODE_SHORT => Self::Short,
+            ADDRESS_MODE_EXTENDED => Self::Extended,
+            _ => Self::Reserved,
+        }
+    }
+}
+
+/// The Frame Control Field at the start of every frame (802.15.4 §7.2.1.1)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameControl {
+    // Type of the frame
+    pub frame_type: FrameType,
+    // Whether the frame is protected by the MAC sublayer security
+    pub security_enabled: bool,
+    // Whether the sender has more data pending for the recipient
+    pub frame_pending: bool,
+    // Whether the recipient should acknowledge the frame
+    pub ack_request: bool,
+    // Whether the source PAN identifier is omitted (same as the destination)
+    pub pan_id_compression: bool,
+    // Addressing mode of the destination address
+    pub dst_addr_mode: AddressMode,
+    // Version of the frame format (0: 2003, 1: 2006)
+    pub frame_version: u8,
+    // Addressing mode of the source address
+    pub src_addr_mode: AddressMode,
+}
+
+impl From<u16> for FrameControl {
+    fn from(value: u16) -> Self {
+        Self {
+            frame_type: FrameType::from(value as u8),
+            security_enabled: (value & 1 << 3) != 0,
+            frame_pending: (value & 1 << 4) != 0,
+            ack_request: (value & 1 << 5) != 0,
+            pan_id_compression: (value & 1 << 6) != 0,
+            dst_addr_mode: AddressMode::from(value >> 10),
+            frame_version: ((value >> 12) & 0b11) as u8,
+            src_addr_mode: AddressMode::from(value >> 14),
+        }
+    }
+}
+
+/// The MAC header of a received frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MacHeader {
+    // Frame Control Field
+    pub frame_control: FrameControl,
+    // Sequence number of the frame
+    pub sequence_number: u8,
+    // Destination PAN identifier
+    pub dst_pan: Option<PanId>,
+    // Destination address
+    pub dst_addr: Option<Address>,
+    // Source PAN identifier (the destination PAN identifier when PAN ID
+    // compression is used)
+    pub src_pan: Option<PanId>,
+    // Source address
+    pub src_addr: Option<Address>,
+}
+
+impl MacHeader {
+    /// Parse the MAC header of a frame (without the length byte), returning
+    /// the header and the remaining bytes of the frame, or None if the header
+    /// is malformed
+    pub fn parse(data: &[u8]) -> Option<(MacHeader, &[u8])> {
+        if data.len() < 3 {
+            return None;
+        }
+        let frame_control = FrameControl::from(u16::from_le_bytes([data[0], data[1]]));
+
+        let mut index = 3;
+        let mut dst_pan = None;
+        let mut dst_addr = None;
+        if frame_control.dst_addr_mode != AddressMode::None {
+            dst_pan = Some(PanId(read_u16(data, &mut index)?));
+            dst_addr = Some(read_address(data, &mut index, frame_control.dst_addr_mode)?);
+        }
+        let mut src_pan = None;
+        let mut src_addr = None;
+        if frame_control.src_addr_mode != AddressMode::None {
+            src_pan = if frame_control.pan_id_compression && dst_pan.is_some() {
+                dst_pan
+            } else {
+                Some(PanId(read_u16(data, &mut index)?))
+            };
+            src_addr = Some(read_address(data, &mut index, frame_control.src_addr_mode)?);
+        }
+
+        let header = Self {
+            frame_control,
+            sequence_number: data[2],
+            dst_pan,
+            dst_addr,
+            src_pan,
+            src_addr,
+        };
+        Some((header, &data[index..]))
+    }
+}
+
+/// Error when parsing the bytes read from the RX FIFO into a frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameParseError {
+    // There are no bytes to parse
+    Empty,
+    // The length byte is longer than the bytes given
+    Truncated,
+    // The frame is too short to hold the two bytes appended by the radio
+    FrameTooShort,
+    // The MAC header is malformed (or longer than the frame)
+    MalformedHeader,
+}
+
+/// A frame read from the RX FIFO
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RxFrame {
+    // The bytes of the frame following the length byte (the last two bytes
+    // are the RSSI and CRC / correlation bytes when AUTOCRC is enabled, or
+    // the FCS otherwise), see payload for the MAC payload
+    raw: [u8; 128],
+    // Number of bytes of raw received (at least FCS_LENGTH)
+    len: u8,
+    // Link quality indicator (correlation value)
+    pub lqi: u8,
+    // Whether the CRC of the frame was valid (only when AUTOCRC is enabled)
+    pub crc_valid: bool,
+}
+
+impl RxFrame {
+    /// Construct a received frame from the bytes following the length byte
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FrameError> {
+        if bytes.len() < FCS_LENGTH {
+            return Err(FrameError::FrameTooShort);
+        }
+        if bytes.len() > MAX_FRAME_LENGTH {
+            return Err(FrameError::FrameTooLong);
+        }
+
+        let mut raw = [0u8; 128];
+        raw[..bytes.len()].copy_from_slice(bytes);
+        let (lqi, crc_valid) = parse_lqi_crc_byte(bytes[bytes.len() - 1]);
+        Ok(Self {
+            raw,
+            len: bytes.len() as u8,
+            lqi,
+            crc_valid,
+        })
+    }
+
+    /// The bytes of the frame (including the two trailing bytes)
+    pub fn bytes(&self) -> &[u8] {
+        &self.raw[..(self.len as usize)]
+    }
+
+    /// The RSSI of the frame (offset as RSSI.RSSI_VAL) appended by the radio
+    /// in place of the first FCS byte (only when AUTOCRC is enabled, 0 if the
+    /// frame is too short to hold one)
+    pub fn rssi(&self) -> i8 {
+        (self.len as usize).checked_sub(FCS_LENGTH).map_or(0, |index| self.raw[index] as i8)
+    }
+
+    /// The frame control field (all zero if the frame is too short to hold
+    /// one)
+    pub fn frame_control(&self) -> FrameControl {
+        let data = self.data();
+        match data.get(..2) {
+            Some(bytes) => FrameControl::from(u16::from_le_bytes([bytes[0], bytes[1]])),
+            None => FrameControl::from(0),
+        }
+    }
+
+    /// The type of the frame
+    pub fn frame_type(&self) -> FrameType {
+        self.frame_control().frame_type
+    }
+
+    /// The sequence number of the frame (0 if the frame is too short to hold
+    /// one)
+    pub fn sequence_number(&self) -> u8 {
+        self.data().get(2).copied().unwrap_or(0)
+    }
+
+    /// The destination address of the frame, if present
+    pub fn dest_address(&self) -> Option<Address> {
+        MacHeader::parse(self.data()).and_then(|(header, _)| header.dst_addr)
+    }
+
+    /// The source address of the frame, if present
+    pub fn src_address(&self) -> Option<Address> {
+        MacHeader::parse(self.data()).and_then(|(header, _)| header.src_addr)
+    }
+
+    /// The MAC payload of the frame (empty if the MAC header is malformed)
+    pub fn payload(&self) -> &[u8] {
+        MacHeader::parse(self.data()).map_or(&[], |(_, payload)| payload)
+    }
+
+    /// Whether the sender requested an acknowledgement
+    pub fn ack_requested(&self) -> bool {
+        self.frame_control().ack_request
+    }
+
+    /// Whether the sender has more data pending for the recipient
+    pub fn frame_pending(&self) -> bool {
+        self.frame_control().frame_pending
+    }
+
+    /// Whether the frame is protected by the MAC sublayer security
+    pub fn security_enabled(&self) -> bool {
+        self.frame_control().security_enabled
+    }
+
+    // The MAC header and payload (without the two trailing bytes)
+    fn data(&self) -> &[u8] {
+        &self.bytes()[..(self.len as usize).saturating_sub(FCS_LENGTH)]
+    }
+
+    /// Whether the last two bytes of the frame are a valid FCS for the
+    /// preceding bytes (only meaningful when AUTOCRC is disabled, otherwise
+    /// use crc_valid)
+    pub fn check_fcs(&self) -> bool {
+        let (data, fcs) = self.bytes().split_at((self.len as usize).saturating_sub(FCS_LENGTH));
+        fcs.len() == FCS_LENGTH && compute_fcs(data) == u16::from_le_bytes([fcs[0], fcs[1]])
+    }
+}
+
+/// Fields of a received frame's MAC header along with its payload
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ParsedFrame<'a> {
+    pub frame_type: FrameType,
+    pub frame_pending: bool,
+    pub ack_request: bool,
+    pub sequence_number: u8,
+    pub dst_pan: Option<PanId>,
+    pub dst_addr: Option<Address>,
+    pub src_pan: Option<PanId>,
+    pub src_addr: Option<Address>,
+    pub payload: &'a [u8],
+}
+
+impl<'a> ParsedFrame<'a> {
+    /// Parse a frame (without the length byte or FCS), returning None if the
+    /// frame is malformed
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let (header, payload) = MacHeader::parse(data)?;
+        Some(Self {
+            frame_type: header.frame_control.frame_type,
+            frame_pending: header.frame_control.frame_pending,
+            ack_request: header.frame_control.ack_request,
+            sequence_number: header.sequence_number,
+            dst_pan: header.dst_pan,
+            dst_addr: header.dst_addr,
+            src_pan: header.src_pan,
+            src_addr: header.src_addr,
+            payload,
+        })
+    }
+}
+
+fn read_u16(data: &[u8], index: &mut usize) -> Option<u16> {
+    let bytes = data.get(*index..(*index + 2))?;
+    *index += 2;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_address(data: &[u8], index: &mut usize, mode: AddressMode) -> Option<Address> {
+    match mode {
+        AddressMode::Short => Some(Address::Short(ShortAddress(read_u16(data, index)?))),
+        AddressMode::Extended => {
+            let bytes = data.get(*index..(*index + 8))?;
+            *index += 8;
+            Some(Address::Extended(IEEEAddress(bytes.try_into().ok()?)))
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rx_frame_from_bytes() {
+        let frame = RxFrame::from_bytes(&[0x02, 0x00, 0x2A, 0xD0, 0xE5]).unwrap();
+
+        assert_eq!(frame.bytes().len(), 5);
+        assert_eq!(frame.bytes(), &[0x02, 0x00, 0x2A, 0xD0, 0xE5]);
+        assert_eq!(frame.lqi, 0x65);
+        assert!(frame.crc_valid);
+        assert_eq!(frame.rssi(), -48);
+    }
+
+    #[test]
+    fn test_rx_frame_check_fcs() {
+        let frame = RxFrame::from_bytes(&[0x02, 0x00, 0x6A, 0xE4, 0x79]).unwrap();
+        assert!(frame.check_fcs());
+
+        let frame = RxFrame::from_bytes(&[0x02, 0x00, 0x6B, 0xE4, 0x79]).unwrap();
+        assert!(!frame.check_fcs());
+
+        let frame = RxFrame::from_bytes(&[0x00, 0x00]).unwrap();
+        assert!(frame.check_fcs());
+        assert_eq!(frame.rssi(), 0);
+    }
+
+    #[test]
+    fn test_rx_frame_try_from() {
+        let bytes: &[u8] = &[
+            0x0C,
+            0x61, 0x88,
+            0x07,
+            0x34, 0x12,
+            0x01, 0x00,
+            0x02, 0x00,
+            0xAA,
+            0xD0, 0xE5,
+            // Following frame in the FIFO
+            0x05,
+        ];
+        let frame = RxFrame::try_from(bytes).unwrap();
+
+        assert_eq!(frame.bytes().len(), 12);
+        assert_eq!(frame.frame_type(), FrameType::Data);
+        assert_eq!(frame.sequence_number(), 7);
+        assert_eq!(frame.dest_address(), Some(Address::Short(ShortAddress(0x0001))));
+        assert_eq!(frame.src_address(), Some(Address::Short(ShortAddress(0x0002))));
+        assert_eq!(frame.payload(), &[0xAA]);
+        assert!(frame.ack_requested());
+        assert!(!frame.frame_pending());
+        assert!(!frame.security_enabled());
+        assert_eq!(frame.lqi, 0x65);
+    }
+
+    #[test]
+    fn test_rx_frame_try_from_errors() {
+        assert_eq!(RxFrame::try_from(&[][..]), Err(FrameParseError::Empty));
+        assert_eq!(RxFrame::try_from(&[0x05, 0x02, 0x00][..]), Err(FrameParseError::Truncated));
+        assert_eq!(RxFrame::try_from(&[0x01, 0x02][..]), Err(FrameParseError::FrameTooShort));
+        assert_eq!(RxFrame::try_from(&[0x03, 0x02, 0xD0, 0xE5][..]), Err(FrameParseError::MalformedHeader));
+        // Destination addressing mode set without the address
+        assert_eq!(
+            RxFrame::try_from(&[0x05, 0x01, 0x08, 0x00, 0xD0, 0xE5][..]),
+            Err(FrameParseError::MalformedHeader),
+        );
+    }
+
+    #[test]
+    fn test_rx_frame_from_bytes_invalid_length() {
+        assert_eq!(RxFrame::from_bytes(&[0x80]), Err(FrameError::FrameTooShort));
+        assert_eq!(RxFrame::from_bytes(&[0u8; 128]), Err(FrameError::FrameTooLong));
+    }
+
+    #[test]
+    fn test_frame_control_from_u16() {
+        let frame_control = FrameControl::from(0b1110_1000_0110_1001);
+
+        assert_eq!(frame_control.frame_type, FrameType::Data);
+        assert!(frame_control.security_enabled);
+        assert!(!frame_control.frame_pending);
+        assert!(frame_control.ack_request);
+        assert!(frame_control.pan_id_compression);
+        assert_eq!(frame_control.dst_addr_mode, AddressMode::Short);
+        assert_eq!(frame_control.frame_version, 2);
+        assert_eq!(frame_control.src_addr_mode, AddressMode::Extended);
+    }
+
+    #[test]
+    fn test_mac_header_parse() {
+        let frame = data_request_frame(
+            1,
+            PanId(0x1234),
+            ShortAddress(0x0000).into(),
+            IEEEAddress([1, 2, 3, 4, 5, 6, 7, 8]),
+        );
+
+        let (header, payload) = MacHeader::parse(&frame[1..]).unwrap();
+
+        assert_eq!(header.frame_control.frame_type, FrameType::MacCommand);
+        assert_eq!(header.sequence_number, 1);
+        assert_eq!(header.dst_pan, Some(PanId(0x1234)));
+        assert_eq!(header.dst_addr, Some(Address::Short(ShortAddress(0x0000))));
+        assert_eq!(header.src_pan, Some(PanId(0x1234)));
+        assert_eq!(header.src_addr, Some(Address::Extended(IEEEAddress([1, 2, 3, 4, 5, 6, 7, 8]))));
+        assert_eq!(payload, &[MacCommand::DataRequest as u8]);
+    }
+
+    #[test]
+    fn test_mac_header_parse_truncated() {
+        assert_eq!(MacHeader::parse(&[0x41, 0x88, 0x01, 0x34]), None);
+    }
+
+    #[test]
+    fn test_ack_frame() {
+        assert_eq!(ack_frame(0x56, false).as_slice(), &[0x05, 0x02, 0x00, 0x56]);
+        assert_eq!(ack_frame(0x56, true).as_slice(), &[0x05, 0x12, 0x00, 0x56]);
+    }
+
+    #[test]
+    fn test_beacon_request_frame() {
+        let frame = beacon_request_frame(0x42);
+
+        assert_eq!(
+            frame.as_slice(),
+            &[0x0A, 0x03, 0x08, 0x42, 0xFF, 0xFF, 0xFF, 0xFF, 0x07],
+        )
+    }
+
+    #[test]
+    fn test_data_request_frame() {
+        let frame = data_request_frame(
+            1,
+            PanId(0x1234),
+            ShortAddress(0x0000).into(),
+            IEEEAddress([1, 2, 3, 4, 5, 6, 7, 8]),
+        );
+
+        assert_eq!(
+            frame.as_slice(),
+            &[
+                0x12,
+                0x63, 0xC8,
+                0x01,
+                0x34, 0x12,
+                0x00, 0x00,
+                1, 2, 3, 4, 5, 6, 7, 8,
+                0x04,
+            ],
+        )
+    }
+
+    #[test]
+    fn test_tx_frame_builder() {
+        let frame = TxFrameBuilder::new()
+            .ack_request(true)
+            .sequence_number(7)
+            .dest_pan(0x1234)
+            .dest_short_address(0x0001)
+            .src_short_address(0x0002)
+            .payload(&[0xAA, 0xBB])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            frame.as_ref(),
+            &[
+                0x0D,
+                0x61, 0x88,
+                0x07,
+                0x34, 0x12,
+                0x01, 0x00,
+                0x02, 0x00,
+                0xAA, 0xBB,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_tx_frame_builder_extended_addresses() {
+        let frame = TxFrameBuilder::new()
+            .frame_type(FrameType::MacCommand)
+            .dest_pan(0xFFFF)
+            .dest_extended_address([1, 2, 3, 4, 5, 6, 7, 8])
+            .src_pan(0x1234)
+            .src_extended_address([8, 7, 6, 5, 4, 3, 2, 1])
+            .build()
+            .unwrap();
+
+        assert_eq!(frame.as_ref()[..4], [0x19, 0x03, 0xCC, 0x00]);
+        assert_eq!(frame.as_ref()[4..6], [0xFF, 0xFF]);
+        assert_eq!(frame.as_ref()[14..16], [0x34, 0x12]);
+    }
+
+    #[test]
+    fn test_tx_frame_builder_errors() {
+        assert_eq!(
+            TxFrameBuilder::new().dest_short_address(0x0001).build(),
+            Err(FrameError::MissingPanId),
+        );
+        assert_eq!(
+            TxFrameBuilder::new().src_short_address(0x0001).build(),
+            Err(FrameError::MissingPanId),
+        );
+        assert_eq!(
+            TxFrameBuilder::new().payload(&[0u8; 126]).build(),
+            Err(FrameError::FrameTooLong),
+        );
+    }
+
+    #[test]
+    fn test_orphan_notification_frame() {
+        let frame = orphan_notification_frame(3, IEEEAddress([1, 2, 3, 4, 5, 6, 7, 8]));
+
+        assert_eq!(
+            frame.as_slice(),
+            &[
+                0x12,
+                0x43, 0xC8,
+                0x03,
+                0xFF, 0xFF,
+                0xFF, 0xFF,
+                1, 2, 3, 4, 5, 6, 7, 8,
+                0x06,
+            ],
+        )
+    }
+
+    #[test]
+    fn test_coordinator_realignment_frame_to_orphan() {
+        let frame = coordinator_realignment_frame(
+            4,
+            Channel::try_from(15).unwrap(),
+            PanId(0x1234),
+            IEEEAddress([1, 2, 3, 4, 5, 6, 7, 8]),
+            ShortAddress(0x0000),
+            Some((IEEEAddress([9, 10, 11, 12, 13, 14, 15, 16]), ShortAddress(0x5678))),
+        );
+
+        assert_eq!(
+            frame.as_slice(),
+            &[
+                0x21,
+                0x23, 0xCC,
+                0x04,
+                0xFF, 0xFF,
+                9, 10, 11, 12, 13, 14, 15, 16,
+                0x34, 0x12,
+                1, 2, 3, 4, 5, 6, 7, 8,
+                0x08, 0x34, 0x12, 0x00, 0x00, 15, 0x78, 0x56,
+            ],
+        )
+    }
+
+    #[test]
+    fn test_coordinator_realignment_frame_broadcast() {
+        let frame = coordinator_realignment_frame(
+            4,
+            Channel::try_from(15).unwrap(),
+            PanId(0x1234),
+            IEEEAddress([1, 2, 3, 4, 5, 6, 7, 8]),
+            ShortAddress(0x0000),
+            None,
+        );
+        let parsed = ParsedFrame::parse(&frame[1..]).unwrap();
+
+        assert!(!parsed.ack_request);
+        assert_eq!(parsed.dst_addr, Some(Address::Short(ShortAddress::BROADCAST)));
+        assert_eq!(&parsed.payload[6..], &[0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn test_beacon_frame() {
+        let mut builder = beacon_frame_builder();
+        builder.superframe_spec = SuperframeSpec {
+            beacon_order: 15,
+            superframe_order: 15,
+            final_cap_slot: 15,
+            pan_coordinator: true,
+            association_permit: true,
+            ..Default::default()
+        };
+        builder.pending_addresses.add_short(ShortAddress(0x5678)).unwrap();
+        builder.beacon_payload = &[0xAA];
+        let frame = builder.build(9, PanId(0x1234), ShortAddress(0x0000).into()).unwrap();
+
+        assert_eq!(
+            frame.as_slice(),
+            &[
+                0x10,
+                0x00, 0x80,
+                0x09,
+                0x34, 0x12,
+                0x00, 0x00,
+                0xFF, 0xCF,
+                0x00,
+                0x01, 0x78, 0x56,
+                0xAA,
+            ],
+        )
+    }
+
+    #[test]
+    fn test_beacon_frame_too_long() {
+        let mut builder = beacon_frame_builder();
+        builder.beacon_payload = &[0u8; 120];
+
+        assert_eq!(
+            builder.build(0, PanId(0x1234), ShortAddress(0x0000).into()),
+            Err(FrameError::FrameTooLong),
+        )
+    }
+
+    #[test]
+    fn test_parse_association_request() {
+        let frame = association_request_frame(
+            7,
+            PanId(0x1234),
+            ShortAddress(0x0000).into(),
+            IEEEAddress([1, 2, 3, 4, 5, 6, 7, 8]),
+            0x80,
+        );
+        let parsed = ParsedFrame::parse(&frame[1..]).unwrap();
+
+        assert_eq!(parsed.frame_type, FrameType::MacCommand);
+        assert!(parsed.ack_request);
+        assert_eq!(parsed.sequence_number, 7);
+        assert_eq!(parsed.dst_pan, Some(PanId(0x1234)));
+        assert_eq!(parsed.dst_addr, Some(Address::Short(ShortAddress(0x0000))));
+        assert_eq!(parsed.src_pan, Some(PanId::BROADCAST));
+        assert_eq!(parsed.src_addr, Some(Address::Extended(IEEEAddress([1, 2, 3, 4, 5, 6, 7, 8]))));
+        assert_eq!(parsed.payload, &[0x01, 0x80]);
+    }
+
+    #[test]
+    fn test_parse_truncated_frame() {
+        assert_eq!(ParsedFrame::parse(&[0x41, 0x88]), None);
+        assert_eq!(ParsedFrame::parse(&[0x41, 0x88, 0x01, 0x34]), None);
+    }
+}
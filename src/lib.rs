@@ -2,86 +2,312 @@
 //! Drive for the CC2420 IEEE 802.15.4 Compatible Radio Module
 //! 
 
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(any(test, feature = "test_utils"))]
 extern crate alloc;
-use alloc::string::ToString;
 
 use core::cmp::min;
 
-use alloc::vec;
-use alloc::vec::Vec;
-
 use embedded_hal::spi::{SpiDevice, Mode, MODE_0};
 use embedded_hal::digital::InputPin;
 use embedded_hal::delay::DelayNs;
 
+#[cfg(any(test, feature = "test_utils"))]
+pub mod test_utils;
+
 mod ram;
 use ram::Ram;
 
 mod register;
 use register::*;
+pub use register::fsm::{FiniteStateMachineConstants, FiniteStateMachineConstantsBuilder};
+pub use register::io_configuration::{IOConfigurationRegister, IOConfigurationRegister1, IOConfigurationRegister1Builder, IOConfigurationRegisterBuilder};
+pub use register::modem_control::{ModemControlRegister1, ModemControlRegister1Builder};
+pub use register::override_registers::{AndOverrideRegister, AndOverrideRegisterBuilder, OrOverrideRegister, OrOverrideRegisterBuilder};
 
 pub mod error;
-pub use error::RadioError;
+pub use error::{BuildError, RadioError};
 
 pub mod status;
 pub use status::RadioStatus;
 
 pub mod strobe;
-pub use strobe::Strobe;
+pub use strobe::{Strobe, TryFromStrobeError};
 
 pub mod config;
-pub use config::Configuration;
+pub use config::{Configuration, ConfigureParts, HssdSource, LnaGainMode, OutputPower, RadioConfig, ReceiveFilter, SecureConfiguration};
+
+pub mod builder;
+pub use builder::RadioBuilder;
+
+pub mod address;
+pub use address::{Address, IEEEAddress, PanId, ShortAddress};
+
+#[cfg(feature = "float")]
+pub mod battery;
+#[cfg(feature = "float")]
+pub use battery::battery_voltage_estimate;
+
+pub mod bounded_vec;
+pub use bounded_vec::BoundedVec;
+
+pub mod diagnostics;
+pub use diagnostics::RegisterSnapshot;
+
+#[cfg(feature = "cache")]
+pub mod cache;
+#[cfg(feature = "cache")]
+pub use cache::RegisterCache;
+
+pub mod channel;
+pub use channel::{Channel, Frequency, Ieee802154Channel};
+
+pub mod fcs;
+pub use fcs::compute_fcs;
+
+pub mod frame;
+pub use frame::{BeaconFrameBuilder, FrameControl, FrameParseError, FrameType, MacHeader, RxFrame, TxFrame, TxFrameBuilder};
+use frame::{MacCommand, ParsedFrame};
+
+pub mod mac;
+pub use mac::BackoffRng;
+use mac::{AssociationResult, AssociationStatus, PanDescriptor, MAX_SCAN_RESULTS};
+
+pub mod metadata;
+
+pub mod pin;
+pub use pin::NoPin;
+
+pub mod power;
+pub use power::TxPowerLevel;
+
+pub mod state;
+pub use state::{RadioState, RadioStateMachine};
+
+pub mod reset;
+pub use reset::ResetMask;
+
+pub mod security;
+pub use security::{EncryptionKey, KeySlot, Nonce, SecurityLevel, SecurityMode};
+#[cfg(feature = "replay-protection")]
+pub use security::ReplayCounters;
 
 pub const RADIO_SPI_MODE: Mode = MODE_0;
 pub const MAX_SCLK_FREQUENCY: u32 = 10_000_000;
 
+// Part number reported by the CC2420 in the manufacturer ID registers
+const CC2420_PART_NUMBER: u16 = 0x002;
+// Oldest supported version number of the CC2420
+const CC2420_MIN_VERSION: u8 = 3;
+// Manufacturer ID reported by the CC2420 (Texas Instruments / Chipcon)
+const CC2420_MANUFACTURER_ID: u16 = 0x33D;
+
+// Size of the RX FIFO in bytes
+const RX_FIFO_SIZE: usize = 128;
+
+// Whether a pin is at its active level (its IOCFG0 polarity bit is set when
+// it is active low)
+fn pin_active<P: InputPin>(pin: &mut P, active_low: bool) -> Result<bool, P::Error> {
+    if active_low {
+        pin.is_low()
+    } else {
+        pin.is_high()
+    }
+}
+// Size of the largest RAM transfer (2 address bytes and the 128 bytes of a
+// FIFO)
+const RAM_TRANSFER_SIZE: usize = 130;
 // Delay (for configuration) to wait before checking the register value has
 // been updated
 const REGISTER_WRITE_DELAY_US: u32 = 100;
 // Delay (for configuration) to wait before checking the value in RAM has
 // been updated
 const RAM_WRITE_DELAY_US: u32 = 100;
+// Delay (after entering RX) before the RSSI value is valid (8 symbol periods)
+const RSSI_VALID_DELAY_US: u32 = 128;
+// Time to receive a single byte over the air (250 kbps)
+const BYTE_PERIOD_US: u32 = 32;
+// Number of bytes sent before the length byte of a frame (default 4 byte
+// preamble and 2 byte SFD)
+const PREAMBLE_AND_SFD_BYTES: u32 = 6;
+// Time from STXON to the start of the preamble (12 symbol periods)
+const TX_TURNAROUND_US: u32 = 192;
+// Longest time a transmission can take (turnaround, preamble, SFD and a
+// 128 byte frame)
+const TX_COMPLETE_TIMEOUT_US: u32 = TX_TURNAROUND_US + (PREAMBLE_AND_SFD_BYTES + 128) * BYTE_PERIOD_US;
+// Delay between checks of the FIFO pin when listening for a frame
+const LISTEN_POLL_US: u32 = 100;
+// Scan duration (n in aBaseSuperframeDuration * (2^n + 1)) used to look for
+// beacons before associating
+const ASSOCIATION_SCAN_DURATION: u8 = 3;
+// Capability information sent in association requests (allocate address)
+const ASSOCIATION_CAPABILITY: u8 = 1 << 7;
+// Delay between checks of the crystal oscillator's stable flag
+const XOSC_POLL_US: u32 = 50;
+// Maximum time to wait for the crystal oscillator to stabilise
+pub(crate) const XOSC_STABLE_TIMEOUT_US: u32 = 5_000;
+// Delay between checks of the encryption module's busy flag
+const ENC_BUSY_POLL_US: u32 = 5;
+// Maximum time to wait for a stand-alone encryption (takes ~21us)
+const ENCRYPTION_TIMEOUT_US: u32 = 100;
+// Delay between checks of the frequency synthesizer's calibration done flag
+const CALIBRATION_POLL_US: u32 = 20;
+// Maximum time to wait for the frequency synthesizer to calibrate (takes 12
+// symbol periods)
+const CALIBRATION_TIMEOUT_US: u32 = 1_000;
+// Delay between checks of the PLL lock flag
+const PLL_LOCK_POLL_US: u32 = 10;
+// Maximum time to wait for the PLL to lock after starting calibration
+const PLL_LOCK_TIMEOUT_US: u32 = 1_000;
+// Time for the battery monitor comparator output to become valid
+const BATTMON_SETTLE_US: u32 = 5;
+// Time to hold the chip in reset (MAIN.RESETn low)
+const RESET_ASSERT_US: u32 = 100;
+// Time to wait after releasing the reset before accessing the chip
+const RESET_SETTLE_US: u32 = 200;
 
-pub struct Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
+pub struct Radio<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP = NoPin<GPIOE>> where
     SPI: SpiDevice<u8, Error=SPIE>,
     SFD: InputPin<Error=GPIOE>,
-    FIFO: InputPin<Error=GPIOE> {
-    // Whether or not the radio is powered up
-    pub powered_up: bool,
+    FIFO: InputPin<Error=GPIOE>,
+    FIFOP: InputPin<Error=GPIOE> {
+    // State of the radio (from the strobes sent by the driver and the TX
+    // status read back from the chip)
+    state: RadioState,
+    // Sequence number of the next beacon frame (macBSN)
+    beacon_sequence_number: u8,
+    // Sequence number of the next data frame (macDSN)
+    sequence_number: u8,
+    // Link quality indicator of the last frame read by receive_frame or
+    // receive_frame_bounded
+    last_lqi: u8,
+    // Whether the radio has joined a PAN
+    association_status: AssociationStatus,
+    // Whether received frame counters are checked for replays
+    #[cfg(feature = "replay-protection")]
+    replay_protection: bool,
+    // Last accepted frame counter of each source
+    #[cfg(feature = "replay-protection")]
+    replay_counters: ReplayCounters,
+    // Last known register values
+    #[cfg(feature = "cache")]
+    cache: RegisterCache,
+    // Last IOCFG0 value written, for the polarity of the FIFO, FIFOP and SFD
+    // pins
+    io_configuration: io_configuration::IOConfigurationRegister,
     // SPI Peripheral Device
     spi: SPI,
     // Data Sent Interrupt
     sfd: SFD,
     // Data Received Interrupt
     fifo: FIFO,
+    // RX FIFO Above Threshold Interrupt
+    fifop: FIFOP,
 }
 
 impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
     SPI: SpiDevice<u8, Error=SPIE>,
     SFD: InputPin<Error=GPIOE>,
-    FIFO: InputPin<Error=GPIOE> {
+    FIFO: InputPin<Error=GPIOE>,
+    GPIOE: embedded_hal::digital::Error {
+    /// Construct a radio without a FIFOP pin (see new_with_fifop)
     pub fn new(spi: SPI, sfd: SFD, fifo: FIFO) -> Self {
+        Self::new_with_fifop(spi, sfd, fifo, NoPin::new())
+    }
+}
+
+impl<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP> Radio<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP> where
+    SPI: SpiDevice<u8, Error=SPIE>,
+    SFD: InputPin<Error=GPIOE>,
+    FIFO: InputPin<Error=GPIOE>,
+    FIFOP: InputPin<Error=GPIOE> {
+    /// Construct a radio with the FIFOP pin connected (used for interrupt
+    /// driven receive, see enable_rx_interrupt_mode)
+    pub fn new_with_fifop(spi: SPI, sfd: SFD, fifo: FIFO, fifop: FIFOP) -> Self {
         Self {
-            powered_up: false,
+            state: RadioState::PoweredDown,
+            beacon_sequence_number: 0,
+            sequence_number: 0,
+            last_lqi: 0,
+            association_status: AssociationStatus::NotAssociated,
+            #[cfg(feature = "replay-protection")]
+            replay_protection: false,
+            #[cfg(feature = "replay-protection")]
+            replay_counters: ReplayCounters::new(),
+            #[cfg(feature = "cache")]
+            cache: RegisterCache::new(),
+            io_configuration: IOConfigurationRegisterBuilder::default().build().unwrap(),
             spi,
             sfd,
             fifo,
+            fifop,
         }
     }
 
-    /// Apply a given configuration to the radio and starting the crystal oscillator on the radio.
-    pub fn configure(&mut self, config: Configuration, delay: &mut dyn DelayNs) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+    /// Release the SPI device and the SFD and FIFO pins, consuming the driver
+    /// (the FIFOP pin is dropped, see split_with_fifop).
+    /// 
+    /// Note: The radio is left in its current state, call power_down (or
+    /// shutdown) first if it is powered up.
+    pub fn split(self) -> (SPI, SFD, FIFO) {
+        (self.spi, self.sfd, self.fifo)
+    }
+
+    /// Release the SPI device and the SFD, FIFO and FIFOP pins, consuming the
+    /// driver.
+    pub fn split_with_fifop(self) -> (SPI, SFD, FIFO, FIFOP) {
+        (self.spi, self.sfd, self.fifo, self.fifop)
+    }
+
+    /// The SPI device connected to the radio
+    pub fn spi(&self) -> &SPI {
+        &self.spi
+    }
+
+    /// The SPI device connected to the radio (e.g. to share the bus).
+    /// 
+    /// Note: Strobes sent directly are not reflected in state().
+    pub fn spi_mut(&mut self) -> &mut SPI {
+        &mut self.spi
+    }
+
+    /// Apply a given configuration to the radio and starting the crystal oscillator on the radio,
+    /// waiting at most timeout_us for the oscillator to stabilise.
+    pub fn configure(&mut self, config: Configuration, delay: &mut dyn DelayNs, timeout_us: u32) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.apply_configuration(config, ConfigureParts::ALL, delay, timeout_us)
+    }
+
+    /// Re-apply only the register configuration, skipping the addresses and
+    /// keys stored in RAM (for when the RAM contents are still valid, e.g.
+    /// after a power glitch).
+    pub fn configure_minimal(&mut self, config: Configuration, delay: &mut dyn DelayNs) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.configure_parts(config, ConfigureParts::REGISTERS_ONLY, delay)
+    }
+
+    /// Apply a given configuration, only writing the parts stored in RAM that
+    /// are selected by parts.
+    pub fn configure_parts(&mut self, config: Configuration, parts: ConfigureParts, delay: &mut dyn DelayNs) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.apply_configuration(config, parts, delay, XOSC_STABLE_TIMEOUT_US)
+    }
+
+    fn apply_configuration(&mut self, config: Configuration, parts: ConfigureParts, delay: &mut dyn DelayNs, timeout_us: u32) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        config::validate_configuration(&config).map_err(RadioError::InvalidConfiguration)?;
+
+        if config.verify_chip_id {
+            self.verify_chip_id()?;
+        }
+
         // Modem Configuration
         let modem_config = ModemControlRegister0Builder::default()
             .pan_coordinator(config.pan_coordinator)
             .adr_decode(config.address_decoding)
+            .cca_mode(config.cca_mode)
             .auto_crc(config.enable_crc)
             .auto_ack(config.auto_acknowledge)
             .preamble_length(config.preamble_length)
             .build()
-            .map_err(|e| { RadioError::InvalidConfiguration(e.to_string()) })?;
+            .map_err(|e| { RadioError::InvalidConfiguration(e.message()) })?;
         self.write_register(&modem_config)?;
         delay.delay_us(REGISTER_WRITE_DELAY_US);
         let mut found_modem_config = ModemControlRegister0Builder::default().build().unwrap();
@@ -90,6 +316,19 @@ impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
             return Err(RadioError::FailedConfiguration("Configuration of Modem Failed"));
         }
 
+        // Correlation Threshold Configuration
+        let modem_control_1 = ModemControlRegister1Builder::default()
+            .corr_threshold(config.correlation_threshold)
+            .build()
+            .map_err(|e| RadioError::InvalidConfiguration(e.message()))?;
+        self.write_register(&modem_control_1)?;
+        delay.delay_us(REGISTER_WRITE_DELAY_US);
+        let mut found_modem_control_1 = ModemControlRegister1Builder::default().build().unwrap();
+        self.read_register(&mut found_modem_control_1)?;
+        if found_modem_control_1 != modem_control_1 {
+            return Err(RadioError::FailedConfiguration("Configuration of Correlation Threshold Failed"));
+        }
+
         // Sync Word Configuration
         let sync_word = SyncWordRegisterBuilder::default()
             .sync_word(u16::from_le_bytes(config.sync_word))
@@ -103,65 +342,94 @@ impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
             return Err(RadioError::FailedConfiguration("Configuration of Sync Word Failed"));
         }
 
-        // Set Short Address
-        self.set_short_address(u16::from_le_bytes(config.short_address))?;
-        delay.delay_us(RAM_WRITE_DELAY_US);
-        let found_short_address = self.read_short_address()?.to_le_bytes();
-        if found_short_address != config.short_address {
-            return Err(RadioError::FailedConfiguration("Configuration of Short Address Failed"));
+        // IO Configuration (only FIFOP_THR is updated, BCN_ACCEPT and the pin
+        // polarities are left as they are)
+        self.set_fifop_threshold(config.fifop_threshold)?;
+        delay.delay_us(REGISTER_WRITE_DELAY_US);
+        let mut io_configuration = IOConfigurationRegisterBuilder::default().build().unwrap();
+        self.read_register(&mut io_configuration)?;
+        if io_configuration.fifop_threshold != config.fifop_threshold {
+            return Err(RadioError::FailedConfiguration("Configuration of IO Failed"));
         }
 
-        // Set Pan ID
-        self.set_pan_id(u16::from_le_bytes(config.pan_identifier))?;
-        delay.delay_us(RAM_WRITE_DELAY_US);
-        let found_pan_id = self.read_pan_id()?.to_le_bytes();
-        if found_pan_id != config.pan_identifier {
-            return Err(RadioError::FailedConfiguration("Configuration of Pan ID Failed"));
-        }
+        if parts.addresses {
+            // Set Short Address
+            self.set_short_address(u16::from_le_bytes(config.short_address))?;
+            delay.delay_us(RAM_WRITE_DELAY_US);
+            let found_short_address = self.read_short_address()?.to_le_bytes();
+            if found_short_address != config.short_address {
+                return Err(RadioError::FailedConfiguration("Configuration of Short Address Failed"));
+            }
 
-        // Set IEEE Address
-        self.set_ieee_address(config.ieee_address)?;
-        delay.delay_us(RAM_WRITE_DELAY_US);
-        let found_ieee_address = self.read_ieee_address()?;
-        if found_ieee_address != config.ieee_address {
-            return Err(RadioError::FailedConfiguration("Configuration of IEEE Address Failed"));
-        }
+            // Set Pan ID
+            self.set_pan_id(u16::from_le_bytes(config.pan_identifier))?;
+            delay.delay_us(RAM_WRITE_DELAY_US);
+            let found_pan_id = self.read_pan_id()?.to_le_bytes();
+            if found_pan_id != config.pan_identifier {
+                return Err(RadioError::FailedConfiguration("Configuration of Pan ID Failed"));
+            }
 
-        // Set Tx Encryption Key
-        self.set_key_1(config.tx_encryption_key)?;
-        delay.delay_us(RAM_WRITE_DELAY_US);
-        let found_tx_key = self.read_key_1()?;
-        if found_tx_key != config.tx_encryption_key {
-            return Err(RadioError::FailedConfiguration("Configuration of Tx Encryption Key Failed"));
-        }
+            // Set IEEE Address
+            self.set_ieee_address(config.ieee_address)?;
+            delay.delay_us(RAM_WRITE_DELAY_US);
+            let found_ieee_address = self.read_ieee_address()?;
+            if found_ieee_address != config.ieee_address {
+                return Err(RadioError::FailedConfiguration("Configuration of IEEE Address Failed"));
+            }
 
-        // Set Rx Decryption Key
-        self.set_key_0(config.rx_decryption_key)?;
-        delay.delay_us(RAM_WRITE_DELAY_US);
-        let found_rx_key = self.read_key_0()?;
-        if found_rx_key != config.rx_decryption_key {
-            return Err(RadioError::FailedConfiguration("Configuration of Rx Decryption Key Failed"));
+            // A PAN coordinator is associated with the PAN it coordinates
+            self.association_status = if config.pan_coordinator {
+                AssociationStatus::Associated {
+                    pan_id: PanId(u16::from_le_bytes(config.pan_identifier)),
+                    short_addr: ShortAddress(u16::from_le_bytes(config.short_address)),
+                }
+            } else {
+                AssociationStatus::NotAssociated
+            };
         }
 
-        // Start up the crystal oscillator
-        let mut status = self.xosc_on()?;
-        while !status.xosx_stable {
-            status = self.status()?;
-            delay.delay_us(100);
+        if parts.keys {
+            // Keys and nonces are written before in-line security is enabled
+            let nonces = (Nonce::new(config.tx_nonce), Nonce::new(config.rx_nonce));
+            self.write_security_configuration(config.security, Some(nonces), delay)?;
         }
 
-        self.powered_up = true;
+        // Start up the crystal oscillator
+        self.power_up_within(delay, timeout_us)?;
 
         // Start to Calibrate Tx Frequency
-        self.calibrate_tx()
+        self.calibrate_tx(delay)
     }
 
-    /// Power up the Radio
-    pub fn power_up(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        let mut buffer = [Strobe::XOSCOn.opcode()];
-        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
-        self.powered_up = true;
-        Ok(buffer[0].into())
+    /// Power up the Radio, waiting for the crystal oscillator to stabilise
+    pub fn power_up(&mut self, delay: &mut dyn DelayNs) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.power_up_within(delay, XOSC_STABLE_TIMEOUT_US)
+    }
+
+    fn power_up_within(&mut self, delay: &mut dyn DelayNs, timeout_us: u32) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let _ = self.xosc_on()?;
+        self.wait_for_oscillator(delay, timeout_us)?;
+        self.state = RadioState::Idle;
+        self.status()
+    }
+
+    /// Whether the crystal oscillator is running and stable
+    pub fn xosc_is_stable(&mut self) -> Result<bool, RadioError<SPIE, GPIOE>> {
+        Ok(self.status()?.xosx_stable)
+    }
+
+    /// Wait for the crystal oscillator to become stable, polling every 50us
+    /// for up to timeout_us.
+    pub fn wait_for_oscillator(&mut self, delay: &mut dyn DelayNs, timeout_us: u32) -> Result<(), RadioError<SPIE, GPIOE>> {
+        let mut waited_us = 0;
+        while !self.xosc_is_stable()? {
+            if waited_us >= timeout_us {
+                return Err(RadioError::Timeout);
+            }
+            delay.delay_us(XOSC_POLL_US);
+            waited_us += XOSC_POLL_US;
+        }
+        Ok(())
     }
 
     /// Power down the Radio
@@ -170,53 +438,324 @@ impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
         self.spi.write(&buffer).map_err(RadioError::SpiError)?;
         buffer[0] = Strobe::XOSCOff.opcode();
         self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
-        self.powered_up = false;
+        self.state = RadioState::PoweredDown;
+        Ok(buffer[0].into())
+    }
+
+    /// Power down the Radio, first waiting (for at most the duration of the
+    /// longest frame) for any transmission in progress to complete, then
+    /// checking the frequency synthesizer has turned off before stopping the
+    /// crystal oscillator.
+    pub fn safe_power_down(&mut self, delay: &mut dyn DelayNs) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut waited_us = 0;
+        while self.status()?.tx_active {
+            if waited_us >= TX_COMPLETE_TIMEOUT_US {
+                return Err(RadioError::Timeout);
+            }
+            delay.delay_us(BYTE_PERIOD_US);
+            waited_us += BYTE_PERIOD_US;
+        }
+
+        let _ = self.disable_rx_tx()?;
+        if self.status()?.lock {
+            return Err(RadioError::FailedConfiguration("Disabling the Frequency Synthesizer Failed"));
+        }
+
+        let mut buffer = [Strobe::XOSCOff.opcode()];
+        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
+        self.state = RadioState::PoweredDown;
         Ok(buffer[0].into())
     }
 
     /// Reset the Radio
+    /// 
+    /// Note: This only asserts the reset (MAIN.RESETn low) and leaves the
+    /// radio held in reset, use full_reset instead.
+    #[deprecated(note = "leaves the radio held in reset, use full_reset instead")]
     pub fn reset(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
         let register = MainControlRegisterBuilder::default().reset_n(false).build().unwrap();
+        let status = self.write_register(&register)?;
+        self.registers_reset();
+        Ok(status)
+    }
+
+    /// Reset individual modules of the radio, asserting (clearing) their reset
+    /// bits in MAIN then releasing them again. The rest of the radio is left
+    /// running.
+    pub fn reset_subsystem(&mut self, mask: ResetMask) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut register = MainControlRegisterBuilder::default().build().unwrap();
+        self.read_register_for_update(&mut register)?;
+        let mut reset = register;
+        reset.enc_reset_n &= !mask.contains(ResetMask::ENCRYPTION);
+        reset.demod_reset_n &= !mask.contains(ResetMask::DEMODULATOR);
+        reset.mod_reset_n &= !mask.contains(ResetMask::MODULATOR);
+        reset.fs_reset_n &= !mask.contains(ResetMask::FREQUENCY_SYNTHESIZER);
+        self.write_register(&reset)?;
+        register.enc_reset_n |= mask.contains(ResetMask::ENCRYPTION);
+        register.demod_reset_n |= mask.contains(ResetMask::DEMODULATOR);
+        register.mod_reset_n |= mask.contains(ResetMask::MODULATOR);
+        register.fs_reset_n |= mask.contains(ResetMask::FREQUENCY_SYNTHESIZER);
         self.write_register(&register)
     }
 
+    /// Reset every register of the radio to its default value, asserting the
+    /// reset (MAIN.RESETn low) then releasing it and waiting for the chip to
+    /// settle.
+    pub fn full_reset(&mut self, delay: &mut dyn DelayNs) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let register = MainControlRegisterBuilder::default().reset_n(false).build().unwrap();
+        self.write_register(&register)?;
+        delay.delay_us(RESET_ASSERT_US);
+        let register = MainControlRegisterBuilder::default().build().unwrap();
+        self.write_register(&register)?;
+        delay.delay_us(RESET_SETTLE_US);
+        // The reset turns off the crystal oscillator (and restores the default
+        // pin polarities)
+        self.state = RadioState::PoweredDown;
+        self.registers_reset();
+        self.status()
+    }
+
+    /// Fully shut down the radio, turning off the frequency synthesizer and
+    /// crystal oscillator and resetting every register to its default value.
+    pub fn shutdown(&mut self) -> Result<(), RadioError<SPIE, GPIOE>> {
+        self.disable_rx_tx()?;
+        let mut buffer = [Strobe::XOSCOff.opcode()];
+        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
+        let register = MainControlRegisterBuilder::default().reset_n(false).build().unwrap();
+        self.write_register(&register)?;
+        let register = MainControlRegisterBuilder::default().build().unwrap();
+        self.write_register(&register)?;
+        self.state = RadioState::PoweredDown;
+        self.registers_reset();
+        Ok(())
+    }
+
+    // Forget the register values held by the driver once the chip's registers
+    // have been reset to their defaults
+    fn registers_reset(&mut self) {
+        self.io_configuration = IOConfigurationRegisterBuilder::default().build().unwrap();
+        #[cfg(feature = "cache")]
+        self.cache.invalidate();
+    }
+
+    /// Shut down the radio and re-apply the given configuration, waiting at
+    /// most timeout_us for the oscillator to stabilise.
+    pub fn restart(&mut self, config: Configuration, delay: &mut dyn DelayNs, timeout_us: u32) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.shutdown()?;
+        self.configure(config, delay, timeout_us)
+    }
+
+    /// Write the modem control register 1 (MDMCTRL1: correlation threshold,
+    /// demodulator averaging, modulation and test modes)
+    pub fn set_modem_control_1(&mut self, config: ModemControlRegister1) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        // The fields are public, so validate them as the builder would
+        let config = ModemControlRegister1Builder::default()
+            .corr_threshold(config.corr_threshold)
+            .demod_average_mode(config.demod_average_mode)
+            .modulation_mode(config.modulation_mode)
+            .tx_mode(config.tx_mode)
+            .rx_mode(config.rx_mode)
+            .build()
+            .map_err(|e| RadioError::InvalidConfiguration(e.message()))?;
+        self.write_register(&config)
+    }
+
+    /// Read the modem control register 1 (MDMCTRL1)
+    pub fn read_modem_control_1(&mut self) -> Result<ModemControlRegister1, RadioError<SPIE, GPIOE>> {
+        let mut register = ModemControlRegister1Builder::default().build().unwrap();
+        let _ = self.read_register(&mut register)?;
+        Ok(register)
+    }
+
+    /// Write the finite state machine time constants (FSMTC: RX chain
+    /// startup, TX turnaround and PA on / off times)
+    pub fn set_fsm_constants(&mut self, constants: FiniteStateMachineConstants) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        // The fields are public, so validate them as the builder would
+        let constants = FiniteStateMachineConstantsBuilder::default()
+            .tc_rxchain_to_rx(constants.tc_rxchain_to_rx)
+            .tc_switch_to_tx(constants.tc_switch_to_tx)
+            .tc_paon_to_tx(constants.tc_paon_to_tx)
+            .tc_txend_to_switch(constants.tc_txend_to_switch)
+            .tc_txend_to_paoff(constants.tc_txend_to_paoff)
+            .build()
+            .map_err(|e| RadioError::InvalidConfiguration(e.message()))?;
+        self.write_register(&constants)
+    }
+
+    /// Read the finite state machine time constants (FSMTC)
+    pub fn read_fsm_constants(&mut self) -> Result<FiniteStateMachineConstants, RadioError<SPIE, GPIOE>> {
+        let mut register = FiniteStateMachineConstantsBuilder::default().build().unwrap();
+        let _ = self.read_register(&mut register)?;
+        Ok(register)
+    }
+
     /// Set the sync word of the Radio
     pub fn set_sync_word(&mut self, value: u16) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
         let register = SyncWordRegisterBuilder::default().sync_word(value).build().unwrap();
         self.write_register(&register)
     }
 
+    /// Set the sync word of the Radio from its byte representation (little
+    /// endian, as in Configuration::sync_word)
+    pub fn set_sync_word_bytes(&mut self, bytes: [u8; 2]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.set_sync_word(u16::from_le_bytes(bytes))
+    }
+
+    /// Read the sync word of the Radio as bytes (little endian)
+    pub fn read_sync_word_bytes(&mut self) -> Result<[u8; 2], RadioError<SPIE, GPIOE>> {
+        let mut register = SyncWordRegisterBuilder::default().build().unwrap();
+        let _ = self.read_register(&mut register)?;
+        Ok(register.sync_word.to_le_bytes())
+    }
+
+    /// Set the in-line security mode (SECCTRL0.SEC_MODE) and the size of the
+    /// MIC for CBC-MAC and CCM (SECCTRL0.SEC_M), leaving the key selection
+    /// unchanged.
+    pub fn set_security_mode(&mut self, mode: SecurityMode, level: SecurityLevel) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut register = SecurityControlRegister0Builder::default().build().unwrap();
+        self.read_register_for_update(&mut register)?;
+        register.sec_mode = mode.sec_mode();
+        register.sec_m = level.sec_m();
+        self.write_register(&register)
+    }
+
+    /// Disable in-line security (SEC_MODE = 0)
+    pub fn disable_security(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut register = SecurityControlRegister0Builder::default().build().unwrap();
+        self.read_register_for_update(&mut register)?;
+        register.sec_mode = SecurityMode::Disabled.sec_mode();
+        self.write_register(&register)
+    }
+
     /// Select the key to use for standalone AES encryption
     pub fn set_standalone_key(&mut self, key_0: bool) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
         let mut register = SecurityControlRegister0Builder::default().build().unwrap();
-        let _ = self.read_register(&mut register)?;
+        self.read_register_for_update(&mut register)?;
         register.sec_sa_key_sel = key_0;
         self.write_register(&register)
     }
     
-    /// Select the key to use for tx AES encryption
+    /// Select the key to use for tx AES encryption (the in-line security
+    /// mode used is set by set_security_mode)
     pub fn set_tx_key(&mut self, key_0: bool) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
         let mut register = SecurityControlRegister0Builder::default().build().unwrap();
-        let _ = self.read_register(&mut register)?;
+        self.read_register_for_update(&mut register)?;
         register.sec_tx_key_sel = key_0;
         self.write_register(&register)
     }
 
-    /// Select the key to use for rx AES encryption
+    /// Select the key to use for rx AES encryption (the in-line security
+    /// mode used is set by set_security_mode)
     pub fn set_rx_key(&mut self, key_0: bool) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
         let mut register = SecurityControlRegister0Builder::default().build().unwrap();
-        let _ = self.read_register(&mut register)?;
+        self.read_register_for_update(&mut register)?;
         register.sec_rx_key_sel = key_0;
         self.write_register(&register)
     }
 
+    /// Enable or disable protection of the RX FIFO.
+    /// 
+    /// Note: This should be disabled if MAC level security is not used.
+    pub fn set_rx_fifo_protection(&mut self, enable: bool) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut register = SecurityControlRegister0Builder::default().build().unwrap();
+        self.read_register_for_update(&mut register)?;
+        register.rx_fifo_protection = enable;
+        self.write_register(&register)
+    }
+
+    /// Select whether the length of the authenticated data (rather than the
+    /// first data byte) is used as the first byte into CBC-MAC.
+    pub fn set_cbc_head(&mut self, use_length: bool) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut register = SecurityControlRegister0Builder::default().build().unwrap();
+        self.read_register_for_update(&mut register)?;
+        register.sec_cbc_head = use_length;
+        self.write_register(&register)
+    }
+
+    /// Configure in-line CBC-MAC authentication for both TX and RX.
+    /// 
+    /// `auth_len` is the number of cleartext bytes between the length byte
+    /// and the first authenticated byte (written to SEC_TXL and SEC_RXL).
+    pub fn configure_cbc_mac(&mut self, key_slot: KeySlot, auth_len: u8, mic_size: SecurityLevel) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let lengths = SecurityControlRegister1Builder::default()
+            .sec_txl(auth_len)
+            .sec_rxl(auth_len)
+            .build()
+            .map_err(|e| RadioError::InvalidConfiguration(e.message()))?;
+
+        let mut register = SecurityControlRegister0Builder::default().build().unwrap();
+        self.read_register_for_update(&mut register)?;
+        register.sec_cbc_head = true;
+        register.sec_tx_key_sel = key_slot.select_bit();
+        register.sec_rx_key_sel = key_slot.select_bit();
+        register.sec_m = mic_size.sec_m();
+        register.sec_mode = 1;
+        self.write_register(&register)?;
+
+        self.write_register(&lengths)
+    }
+
+    /// Set the number of cleartext bytes preceding the encrypted /
+    /// authenticated region of transmitted frames (0-127).
+    pub fn set_sec_txl(&mut self, len: u8) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut register = SecurityControlRegister1Builder::default().build().unwrap();
+        self.read_register_for_update(&mut register)?;
+        self.configure_inline_security_lengths(len, register.sec_rxl)
+    }
+
+    /// Set the number of cleartext bytes preceding the decrypted /
+    /// authenticated region of received frames (0-127).
+    pub fn set_sec_rxl(&mut self, len: u8) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut register = SecurityControlRegister1Builder::default().build().unwrap();
+        self.read_register_for_update(&mut register)?;
+        self.configure_inline_security_lengths(register.sec_txl, len)
+    }
+
+    /// Set the TX and RX in-line security cleartext lengths (SEC_TXL and
+    /// SEC_RXL) in a single register write.
+    pub fn configure_inline_security_lengths(&mut self, tx_cleartext: u8, rx_cleartext: u8) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let register = SecurityControlRegister1Builder::default()
+            .sec_txl(tx_cleartext)
+            .sec_rxl(rx_cleartext)
+            .build()
+            .map_err(|e| RadioError::InvalidConfiguration(e.message()))?;
+        self.write_register(&register)
+    }
+
     /// Read the part number of the radio
     pub fn read_part_number(&mut self) -> Result<u16, RadioError<SPIE, GPIOE>> {
+        Ok(self.read_part_number_and_version()?.0)
+    }
+
+    /// Read the part number and version number of the radio from the
+    /// manufacturer ID registers, returning (part_number, version)
+    pub fn read_part_number_and_version(&mut self) -> Result<(u16, u8), RadioError<SPIE, GPIOE>> {
         let mut lower_16_register = LowerManufacturerIDBuilder::default().build().unwrap();
         let _ = self.read_register(&mut lower_16_register)?;
         let mut upper_16_register = UpperManufacturerIDBuilder::default().build().unwrap();
         let _ = self.read_register(&mut upper_16_register)?;
-        Ok(upper_16_register.part_num << 4 | (lower_16_register.part_num as u16))
+        let part_number = upper_16_register.part_num << 4 | (lower_16_register.part_num as u16);
+        Ok((part_number, upper_16_register.version))
+    }
+
+    /// Check whether the connected device is a CC2420.
+    /// 
+    /// Supported devices have part number 0x002 and version 3 or higher
+    /// (earlier engineering samples reported lower versions).
+    pub fn is_cc2420(&mut self) -> Result<bool, RadioError<SPIE, GPIOE>> {
+        let (part_number, version) = self.read_part_number_and_version()?;
+        Ok(part_number == CC2420_PART_NUMBER && version >= CC2420_MIN_VERSION)
+    }
+
+    /// Check that the device on the bus is a CC2420 (part number 0x002 and
+    /// manufacturer ID 0x33D), returning WrongDevice if it is not.
+    pub fn verify_chip_id(&mut self) -> Result<(), RadioError<SPIE, GPIOE>> {
+        let part_number = self.read_part_number()?;
+        let manufacturer = self.read_manufacturer()?;
+        if part_number != CC2420_PART_NUMBER || manufacturer != CC2420_MANUFACTURER_ID {
+            return Err(RadioError::WrongDevice { found_part: part_number, found_manufacturer: manufacturer });
+        }
+        Ok(())
     }
 
     /// Read the manufacturer id of the radio
@@ -229,12 +768,20 @@ impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
     /// Read the version number of the radio
     pub fn version_number(&mut self) -> Result<u8, RadioError<SPIE, GPIOE>> {
         let mut register = UpperManufacturerIDBuilder::default().build().unwrap();
-        let _ = self.read_register(&mut register);
+        let _ = self.read_register(&mut register)?;
         Ok(register.version)
     }
 
     /// Send a Frame (<=128 Bytes of Data)
+    /// 
+    /// Note: Prefer send_with_cca or send_no_cca, which are harder to mix up
+    /// than the cca flag.
+    /// 
+    /// Note: A transmission without CCA can not be started while receiving,
+    /// disable RX first.  CCA is only valid in RX mode, so a transmission with
+    /// CCA can only be started while receiving.
     pub fn send_frame(&mut self, data: &[u8], cca: bool) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.check_can_transmit(cca)?;
         let _ = self.flush_tx_fifo()?;
         if data.len() > 128 {
             return Err(RadioError::InvalidBufferLenth { expected: 128, found: data.len() });
@@ -252,37 +799,201 @@ impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
             buffer[0] = Strobe::EnableTx.opcode();
         }
         self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
+        self.state = RadioState::Transmitting;
+        Ok(buffer[0].into())
+    }
+
+    /// Send a data frame carrying payload to dest in the PAN pan, from this
+    /// node's PAN ID and short address in RAM (or its IEEE address if no
+    /// short address has been allocated) using the next sequence number.
+    pub fn send_frame_to(&mut self, dest: Address, pan: u16, payload: &[u8], cca: bool) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let src_pan = self.read_pan_id()?;
+        let short_address = self.read_short_address()?;
+        let builder = TxFrameBuilder::new()
+            .dest_pan(pan)
+            .src_pan(src_pan)
+            .payload(payload);
+        let builder = match dest {
+            Address::Short(address) => builder.dest_short_address(address.0),
+            Address::Extended(address) => builder.dest_extended_address(address.0),
+        };
+        let builder = if short_address >= 0xFFFE {
+            builder.src_extended_address(self.read_ieee_address()?)
+        } else {
+            builder.src_short_address(short_address)
+        };
+        let frame = self.build_frame(builder)?;
+        self.send_frame(&frame, cca)
+    }
+
+    /// Send a Frame (<=128 Bytes of Data) once the channel is clear (STXONCCA)
+    pub fn send_with_cca(&mut self, data: &[u8]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.send_frame(data, true)
+    }
+
+    // Send a frame with CCA and check that the transmission started, as
+    // STXONCCA does nothing if the channel is busy (or RX has not been on for
+    // RSSI_VALID_DELAY_US).  Returns ChannelBusy if it did not start.
+    fn send_with_cca_checked(&mut self, data: &[u8]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let _ = self.send_with_cca(data)?;
+        let status = self.status()?;
+        if !status.tx_active && !self.sfd_active()? {
+            return Err(RadioError::ChannelBusy);
+        }
+        Ok(status)
+    }
+
+    /// Send a Frame (<=128 Bytes of Data) immediately, without checking that
+    /// the channel is clear (STXON)
+    pub fn send_no_cca(&mut self, data: &[u8]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.send_frame(data, false)
+    }
+
+    /// Send a Frame (<=128 Bytes of Data) using unslotted CSMA-CA (802.15.4
+    /// §7.5.1.4).  Before each of at most max_backoffs attempts the radio waits
+    /// a random number of backoff periods (up to 2^BE - 1, with BE starting at
+    /// macMinBE), then sends the frame if the channel is clear, otherwise BE
+    /// is increased (up to macMaxBE) and the radio backs off again.
+    /// 
+    /// RX is enabled (if it is not already) so the channel can be assessed.
+    /// Returns ChannelBusy if the channel was busy for every attempt.
+    pub fn csma_send(&mut self, data: &[u8], max_backoffs: u8, rng: &mut impl BackoffRng, delay: &mut dyn DelayNs) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        if data.len() > 128 {
+            return Err(RadioError::InvalidBufferLenth { expected: 128, found: data.len() });
+        }
+        if self.state != RadioState::Receiving {
+            let _ = self.enable_rx()?;
+            delay.delay_us(RSSI_VALID_DELAY_US);
+        }
+
+        let mut backoff_exponent = mac::MIN_BACKOFF_EXPONENT;
+        for _ in 0..max_backoffs {
+            let backoff_periods = mac::random_backoff_periods(rng, backoff_exponent);
+            delay.delay_us(backoff_periods * mac::UNIT_BACKOFF_PERIOD * mac::SYMBOL_PERIOD_US);
+            if self.is_channel_clear()? {
+                return self.send_with_cca(data);
+            }
+            backoff_exponent = min(backoff_exponent + 1, mac::MAX_BACKOFF_EXPONENT);
+        }
+        Err(RadioError::ChannelBusy)
+    }
+
+    /// Write bytes into the TX FIFO (at most 128), without flushing the FIFO
+    /// first or starting the transmission.  This allows a frame to be written
+    /// into the FIFO in several parts.
+    pub fn write_tx_fifo_bytes(&mut self, data: &[u8]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        if data.len() > 128 {
+            return Err(RadioError::InvalidBufferLenth { expected: 128, found: data.len() });
+        }
+
+        let mut buffer = [0u8; 129];
+        buffer[0] = Strobe::TxFifo.opcode();
+        buffer[1..(1+data.len())].copy_from_slice(data);
+        self.spi.transfer_in_place(&mut buffer[..(1+data.len())]).map_err(RadioError::SpiError)?;
         Ok(buffer[0].into())
     }
 
+    /// Write a frame into the TX FIFO, preceded by its length byte (the
+    /// payload length plus the 2 byte FCS appended by the radio when AUTOCRC
+    /// is enabled).  The payload may be at most 125 bytes.
+    pub fn write_tx_fifo_with_length_byte(&mut self, payload: &[u8]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let max_payload = frame::MAX_FRAME_LENGTH - frame::FCS_LENGTH;
+        if payload.len() > max_payload {
+            return Err(RadioError::InvalidBufferLenth { expected: max_payload, found: payload.len() });
+        }
+
+        let mut buffer = [0u8; 128];
+        buffer[0] = (payload.len() + frame::FCS_LENGTH) as u8;
+        buffer[1..(1+payload.len())].copy_from_slice(payload);
+        self.write_tx_fifo_bytes(&buffer[..(1+payload.len())])
+    }
+
+    /// Return the sequence number for the next data frame, incrementing it
+    /// (wrapping from 255 back to 0)
+    pub fn next_sequence_number(&mut self) -> u8 {
+        let sequence_number = self.sequence_number;
+        self.sequence_number = self.sequence_number.wrapping_add(1);
+        sequence_number
+    }
+
+    /// Set the sequence number of the next data frame (e.g. to resume the
+    /// sequence when rejoining a PAN)
+    pub fn set_sequence_number(&mut self, sequence_number: u8) {
+        self.sequence_number = sequence_number;
+    }
+
+    /// Build a frame, using the radio's next sequence number unless the
+    /// builder sets one explicitly
+    pub fn build_frame(&mut self, builder: TxFrameBuilder) -> Result<TxFrame, RadioError<SPIE, GPIOE>> {
+        let builder = if builder.has_sequence_number() {
+            builder
+        } else {
+            builder.sequence_number(self.next_sequence_number())
+        };
+        builder.build().map_err(RadioError::InvalidFrame)
+    }
+
+    /// Send a beacon frame from this node, using the PAN ID and short address
+    /// in RAM as the source (or the IEEE address if the short address is
+    /// 0xFFFE or 0xFFFF, i.e. none has been allocated).
+    pub fn send_beacon(&mut self, builder: BeaconFrameBuilder) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let pan_id = PanId(self.read_pan_id()?);
+        let short_address = self.read_short_address()?;
+        let source = if short_address >= 0xFFFE {
+            Address::Extended(IEEEAddress(self.read_ieee_address()?))
+        } else {
+            Address::Short(ShortAddress(short_address))
+        };
+
+        let frame = builder.build(self.beacon_sequence_number, pan_id, source)
+            .map_err(RadioError::InvalidFrame)?;
+        self.beacon_sequence_number = self.beacon_sequence_number.wrapping_add(1);
+        self.send_no_cca(&frame)
+    }
+
     /// Send Data
+    /// 
+    /// Returns FifoUnderflow if the TX FIFO underflowed while it was being
+    /// refilled, flush it with clear_tx_underflow to recover, and Timeout if
+    /// the transmission did not start before the next chunk was due (e.g. the
+    /// channel stayed busy with CCA).
+    /// 
+    /// Note: As with send_frame, a transmission without CCA can not be started
+    /// while receiving, and one with CCA only while receiving.
     pub fn send(&mut self, data: &[u8], cca: bool, delay: &mut dyn DelayNs) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        let _ = self.flush_tx_fifo()?;
+        self.check_can_transmit(cca)?;
+        let mut status = self.flush_tx_fifo()?;
 
-        for start in 0..(data.len()/128) {
+        let mut chunks = data.chunks(128).peekable();
+        while let Some(chunk) = chunks.next() {
             let mut data_buffer = [0u8; 129];
             data_buffer[0] = Strobe::TxFifo.opcode();
-            data_buffer[1..129].copy_from_slice(&data[start*128..(start+1)*128]);
-            self.spi.write(&data_buffer).map_err(RadioError::SpiError)?;
+            data_buffer[1..(1+chunk.len())].copy_from_slice(chunk);
+            self.spi.write(&data_buffer[..(1+chunk.len())]).map_err(RadioError::SpiError)?;
 
-            let buffer = if cca { [Strobe::EnableTxCCA.opcode()] } else { [Strobe::EnableTx.opcode()] };
-            self.spi.write(&buffer).map_err(RadioError::SpiError)?;
+            let mut buffer = if cca { [Strobe::EnableTxCCA.opcode()] } else { [Strobe::EnableTx.opcode()] };
+            self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
+            status = buffer[0].into();
+            self.state = RadioState::Transmitting;
+            // The previous chunk was not refilled in time
+            if status.tx_underflow {
+                return Err(RadioError::FifoUnderflow);
+            }
 
-            while self.sfd.is_low().map_err(RadioError::GpioError)? {
-                delay.delay_us(100);
+            // Wait for the transmission to start before refilling the TX FIFO
+            if chunks.peek().is_some() {
+                let mut waited_us = 0;
+                while !self.sfd_active()? {
+                    if waited_us >= TX_COMPLETE_TIMEOUT_US {
+                        return Err(RadioError::Timeout);
+                    }
+                    delay.delay_us(LISTEN_POLL_US);
+                    waited_us += LISTEN_POLL_US;
+                }
             }
         }
 
-        let final_frame = &data[data.len()/128..];
-        let mut data_buffer = [0u8; 129];
-        data_buffer[0] = Strobe::TxFifo.opcode();
-        data_buffer[1..(1+final_frame.len())].copy_from_slice(final_frame);
-        self.spi.write(&data_buffer[..(1+final_frame.len())]).map_err(RadioError::SpiError)?;
-
-        let mut buffer = if cca { [Strobe::EnableTxCCA.opcode()] } else { [Strobe::EnableTx.opcode()] };
-        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
-
-        Ok(buffer[0].into())
+        Ok(status)
     }
 
     /// Read the Data from the TX FIFO (Presumably only used for testing)
@@ -295,38 +1006,407 @@ impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
     /// Check if Data is Ready (the FIFO pin can also be configured as an
     /// interrupt, which completes the same functionality as this)
     pub fn data_ready(&mut self) -> Result<bool, RadioError<SPIE, GPIOE>> {
-        self.fifo.is_high().map_err(RadioError::GpioError)
+        pin_active(&mut self.fifo, self.io_configuration.fifo_polarity).map_err(RadioError::GpioError)
+    }
+
+    /// Check if the FIFOP pin is asserted, i.e. the RX FIFO holds more than
+    /// the FIFOP threshold or a complete frame (always false with NoPin).
+    pub fn fifop_asserted(&mut self) -> Result<bool, RadioError<SPIE, GPIOE>> {
+        pin_active(&mut self.fifop, self.io_configuration.fifop_polarity).map_err(RadioError::GpioError)
+    }
+
+    // Whether the SFD pin is asserted (a frame is being sent or received)
+    fn sfd_active(&mut self) -> Result<bool, RadioError<SPIE, GPIOE>> {
+        pin_active(&mut self.sfd, self.io_configuration.sfd_polarity).map_err(RadioError::GpioError)
     }
 
     /// Start Receiving Data
+    /// 
+    /// Note: This fails if the radio is already receiving, use enable_rx to
+    /// (re-)enable RX mode unconditionally.
     pub fn start_receiving(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        let mut buffer = [Strobe::EnableRx.opcode()];
-        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
-        Ok(buffer[0].into())
+        if self.state == RadioState::Receiving {
+            return Err(RadioError::InvalidState { current: self.state, required: RadioState::Idle });
+        }
+        self.enable_rx()
+    }
+
+    /// The state of the radio, as tracked from the strobes sent by the driver
+    pub fn state(&self) -> RadioState {
+        self.state
+    }
+
+    /// Whether the radio is powered up (the crystal oscillator is running)
+    pub fn is_powered_up(&self) -> bool {
+        self.state != RadioState::PoweredDown
+    }
+
+    fn check_can_transmit(&self, cca: bool) -> Result<(), RadioError<SPIE, GPIOE>> {
+        // CCA is only valid in RX mode, outside of it STXONCCA is ignored
+        if cca && self.state != RadioState::Receiving {
+            return Err(RadioError::InvalidState { current: self.state, required: RadioState::Receiving });
+        }
+        if !cca && self.state == RadioState::Receiving {
+            return Err(RadioError::InvalidState { current: self.state, required: RadioState::Idle });
+        }
+        Ok(())
     }
 
     /// Read data from the RX FIFO (equal to the length of the buffer) into a
     /// given buffer, returning the radio status
+    /// 
+    /// Note: The RX FIFO is 128 bytes so at most the first 128 bytes of the
+    /// buffer are filled.
     pub fn receive(&mut self, buffer: &mut [u8]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        let data_len = min(129, buffer.len());
-        let mut read_buffer = [0u8; 129];
+        let data_len = min(RX_FIFO_SIZE, buffer.len());
+        let mut read_buffer = [0u8; RX_FIFO_SIZE + 1];
         read_buffer[0] = Strobe::RxFifo.opcode();
         self.spi.transfer_in_place(&mut read_buffer[..=data_len]).map_err(RadioError::SpiError)?;
-        buffer[..].copy_from_slice(&read_buffer[1..=data_len]);
-        Ok(buffer[0].into())
+        buffer[..data_len].copy_from_slice(&read_buffer[1..=data_len]);
+        Ok(read_buffer[0].into())
     }
 
-    /// Not sure why you would want to do this, but the use case is outlined in
-    /// the datasheet for testing, so this is included for continuity sake
-    pub fn write_rx_fifo(&mut self, data: [u8; 128]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        self.write_ram(Ram::RxFifo, &data)
+    /// Read the next frame from the RX FIFO, reading its length byte first
+    /// and then exactly that many bytes.
+    /// 
+    /// Returns FifoOverflow if the RX FIFO has overflowed (see
+    /// rx_fifo_is_overflowed), flush it with flush_rx_fifo to recover.
+    /// 
+    /// Note: The LQI and CRC status are taken from the last byte of the frame,
+    /// which only holds them when AUTOCRC is enabled.
+    pub fn receive_frame(&mut self) -> Result<RxFrame, RadioError<SPIE, GPIOE>> {
+        if self.rx_fifo_is_overflowed()? {
+            return Err(RadioError::FifoOverflow);
+        }
+        let mut length = [0u8];
+        let _ = self.receive(&mut length)?;
+        let length = (length[0] & 0x7F) as usize;
+
+        let mut buffer = [0u8; RX_FIFO_SIZE];
+        let _ = self.receive(&mut buffer[..length])?;
+        let frame = RxFrame::from_bytes(&buffer[..length]).map_err(RadioError::InvalidFrame)?;
+        self.last_lqi = frame.lqi;
+        Ok(frame)
     }
 
-    /// Read the entire contents of the RX FIFO.  In general, receive() should be
-    /// used in place of this function, however it may be useful for debugging
-    /// purposes.
-    pub fn read_rx_fifo(&mut self) -> Result<[u8; 128], RadioError<SPIE, GPIOE>> {
-        let mut buffer = [0u8; 128];
+    /// The current RSSI (RSSI.RSSI_VAL) and the link quality indicator of
+    /// the last frame read by receive_frame or receive_frame_bounded (0 if
+    /// none has been read).
+    /// 
+    /// Note: The RSSI is read from the radio, so it is only the signal
+    /// strength of the last frame if read straight after receiving it (see
+    /// RxFrame::rssi for the RSSI captured with the frame).
+    pub fn last_frame_rssi_lqi(&mut self) -> Result<(i8, u8), RadioError<SPIE, GPIOE>> {
+        Ok((self.read_rssi_value()?, self.last_lqi))
+    }
+
+    /// Read the next frame from the RX FIFO into a BoundedVec (without the
+    /// length byte), reading its length byte first and then exactly that
+    /// many bytes.
+    /// 
+    /// Note: A frame longer than N bytes is still read out of the FIFO (and
+    /// dropped) so the next frame can be received.  As with receive_frame,
+    /// FifoOverflow is returned if the RX FIFO has overflowed.
+    pub fn receive_frame_bounded<const N: usize>(&mut self) -> Result<BoundedVec<u8, N>, RadioError<SPIE, GPIOE>> {
+        if self.rx_fifo_is_overflowed()? {
+            return Err(RadioError::FifoOverflow);
+        }
+        let mut length = [0u8];
+        let _ = self.receive(&mut length)?;
+        let length = (length[0] & 0x7F) as usize;
+
+        let mut buffer = [0u8; RX_FIFO_SIZE];
+        let _ = self.receive(&mut buffer[..length])?;
+        if let Some(&lqi_crc) = buffer[..length].last() {
+            (self.last_lqi, _) = metadata::parse_lqi_crc_byte(lqi_crc);
+        }
+        let mut frame = BoundedVec::new();
+        frame.extend_from_slice(&buffer[..length])
+            .map_err(|_| RadioError::BufferTooSmall { needed: length, capacity: N })?;
+        Ok(frame)
+    }
+
+    /// Read exactly buf.len() bytes (at most 128) from the RX FIFO
+    pub fn read_rx_fifo_bytes(&mut self, buf: &mut [u8]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        if buf.len() > RX_FIFO_SIZE {
+            return Err(RadioError::InvalidBufferLenth { expected: RX_FIFO_SIZE, found: buf.len() });
+        }
+        self.receive(buf)
+    }
+
+    /// Perform an active scan (802.15.4 §7.5.2.1.2).  On each channel a beacon
+    /// request is sent and beacons are collected for
+    /// aBaseSuperframeDuration * (2^scan_duration + 1) symbols.
+    /// 
+    /// Returns the PAN descriptors of the beacons received (at most 16,
+    /// one per coordinator and channel), sorted by link quality (highest
+    /// first).  The PAN ID in RAM is restored once the scan is complete.
+    /// Returns ChannelBusy if a beacon request could not be sent.
+    /// 
+    /// Note: This assumes AUTOCRC is enabled so the CRC status of received
+    /// frames is available.
+    pub fn active_scan(&mut self, channels: &[Channel], scan_duration: u8, delay: &mut dyn DelayNs) -> Result<BoundedVec<PanDescriptor, MAX_SCAN_RESULTS>, RadioError<SPIE, GPIOE>> {
+        let pan_id = self.read_pan_id()?;
+        // Accept beacons from any PAN while scanning
+        let result = self.set_pan_id(PanId::BROADCAST.0)
+            .and_then(|_| self.collect_beacons(channels, scan_duration, delay));
+
+        // RX is disabled and the PAN ID restored whether or not the scan
+        // succeeded
+        let restored = self.disable_rx_tx().and_then(|_| self.set_pan_id(pan_id));
+        let mut descriptors = result?;
+        restored?;
+        descriptors.sort_unstable_by_key(|descriptor| core::cmp::Reverse(descriptor.link_quality));
+        Ok(descriptors)
+    }
+
+    // Send a beacon request on each channel and collect the beacons received
+    // (see active_scan)
+    fn collect_beacons(&mut self, channels: &[Channel], scan_duration: u8, delay: &mut dyn DelayNs) -> Result<BoundedVec<PanDescriptor, MAX_SCAN_RESULTS>, RadioError<SPIE, GPIOE>> {
+        let mut descriptors = BoundedVec::<PanDescriptor, MAX_SCAN_RESULTS>::new();
+        let mut frame = [0u8; 128];
+
+        for channel in channels {
+            let _ = self.write_channel(*channel)?;
+            let _ = self.enable_rx()?;
+            delay.delay_us(RSSI_VALID_DELAY_US);
+            let sequence_number = self.next_sequence_number();
+            let _ = self.send_with_cca_checked(&frame::beacon_request_frame(sequence_number))?;
+
+            let mut remaining_us = mac::scan_duration_symbols(scan_duration) * mac::SYMBOL_PERIOD_US;
+            while let Some(length) = self.listen_for_frame(&mut frame, &mut remaining_us, delay)? {
+                let (link_quality, _) = metadata::parse_lqi_crc_byte(frame[length - 1]);
+                let Some(descriptor) = ParsedFrame::parse(&frame[..(length - 2)])
+                    .and_then(|parsed| PanDescriptor::from_beacon(&parsed, *channel, link_quality)) else {
+                    continue;
+                };
+                let duplicate = descriptors.iter().any(|found| {
+                    found.channel == descriptor.channel &&
+                    found.coordinator_pan == descriptor.coordinator_pan &&
+                    found.coordinator_address == descriptor.coordinator_address
+                });
+                if !duplicate {
+                    // Beacons beyond the first 16 are dropped
+                    let _ = descriptors.push(descriptor);
+                }
+            }
+            let _ = self.flush_rx_fifo()?;
+        }
+        Ok(descriptors)
+    }
+
+    /// Measure the energy on each of the given channels (at most 16).  On each
+    /// channel RX is enabled for dwell_us (which should be at least 128us, 8
+    /// symbol periods, for the RSSI to be valid) before the RSSI is read.
+    /// 
+    /// Returns the (channel, RSSI) pairs in the order the channels were given.
+    /// The radio is returned to its original channel afterwards, with RX
+    /// re-enabled if it was receiving.
+    pub fn energy_scan(&mut self, channels: &[u8], dwell_us: u32, delay: &mut dyn DelayNs) -> Result<BoundedVec<(u8, i8), { Channel::COUNT }>, RadioError<SPIE, GPIOE>> {
+        if channels.len() > Channel::COUNT {
+            return Err(RadioError::InvalidBufferLenth { expected: Channel::COUNT, found: channels.len() });
+        }
+        if let Some(channel) = channels.iter().find(|channel| Channel::try_from(**channel).is_err()) {
+            return Err(RadioError::InvalidChannel(*channel));
+        }
+
+        let receiving = self.state == RadioState::Receiving;
+        let mut frequency_synthesis = FrequencySynthesizerRegisterBuilder::default().build().unwrap();
+        let _ = self.read_register(&mut frequency_synthesis)?;
+
+        let mut energies = BoundedVec::new();
+        for channel in channels {
+            let _ = self.write_channel(Channel::try_from(*channel).unwrap())?;
+            let _ = self.enable_rx()?;
+            delay.delay_us(dwell_us);
+            let rssi = self.read_rssi_value()?;
+            let _ = self.disable_rx_tx()?;
+            // At most Channel::COUNT channels are scanned
+            let _ = energies.push((*channel, rssi));
+        }
+
+        let _ = self.write_register(&frequency_synthesis)?;
+        if receiving {
+            let _ = self.enable_rx()?;
+        }
+        Ok(energies)
+    }
+
+    /// Measure the energy on every 2.4 GHz channel (11-26), see energy_scan
+    pub fn scan_all_channels(&mut self, dwell_us: u32, delay: &mut dyn DelayNs) -> Result<BoundedVec<(u8, i8), { Channel::COUNT }>, RadioError<SPIE, GPIOE>> {
+        let mut channels = [0u8; Channel::COUNT];
+        for (number, channel) in channels.iter_mut().zip(Channel::all()) {
+            *number = channel.number();
+        }
+        self.energy_scan(&channels, dwell_us, delay)
+    }
+
+    /// Search for the coordinator of a PAN the radio has lost its association
+    /// with (an orphan scan).  An orphan notification is broadcast on each
+    /// channel (starting at channel 11) and the radio listens for scan_duration
+    /// for a coordinator realignment frame in response.
+    /// 
+    /// Returns the PAN parameters from the first realignment frame received, or
+    /// None if no coordinator responded on any channel.  Returns ChannelBusy
+    /// if an orphan notification could not be sent.
+    pub fn orphan_scan(&mut self, scan_duration: u8, delay: &mut dyn DelayNs) -> Result<Option<PanDescriptor>, RadioError<SPIE, GPIOE>> {
+        let source = IEEEAddress(self.read_ieee_address()?);
+        let mut frame = [0u8; 128];
+
+        for channel in Channel::all() {
+            let _ = self.write_channel(channel)?;
+            let _ = self.enable_rx()?;
+            delay.delay_us(RSSI_VALID_DELAY_US);
+            let sequence_number = self.next_sequence_number();
+            let _ = self.send_with_cca_checked(&frame::orphan_notification_frame(sequence_number, source))?;
+
+            let mut remaining_us = mac::scan_duration_symbols(scan_duration) * mac::SYMBOL_PERIOD_US;
+            while let Some(length) = self.listen_for_frame(&mut frame, &mut remaining_us, delay)? {
+                let (link_quality, _) = metadata::parse_lqi_crc_byte(frame[length - 1]);
+                if let Some(descriptor) = ParsedFrame::parse(&frame[..(length - 2)])
+                    .and_then(|parsed| PanDescriptor::from_realignment(&parsed, link_quality)) {
+                    let _ = self.disable_rx_tx()?;
+                    let _ = self.flush_rx_fifo()?;
+                    return Ok(Some(descriptor));
+                }
+            }
+            let _ = self.flush_rx_fifo()?;
+        }
+
+        let _ = self.disable_rx_tx()?;
+        Ok(None)
+    }
+
+    /// Join a PAN.  Every channel is scanned, measuring its energy and
+    /// collecting beacons (in response to a beacon request), then an
+    /// association request is sent to the coordinator on the lowest energy
+    /// channel with a beacon from a matching PAN (or from any PAN if pan_id
+    /// is None) that is permitting association.
+    /// 
+    /// On success the PAN ID and allocated short address are written into RAM
+    /// for address recognition, otherwise the PAN ID in RAM is restored.
+    /// Returns ChannelBusy if a beacon request, the association request or
+    /// the following data request could not be sent.
+    /// 
+    /// Note: This assumes AUTOCRC is enabled so the CRC status of received
+    /// frames is available.
+    pub fn scan_and_associate(&mut self, pan_id: Option<PanId>, delay: &mut dyn DelayNs) -> Result<AssociationResult, RadioError<SPIE, GPIOE>> {
+        let previous_pan_id = self.read_pan_id()?;
+        self.association_status = AssociationStatus::Associating;
+        let result = self.associate(pan_id, delay);
+        if self.association_status != AssociationStatus::Associating {
+            return result;
+        }
+
+        self.association_status = AssociationStatus::NotAssociated;
+        let restored = self.set_pan_id(previous_pan_id);
+        let result = result?;
+        restored?;
+        Ok(result)
+    }
+
+    /// Whether the radio has joined a PAN (by scan_and_associate, or by being
+    /// configured as the PAN coordinator)
+    pub fn association_status(&self) -> &AssociationStatus {
+        &self.association_status
+    }
+
+    fn associate(&mut self, pan_id: Option<PanId>, delay: &mut dyn DelayNs) -> Result<AssociationResult, RadioError<SPIE, GPIOE>> {
+        let source = IEEEAddress(self.read_ieee_address()?);
+        let mut modem_control = ModemControlRegister0Builder::default().build().unwrap();
+        let _ = self.read_register(&mut modem_control)?;
+        let mut frame = [0u8; 128];
+
+        let energies = self.scan_all_channels(RSSI_VALID_DELAY_US, delay)?;
+        let mut channels = [Channel::default(); Channel::COUNT];
+        for (slot, channel) in channels.iter_mut().zip(Channel::all()) {
+            *slot = channel;
+        }
+        let descriptors = self.active_scan(&channels, ASSOCIATION_SCAN_DURATION, delay)?;
+
+        // The coordinator on the channel with the lowest energy
+        let coordinator = descriptors.iter()
+            .filter(|descriptor| descriptor.association_permit())
+            .filter(|descriptor| !pan_id.is_some_and(|pan_id| pan_id != descriptor.coordinator_pan))
+            .min_by_key(|descriptor| {
+                energies.iter()
+                    .find(|(channel, _)| *channel == descriptor.channel.number())
+                    .map_or(i8::MAX, |(_, energy)| *energy)
+            });
+        let Some(&coordinator) = coordinator else {
+            return Ok(AssociationResult::TimedOut);
+        };
+
+        let _ = self.write_channel(coordinator.channel)?;
+        let _ = self.set_pan_id(coordinator.coordinator_pan.0)?;
+        let _ = self.enable_rx()?;
+        delay.delay_us(RSSI_VALID_DELAY_US);
+        let sequence_number = self.next_sequence_number();
+        let request = frame::association_request_frame(
+            sequence_number,
+            coordinator.coordinator_pan,
+            coordinator.coordinator_address,
+            source,
+            ASSOCIATION_CAPABILITY,
+        );
+        let _ = self.send_with_cca_checked(&request)?;
+        // Allow for the request itself (and its preamble) to be sent
+        let ack_timeout_us = (request.len() as u32 + PREAMBLE_AND_SFD_BYTES) * BYTE_PERIOD_US
+            + mac::ACK_WAIT_DURATION * mac::SYMBOL_PERIOD_US;
+        if !self.listen_for_ack(sequence_number, ack_timeout_us, delay)? {
+            return Ok(AssociationResult::TimedOut);
+        }
+
+        // The coordinator holds the response until it is polled with a data
+        // request after macResponseWaitTime
+        delay.delay_us(mac::RESPONSE_WAIT_TIME * mac::SYMBOL_PERIOD_US);
+        let _ = self.flush_rx_fifo()?;
+        let data_request = frame::data_request_frame(
+            self.next_sequence_number(),
+            coordinator.coordinator_pan,
+            coordinator.coordinator_address,
+            source,
+        );
+        let _ = self.send_with_cca_checked(&data_request)?;
+
+        let mut remaining_us = mac::RESPONSE_WAIT_TIME * mac::SYMBOL_PERIOD_US;
+        while let Some(length) = self.listen_for_frame(&mut frame, &mut remaining_us, delay)? {
+            let Some(parsed) = ParsedFrame::parse(&frame[..(length - 2)]) else {
+                continue;
+            };
+            let payload = parsed.payload;
+            if parsed.frame_type != FrameType::MacCommand || payload.len() < 4 || payload[0] != MacCommand::AssociationResponse as u8 {
+                continue;
+            }
+            if parsed.ack_request && !modem_control.auto_ack {
+                let _ = self.acknowledge_cleared()?;
+            }
+            if payload[3] != mac::ASSOCIATION_SUCCESSFUL {
+                return Ok(AssociationResult::Denied);
+            }
+            let short_address = u16::from_le_bytes([payload[1], payload[2]]);
+            let _ = self.set_short_address(short_address)?;
+            self.association_status = AssociationStatus::Associated {
+                pan_id: coordinator.coordinator_pan,
+                short_addr: ShortAddress(short_address),
+            };
+            return Ok(AssociationResult::Success(ShortAddress(short_address)));
+        }
+
+        Ok(AssociationResult::TimedOut)
+    }
+
+    /// Not sure why you would want to do this, but the use case is outlined in
+    /// the datasheet for testing, so this is included for continuity sake
+    pub fn write_rx_fifo(&mut self, data: [u8; 128]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.write_ram(Ram::RxFifo, &data)
+    }
+
+    /// Read the entire contents of the RX FIFO.  In general, receive() should be
+    /// used in place of this function, however it may be useful for debugging
+    /// purposes.
+    pub fn read_rx_fifo(&mut self) -> Result<[u8; 128], RadioError<SPIE, GPIOE>> {
+        let mut buffer = [0u8; 128];
         let _ = self.read_ram(Ram::RxFifo, &mut buffer)?;
         Ok(buffer)
     }
@@ -357,21 +1437,21 @@ impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
 
     /// Set the 16-bit short address used for address recognition.
     /// 
-    /// Note: The value is passed in as a u16 and converted to big
-    /// endian bytes.
+    /// Note: The value is passed in as a u16 and stored in little endian
+    /// byte order (as in 802.15.4 frames).
     pub fn set_short_address(&mut self, value: u16) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        let bytes = value.to_be_bytes();
+        let bytes = value.to_le_bytes();
         self.write_ram(Ram::ShortAddress, &bytes)
     }
 
     /// Read the 16-bit short address for address recognition.
     /// 
-    /// Note: The value returned is a u16 converted from big endian
-    /// bytes.
+    /// Note: The value returned is a u16 converted from the little endian
+    /// bytes in RAM.
     pub fn read_short_address(&mut self) -> Result<u16, RadioError<SPIE, GPIOE>> {
         let mut buffer = [0u8; 2];
         let _ = self.read_ram(Ram::ShortAddress, &mut buffer)?;
-        Ok(u16::from_be_bytes(buffer))
+        Ok(u16::from_le_bytes(buffer))
     }
 
     /// Set the 64-bit IEEE address of the current node, used for
@@ -390,38 +1470,274 @@ impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
 
     /// Set the 16-bit PAN identifier for address recognition.
     /// 
-    /// Note: The value is passed in as a u16 and converted to big
-    /// endian bytes.
+    /// Note: The value is passed in as a u16 and stored in little endian
+    /// byte order (as in 802.15.4 frames).
     pub fn set_pan_id(&mut self, value: u16) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        let bytes = value.to_be_bytes();
+        let bytes = value.to_le_bytes();
         self.write_ram(Ram::PanID, &bytes)
     }
 
     /// Read the 16-bit PAN identifier for address recognition.
     /// 
-    /// Note: The value returned is a u16 converted from big endian
-    /// bytes.
+    /// Note: The value returned is a u16 converted from the little endian
+    /// bytes in RAM.
     pub fn read_pan_id(&mut self) -> Result<u16, RadioError<SPIE, GPIOE>> {
         let mut buffer = [0u8; 2];
         let _ = self.read_ram(Ram::PanID, &mut buffer)?;
-        Ok(u16::from_be_bytes(buffer))
+        Ok(u16::from_le_bytes(buffer))
+    }
+
+    /// Set the transmit power of the radio (only the PA fields of TXCTRL are
+    /// changed)
+    pub fn set_tx_power(&mut self, level: TxPowerLevel) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        if !level.is_valid() {
+            return Err(RadioError::InvalidConfiguration("Invalid TX power. PA_LEVEL<=31, PA_CURRENT<=7"));
+        }
+
+        let mut transmit_control = TransmitControlRegisterBuilder::default().build().unwrap();
+        self.read_register_for_update(&mut transmit_control)?;
+        transmit_control.pa_level = level.pa_level();
+        transmit_control.pa_current = level.pa_current();
+        self.write_register(&transmit_control)
+    }
+
+    /// Apply the run time radio settings (channel, output power and CCA),
+    /// only writing FSCTRL, TXCTRL, RSSI and MDMCTRL0 (much faster than a full
+    /// configure).
+    pub fn apply_radio_config(&mut self, config: RadioConfig, delay: &mut dyn DelayNs) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        if config.cca_mode == 0 || config.cca_mode > 3 {
+            return Err(RadioError::InvalidConfiguration("Invalid CCA_MODE. 1<=CCA_MODE<=3"));
+        }
+
+        let _ = self.write_channel(config.channel)?;
+
+        let mut transmit_control = TransmitControlRegisterBuilder::default().build().unwrap();
+        self.read_register_for_update(&mut transmit_control)?;
+        transmit_control.pa_level = config.output_power.pa_level();
+        self.write_register(&transmit_control)?;
+
+        let rssi = RSSIRegisterBuilder::default()
+            .cca_threshold(config.cca_threshold)
+            .build()
+            .map_err(|e| RadioError::InvalidConfiguration(e.message()))?;
+        self.write_register(&rssi)?;
+
+        let mut modem_config = ModemControlRegister0Builder::default().build().unwrap();
+        self.read_register_for_update(&mut modem_config)?;
+        modem_config.cca_mode = config.cca_mode;
+        self.write_register(&modem_config)?;
+
+        delay.delay_us(REGISTER_WRITE_DELAY_US);
+        if self.read_radio_config()? != config {
+            return Err(RadioError::FailedConfiguration("Configuration of Radio Failed"));
+        }
+        self.status()
+    }
+
+    /// Read the current run time radio settings (channel, output power and CCA).
+    /// 
+    /// Note: The output power is rounded down to the nearest entry of the
+    /// output power table.
+    pub fn read_radio_config(&mut self) -> Result<RadioConfig, RadioError<SPIE, GPIOE>> {
+        let mut frequency_synthesizer = FrequencySynthesizerRegisterBuilder::default().build().unwrap();
+        let _ = self.read_register(&mut frequency_synthesizer)?;
+        let channel = frequency_synthesizer.frequency.to_channel()
+            .ok_or(RadioError::InvalidConfiguration("Frequency is not an IEEE 802.15.4 channel"))?;
+
+        let mut transmit_control = TransmitControlRegisterBuilder::default().build().unwrap();
+        let _ = self.read_register(&mut transmit_control)?;
+
+        let mut rssi = RSSIRegisterBuilder::default().build().unwrap();
+        let _ = self.read_register(&mut rssi)?;
+
+        let mut modem_config = ModemControlRegister0Builder::default().build().unwrap();
+        let _ = self.read_register(&mut modem_config)?;
+
+        Ok(RadioConfig {
+            channel,
+            output_power: OutputPower::from_pa_level(transmit_control.pa_level),
+            cca_threshold: rssi.cca_threshold,
+            cca_mode: modem_config.cca_mode,
+        })
+    }
+
+    /// Apply a receive (address recognition) filter.
+    /// 
+    /// Address recognition is disabled while the addresses in RAM are
+    /// written and verified, then re-enabled (if requested) once all of
+    /// them match, so a failure part way through never leaves the radio
+    /// filtering on a partially updated set of addresses.
+    pub fn apply_receive_filter(&mut self, filter: ReceiveFilter, delay: &mut dyn DelayNs) -> Result<(), RadioError<SPIE, GPIOE>> {
+        let mut modem_config = ModemControlRegister0Builder::default().build().unwrap();
+        let _ = self.read_register(&mut modem_config)?;
+        if modem_config.adr_decode {
+            modem_config.adr_decode = false;
+            self.write_register(&modem_config)?;
+        }
+
+        self.set_pan_id(filter.pan_id.0)?;
+        self.set_short_address(filter.short_addr.0)?;
+        self.set_ieee_address(filter.ieee_addr.0)?;
+        delay.delay_us(RAM_WRITE_DELAY_US);
+        if self.read_pan_id()? != filter.pan_id.0 {
+            return Err(RadioError::FailedConfiguration("Configuration of Pan ID Failed"));
+        }
+        if self.read_short_address()? != filter.short_addr.0 {
+            return Err(RadioError::FailedConfiguration("Configuration of Short Address Failed"));
+        }
+        if self.read_ieee_address()? != filter.ieee_addr.0 {
+            return Err(RadioError::FailedConfiguration("Configuration of IEEE Address Failed"));
+        }
+        self.set_bcn_accept(filter.accept_beacons)?;
+
+        if filter.address_decode {
+            modem_config.adr_decode = true;
+            self.write_register(&modem_config)?;
+            delay.delay_us(REGISTER_WRITE_DELAY_US);
+            let mut found_modem_config = ModemControlRegister0Builder::default().build().unwrap();
+            let _ = self.read_register(&mut found_modem_config)?;
+            if found_modem_config != modem_config {
+                return Err(RadioError::FailedConfiguration("Configuration of Modem Failed"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Apply an in-line security configuration.
+    /// 
+    /// In-line security is disabled while the keys and cleartext lengths are
+    /// written and verified, and only enabled (with the requested mode) once
+    /// all of them match.  The TX key is stored in key slot 1 and the RX key
+    /// in key slot 0.
+    pub fn apply_security_configuration(&mut self, sec: SecureConfiguration, delay: &mut dyn DelayNs) -> Result<(), RadioError<SPIE, GPIOE>> {
+        self.write_security_configuration(sec, None, delay)
+    }
+
+    /// Write a security configuration (and the TX and RX nonces, if given)
+    /// with in-line security disabled, enabling it only once everything has
+    /// been verified
+    fn write_security_configuration(&mut self, sec: SecureConfiguration, nonces: Option<(Nonce, Nonce)>, delay: &mut dyn DelayNs) -> Result<(), RadioError<SPIE, GPIOE>> {
+        let lengths = SecurityControlRegister1Builder::default()
+            .sec_txl(sec.tx_cleartext_bytes)
+            .sec_rxl(sec.rx_cleartext_bytes)
+            .build()
+            .map_err(|e| RadioError::InvalidConfiguration(e.message()))?;
+
+        let mut security_control = SecurityControlRegister0Builder::default().build().unwrap();
+        self.read_register_for_update(&mut security_control)?;
+        if security_control.sec_mode != SecurityMode::Disabled.sec_mode() {
+            security_control.sec_mode = SecurityMode::Disabled.sec_mode();
+            self.write_register(&security_control)?;
+        }
+
+        self.set_key_1(sec.tx_key.0)?;
+        self.set_key_0(sec.rx_key.0)?;
+        delay.delay_us(RAM_WRITE_DELAY_US);
+        if self.read_key_1()? != sec.tx_key.0 {
+            return Err(RadioError::FailedConfiguration("Configuration of Tx Encryption Key Failed"));
+        }
+        if self.read_key_0()? != sec.rx_key.0 {
+            return Err(RadioError::FailedConfiguration("Configuration of Rx Decryption Key Failed"));
+        }
+
+        if let Some((tx_nonce, rx_nonce)) = nonces {
+            self.write_tx_nonce(&tx_nonce)?;
+            self.write_rx_nonce(&rx_nonce)?;
+            delay.delay_us(RAM_WRITE_DELAY_US);
+            if &self.read_tx_nonce()? != tx_nonce.as_bytes() {
+                return Err(RadioError::FailedConfiguration("Configuration of Tx Nonce Failed"));
+            }
+            if &self.read_rx_nonce()? != rx_nonce.as_bytes() {
+                return Err(RadioError::FailedConfiguration("Configuration of Rx Nonce Failed"));
+            }
+        }
+
+        self.write_register(&lengths)?;
+        delay.delay_us(REGISTER_WRITE_DELAY_US);
+        let mut found_lengths = SecurityControlRegister1Builder::default().build().unwrap();
+        let _ = self.read_register(&mut found_lengths)?;
+        if found_lengths != lengths {
+            return Err(RadioError::FailedConfiguration("Configuration of Security Lengths Failed"));
+        }
+
+        security_control.sec_tx_key_sel = KeySlot::Key1.select_bit();
+        security_control.sec_rx_key_sel = KeySlot::Key0.select_bit();
+        security_control.sec_m = sec.security_level.sec_m();
+        security_control.sec_mode = sec.security_mode.sec_mode();
+        self.write_register(&security_control)?;
+        delay.delay_us(REGISTER_WRITE_DELAY_US);
+        let mut found_security_control = SecurityControlRegister0Builder::default().build().unwrap();
+        let _ = self.read_register(&mut found_security_control)?;
+        if found_security_control != security_control {
+            return Err(RadioError::FailedConfiguration("Configuration of Security Mode Failed"));
+        }
+
+        Ok(())
+    }
+
+    /// Start checking received frame counters for replays (see check_replay)
+    #[cfg(feature = "replay-protection")]
+    pub fn enable_replay_protection(&mut self) {
+        self.replay_protection = true;
+    }
+
+    /// Stop checking received frame counters and forget the counters seen so
+    /// far
+    #[cfg(feature = "replay-protection")]
+    pub fn disable_replay_protection(&mut self) {
+        self.replay_protection = false;
+        self.replay_counters.clear();
+    }
+
+    /// Check the frame counter of a received secured frame, returning false if
+    /// it is not newer than the last frame counter accepted from the same
+    /// source (i.e. the frame is a replay).  Accepted counters are stored.
+    /// 
+    /// Note: Every frame is accepted while replay protection is disabled
+    #[cfg(feature = "replay-protection")]
+    pub fn check_replay(&mut self, src: ShortAddress, frame_counter: u32) -> bool {
+        if !self.replay_protection {
+            return true;
+        }
+        self.replay_counters.check_and_update(src, frame_counter)
     }
 
     /// Encrypt 128-bits of data using AES encryption and the selected key, using
     /// data as an intermediary buffer
-    /// 
-    /// TODO: Check the timing for this
-    pub fn encrypt(&mut self, mut data: [u8; 16]) -> Result<[u8; 16], RadioError<SPIE, GPIOE>> {
+    pub fn encrypt(&mut self, mut data: [u8; 16], delay: &mut dyn DelayNs) -> Result<[u8; 16], RadioError<SPIE, GPIOE>> {
         let _ = self.write_ram(Ram::EncryptionBuffer, &data)?;
         let _ = self.aes_encryption()?;
+        self.check_enc_busy_with_timeout(ENCRYPTION_TIMEOUT_US, delay)?;
         let _ = self.read_ram(Ram::EncryptionBuffer, &mut data)?;
         Ok(data)
     }
 
-    /// Set the Nonce used in TX in-line authentication and transmitter
+    /// Encrypt a 128-bit block with stand-alone AES using the key in the given
+    /// slot (selecting it in SECCTRL0 first)
+    pub fn aes_encrypt_block(&mut self, key_slot: KeySlot, plaintext: [u8; 16], delay: &mut dyn DelayNs) -> Result<[u8; 16], RadioError<SPIE, GPIOE>> {
+        let _ = self.set_standalone_key(key_slot.select_bit())?;
+        self.encrypt(plaintext, delay)
+    }
+
+    /// Wait for the encryption module to become idle, polling every 5us for
+    /// up to timeout_us.
+    pub fn check_enc_busy_with_timeout(&mut self, timeout_us: u32, delay: &mut dyn DelayNs) -> Result<(), RadioError<SPIE, GPIOE>> {
+        let mut waited_us = 0;
+        while self.status()?.enc_busy {
+            if waited_us >= timeout_us {
+                return Err(RadioError::Timeout);
+            }
+            delay.delay_us(ENC_BUSY_POLL_US);
+            waited_us += ENC_BUSY_POLL_US;
+        }
+        Ok(())
+    }
+
+    /// Write the Nonce used in TX in-line authentication and transmitter
     /// counter for in-line encryption
-    pub fn set_tx_nonce(&mut self, value: [u8; 16]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        self.write_ram(Ram::TxNonce, &value)
+    pub fn write_tx_nonce(&mut self, nonce: &Nonce) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.write_ram(Ram::TxNonce, nonce.as_bytes())
     }
 
     /// Read the Nonce used for TX in-line authentication and transmitter
@@ -432,10 +1748,26 @@ impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
         Ok(buffer)
     }
 
-    /// Set the Nonce used for RX in-line authentication or receiver counter for
+    /// Increment the frame counter (bytes 12-15) of the TX Nonce in RAM.  This
+    /// should be called after each frame encrypted in CTR mode so that the
+    /// same counter is never used twice.
+    /// 
+    /// Note: If the counter would wrap back to 0 it is left unchanged and
+    /// a SecurityError is returned, as continuing would reuse a nonce.
+    pub fn frame_counter_increment(&mut self) -> Result<(), RadioError<SPIE, GPIOE>> {
+        let mut nonce = Nonce::from(self.read_tx_nonce()?);
+        nonce.increment_counter();
+        if nonce.counter() == 0 {
+            return Err(RadioError::SecurityError("TX Frame Counter Overflow"));
+        }
+        let _ = self.write_ram_at(Ram::TxNonce, Nonce::COUNTER_OFFSET, &nonce.as_bytes()[Nonce::COUNTER_OFFSET..])?;
+        Ok(())
+    }
+
+    /// Write the Nonce used for RX in-line authentication or receiver counter for
     /// in-line decryption
-    pub fn set_rx_nonce(&mut self, value: [u8; 16]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        self.write_ram(Ram::RxNonce, &value)
+    pub fn write_rx_nonce(&mut self, nonce: &Nonce) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.write_ram(Ram::RxNonce, nonce.as_bytes())
     }
 
     /// Read the Nonce used for RX in-line authentication or receiver counter for
@@ -447,10 +1779,18 @@ impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
     }
 
     /// Read the status of the radio
+    /// 
+    /// Note: Once a transmission has completed (or STXONCCA found the channel
+    /// busy) the chip is back in RX mode, so the tracked state is updated to
+    /// Receiving when TX_ACTIVE is read as cleared.
     pub fn status(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
         let mut buffer = [Strobe::ReadStatus.opcode()];
         self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
-        Ok(buffer[0].into())
+        let status: RadioStatus = buffer[0].into();
+        if self.state == RadioState::Transmitting && !status.tx_active {
+            self.state = RadioState::Receiving;
+        }
+        Ok(status)
     }
 
     /// Turn on the radio's crystal oscillator
@@ -460,10 +1800,70 @@ impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
         Ok(buffer[0].into())
     }
 
-    /// Calibrate the frequency for Tx.
-    pub fn calibrate_tx(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+    /// Calibrate the frequency for Tx, waiting for the PLL to lock.
+    pub fn calibrate_tx(&mut self, delay: &mut dyn DelayNs) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
         let mut buffer = [Strobe::CalibrateFrequency.opcode()];
         self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
+        self.state = RadioState::Calibrating;
+        self.wait_for_pll_lock(delay, PLL_LOCK_TIMEOUT_US)?;
+        // Only the frequency synthesizer is left running, ready for STXON
+        self.state = RadioState::Idle;
+        Ok(buffer[0].into())
+    }
+
+    /// Wait, for at most timeout_us, for the frequency synthesizer PLL to
+    /// lock (status byte LOCK bit).
+    pub fn wait_for_pll_lock(&mut self, delay: &mut dyn DelayNs, timeout_us: u32) -> Result<(), RadioError<SPIE, GPIOE>> {
+        let mut waited_us = 0;
+        loop {
+            if self.status()?.lock {
+                return Ok(());
+            }
+            if waited_us >= timeout_us {
+                return Err(RadioError::Timeout);
+            }
+            let wait_us = min(PLL_LOCK_POLL_US, timeout_us - waited_us);
+            delay.delay_us(wait_us);
+            waited_us += wait_us;
+        }
+    }
+
+    /// Recalibrate the frequency synthesizer (and receive bandpass filter) for
+    /// the current channel, then enable Rx Mode.
+    /// 
+    /// This can improve the receiver sensitivity after long periods of
+    /// operation or changes in temperature.
+    pub fn recalibrate_rx(&mut self, delay: &mut dyn DelayNs) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let _ = self.calibrate_and_wait(delay, CALIBRATION_TIMEOUT_US)?;
+        self.enable_rx()
+    }
+
+    /// Calibrate the frequency synthesizer (STXCAL) and wait, for at most
+    /// timeout_us, for the calibration to complete (FSCTRL.CAL_DONE).
+    pub fn calibrate_and_wait(&mut self, delay: &mut dyn DelayNs, timeout_us: u32) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let _ = self.calibrate_tx(delay)?;
+        let mut register = FrequencySynthesizerRegisterBuilder::default().build().unwrap();
+        let mut waited_us = 0;
+        loop {
+            let status = self.read_register(&mut register)?;
+            if register.cal_done {
+                self.state = RadioState::Idle;
+                return Ok(status);
+            }
+            if waited_us >= timeout_us {
+                return Err(RadioError::Timeout);
+            }
+            delay.delay_us(CALIBRATION_POLL_US);
+            waited_us += CALIBRATION_POLL_US;
+        }
+    }
+
+    /// Disable Rx / Tx Mode and the frequency synthesizer, leaving the crystal
+    /// oscillator running
+    pub fn disable_rx_tx(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut buffer = [Strobe::DisableRxTx.opcode()];
+        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
+        self.state = RadioState::Idle;
         Ok(buffer[0].into())
     }
 
@@ -471,6 +1871,7 @@ impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
     pub fn enable_rx(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
         let mut buffer = [Strobe::EnableRx.opcode()];
         self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
+        self.state = RadioState::Receiving;
         Ok(buffer[0].into())
     }
 
@@ -478,6 +1879,7 @@ impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
     pub fn enable_tx(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
         let mut buffer = [Strobe::EnableTx.opcode()];
         self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
+        self.state = RadioState::Transmitting;
         Ok(buffer[0].into())
     }
 
@@ -485,12 +1887,16 @@ impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
     pub fn cca_enable_tx(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
         let mut buffer = [Strobe::EnableTxCCA.opcode()];
         self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
+        self.state = RadioState::Transmitting;
         Ok(buffer[0].into())
     }
 
     /// Safely flush the rx fifo (reading a byte first)
     pub fn flush_rx_fifo(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        // TODO: Read 1 Byte from FIFO
+        // The FlushRx strobe is ignored unless a byte has been read from the
+        // RX FIFO (reading an empty FIFO is harmless)
+        let mut buffer = [Strobe::RxFifo.opcode(), 0x00];
+        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
         let mut buffer = [Strobe::FlushRx.opcode()];
         self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
         Ok(buffer[0].into())
@@ -503,89 +1909,3103 @@ impl<SPI, SPIE, SFD, GPIOE, FIFO> Radio<SPI, SPIE, SFD, GPIOE, FIFO> where
         Ok(buffer[0].into())
     }
 
-    /// Send an acknowledge frame, with pending field cleared.
-    pub fn acknowledge_cleared(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        let mut buffer = [Strobe::Ack.opcode()];
-        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
-        Ok(buffer[0].into())
+    /// Enable or disable promiscuous mode (receiving every frame regardless of
+    /// its addressing) by disabling or enabling address recognition.
+    /// 
+    /// Note: Entering promiscuous mode also disables AUTOACK so the radio does
+    /// not acknowledge other devices' traffic, it is not re-enabled on leaving.
+    pub fn set_promiscuous_mode(&mut self, enabled: bool) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut modem_control = ModemControlRegister0Builder::default().build().unwrap();
+        self.read_register_for_update(&mut modem_control)?;
+        modem_control.adr_decode = !enabled;
+        if enabled {
+            modem_control.auto_ack = false;
+        }
+        self.write_register(&modem_control)
     }
 
-    /// Send an acknowledge frame, with pending field set.
-    pub fn acknowledge_set(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        let mut buffer = [Strobe::AckPend.opcode()];
-        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
-        Ok(buffer[0].into())
+    /// Whether the radio is in promiscuous mode (address recognition is
+    /// disabled)
+    pub fn is_promiscuous(&mut self) -> Result<bool, RadioError<SPIE, GPIOE>> {
+        let mut modem_control = ModemControlRegister0Builder::default().build().unwrap();
+        let _ = self.read_register(&mut modem_control)?;
+        Ok(!modem_control.adr_decode)
     }
 
-    /// Enable decryption in-line of the RX FIFO
-    pub fn enable_decryption(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        let mut buffer = [Strobe::RxDecryption.opcode()];
-        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
-        Ok(buffer[0].into())
+    /// Write the AND override register (ANDOR), forcing the analog signals
+    /// whose bits are cleared low (e.g. to power down unused subsystems)
+    pub fn set_and_override(&mut self, register: AndOverrideRegister) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.write_register(&register)
     }
 
-    /// Enable encryption in-line of the TX FIFO
-    pub fn enable_encryption(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        let mut buffer = [Strobe::TxEncryption.opcode()];
-        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
-        Ok(buffer[0].into())
+    /// Read the AND override register (ANDOR)
+    pub fn read_and_override(&mut self) -> Result<AndOverrideRegister, RadioError<SPIE, GPIOE>> {
+        let mut register = AndOverrideRegisterBuilder::default().build().unwrap();
+        let _ = self.read_register(&mut register)?;
+        Ok(register)
     }
 
-    /// AES Stand alone encryption.
-    pub fn aes_encryption(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        let mut buffer = [Strobe::AesEncryption.opcode()];
-        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
-        Ok(buffer[0].into())
+    /// Write the OR override register (OR), forcing the analog signals whose
+    /// bits are set high
+    pub fn set_or_override(&mut self, register: OrOverrideRegister) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.write_register(&register)
     }
 
-    /// Write some register value into a given register
-    pub fn write_register(&mut self, register: &dyn register::Register) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        let mut buffer = register.write_value();
-        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
-        Ok(buffer[0].into())
+    /// Read the OR override register (OR)
+    pub fn read_or_override(&mut self) -> Result<OrOverrideRegister, RadioError<SPIE, GPIOE>> {
+        let mut register = OrOverrideRegisterBuilder::default().build().unwrap();
+        let _ = self.read_register(&mut register)?;
+        Ok(register)
     }
 
-    /// Read the register value into itself and return the status
-    /// 
-    /// TODO: I'm not happy with this definition, so I may change it in the future.
-    /// Mostly, I thought it would be neat if register was a trait instead of an enum,
-    /// but that seems to have made definitions a bit more odd than I would have liked.
-    pub fn read_register(&mut self, register: &mut dyn register::Register) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        let mut buffer = [0u8; 3];
-        buffer[0] = register.read_address();
-        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
-        let status = buffer[0].into();
-        register.fill_from_buffer(buffer);
-        Ok(status)
+    /// Set the polarity of the FIFO, FIFOP, SFD and CCA pins in a single write
+    /// of IOCFG0 (true for active low, false for active high).
+    pub fn configure_io_interrupt_polarity(&mut self, fifo: bool, fifop: bool, sfd: bool, cca: bool) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.update_io_configuration(|register| {
+            register.fifo_polarity = fifo;
+            register.fifop_polarity = fifop;
+            register.sfd_polarity = sfd;
+            register.cca_polarity = cca;
+        })
     }
 
-    /// Write to a given location in RAM.
-    fn write_ram(&mut self, ram: Ram, data: &[u8]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        if data.len() != ram.length() {
-            return Err(RadioError::InvalidBufferLenth { expected: ram.length(), found: data.len() });
-        }
-        let mut buffer = Vec::with_capacity(2 + data.len());
-        let address = ram.write_address();
-        buffer.push(address.0);
-        buffer.push(address.1);
-        for byte in data {
-            buffer.push(*byte);
+    /// Write the IO configuration register (IOCFG0: FIFOP threshold, pin
+    /// polarities and BCN_ACCEPT) and verify it by reading it back
+    pub fn configure_io(&mut self, config: io_configuration::IOConfigurationRegister) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        // The fields are public, so validate them as the builder would
+        let config = IOConfigurationRegisterBuilder::default()
+            .bcn_accept(config.bcn_accept)
+            .fifo_polarity(config.fifo_polarity)
+            .fifop_polarity(config.fifop_polarity)
+            .sfd_polarity(config.sfd_polarity)
+            .cca_polarity(config.cca_polarity)
+            .fifop_threshold(config.fifop_threshold)
+            .build()
+            .map_err(|e| RadioError::InvalidConfiguration(e.message()))?;
+        self.write_register(&config)?;
+        let mut register = IOConfigurationRegisterBuilder::default().build().unwrap();
+        let status = self.read_register(&mut register)?;
+        if register != config {
+            return Err(RadioError::FailedConfiguration("Configuration of IO Failed"));
         }
-        self.spi.transfer_in_place(buffer.as_mut_slice()).map_err(RadioError::SpiError)?;
-        Ok(buffer[0].into())
+        Ok(status)
     }
 
-    /// Read from a given location in RAM.
-    fn read_ram(&mut self, ram: Ram, buffer: &mut [u8]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
-        if buffer.len() != ram.length() {
-            return Err(RadioError::InvalidBufferLenth { expected: ram.length(), found: buffer.len() });
+    /// Write the IO configuration register 1 (IOCFG1: HSSD source and the
+    /// SFD / CCA pin multiplexers) and verify it by reading it back
+    pub fn configure_io1(&mut self, config: io_configuration::IOConfigurationRegister1) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let config = IOConfigurationRegister1Builder::default()
+            .hssd_src(config.hssd_src)
+            .sfd_mux(config.sfd_mux)
+            .cca_mux(config.cca_mux)
+            .build()
+            .map_err(|e| RadioError::InvalidConfiguration(e.message()))?;
+        self.write_register(&config)?;
+        let mut register = IOConfigurationRegister1Builder::default().build().unwrap();
+        let status = self.read_register(&mut register)?;
+        if register != config {
+            return Err(RadioError::FailedConfiguration("Configuration of IO 1 Failed"));
         }
-        let mut write_buffer = vec![0u8; 2 + buffer.len()];
-        let address = ram.read_address();
-        write_buffer[0] = address.0;
-        write_buffer[1] = address.1;
-        self.spi.transfer_in_place(&mut write_buffer).map_err(RadioError::SpiError)?;
-        buffer[..].copy_from_slice(&write_buffer.as_slice()[2..]);
-        Ok(write_buffer[0].into())
+        Ok(status)
     }
-}
\ No newline at end of file
+
+    /// Set the number of bytes in the RX FIFO (0-127) for FIFOP to go active
+    /// (IOCFG0.FIFOP_THR)
+    pub fn set_fifop_threshold(&mut self, threshold: u8) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let _ = IOConfigurationRegisterBuilder::default()
+            .fifop_threshold(threshold)
+            .build()
+            .map_err(|e| RadioError::InvalidConfiguration(e.message()))?;
+        self.update_io_configuration(|register| register.fifop_threshold = threshold)
+    }
+
+    /// Start interrupt driven receive: FIFOP is set active high and asserts
+    /// once fifop_threshold (0-127) bytes are in the RX FIFO (or a complete
+    /// frame has been received), then Rx Mode is enabled.
+    /// 
+    /// Note: The caller is responsible for configuring a rising edge GPIO
+    /// interrupt on the MCU pin connected to FIFOP and calling
+    /// on_fifop_interrupt from its handler.
+    pub fn enable_rx_interrupt_mode(&mut self, fifop_threshold: u8) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let _ = IOConfigurationRegisterBuilder::default()
+            .fifop_threshold(fifop_threshold)
+            .build()
+            .map_err(|e| RadioError::InvalidConfiguration(e.message()))?;
+        self.update_io_configuration(|register| {
+            register.fifop_threshold = fifop_threshold;
+            register.fifop_polarity = false;
+        })?;
+        self.enable_rx()
+    }
+
+    /// Read the frame that asserted FIFOP (see enable_rx_interrupt_mode),
+    /// reading its length byte first and then exactly that many bytes.
+    /// 
+    /// Note: This only performs SPI transfers (no delays or polling) so it
+    /// can be called from the interrupt handler, provided the SPI bus is not
+    /// in use elsewhere when the interrupt fires.
+    pub fn on_fifop_interrupt(&mut self) -> Result<RxFrame, RadioError<SPIE, GPIOE>> {
+        self.receive_frame()
+    }
+
+    /// Set the polarity of the FIFO pin (true for active low)
+    pub fn set_fifo_polarity(&mut self, active_low: bool) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.update_io_configuration(|register| register.fifo_polarity = active_low)
+    }
+
+    /// Set the polarity of the FIFOP pin (true for active low)
+    pub fn set_fifop_polarity(&mut self, active_low: bool) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.update_io_configuration(|register| register.fifop_polarity = active_low)
+    }
+
+    /// Set the polarity of the SFD pin (true for active low)
+    pub fn set_sfd_polarity(&mut self, active_low: bool) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.update_io_configuration(|register| register.sfd_polarity = active_low)
+    }
+
+    /// Set the polarity of the CCA pin (true for active low)
+    pub fn set_cca_polarity(&mut self, active_low: bool) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.update_io_configuration(|register| register.cca_polarity = active_low)
+    }
+
+    /// Accept beacons from every PAN (rather than only the PAN ID in RAM) when
+    /// address recognition is enabled (IOCFG0.BCN_ACCEPT)
+    pub fn set_bcn_accept(&mut self, accept: bool) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.update_io_configuration(|register| register.bcn_accept = accept)
+    }
+
+    /// Read IOCFG0, apply an update to it and write it back
+    fn update_io_configuration(&mut self, update: impl FnOnce(&mut io_configuration::IOConfigurationRegister)) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut register = IOConfigurationRegisterBuilder::default().build().unwrap();
+        self.read_register_for_update(&mut register)?;
+        update(&mut register);
+        self.write_register(&register)
+    }
+
+    /// Select the signals output by the HSSD diagnostic module, for debugging
+    /// the receive / transmit signal chain.
+    /// 
+    /// Note: The HSSD module requires the frequency synthesizer to be running.
+    pub fn set_hssd_src(&mut self, src: HssdSource) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut register = IOConfigurationRegister1Builder::default().build().unwrap();
+        self.read_register_for_update(&mut register)?;
+        let register = IOConfigurationRegister1Builder::default()
+            .hssd_src(src as u8)
+            .sfd_mux(register.sfd_mux)
+            .cca_mux(register.cca_mux)
+            .build()
+            .map_err(|e| RadioError::InvalidConfiguration(e.message()))?;
+        self.write_register(&register)
+    }
+
+    /// Enable the battery monitor with a toggle voltage code (0-31, see
+    /// battery_voltage_estimate), waiting for the comparator output to
+    /// become valid
+    pub fn enable_battery_monitor(&mut self, voltage_code: u8, delay: &mut dyn DelayNs) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let register = BatteryMonitorRegisterBuilder::default()
+            .battmon_en(true)
+            .battmon_voltage(voltage_code)
+            .build()
+            .map_err(|e| RadioError::InvalidConfiguration(e.message()))?;
+        let status = self.write_register(&register)?;
+        delay.delay_us(BATTMON_SETTLE_US);
+        Ok(status)
+    }
+
+    /// Whether the supply voltage is above the battery monitor toggle voltage
+    /// (BATTMON.BATTMON_OK)
+    pub fn is_battery_ok(&mut self) -> Result<bool, RadioError<SPIE, GPIOE>> {
+        let mut register = BatteryMonitorRegisterBuilder::default().build().unwrap();
+        let _ = self.read_register(&mut register)?;
+        Ok(register.battmon_ok())
+    }
+
+    /// Disable the battery monitor
+    pub fn disable_battery_monitor(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut register = BatteryMonitorRegisterBuilder::default().build().unwrap();
+        self.read_register_for_update(&mut register)?;
+        register.battmon_en = false;
+        self.write_register(&register)
+    }
+
+    /// Override the VGA gain chosen by the AGC with a fixed gain (0-127)
+    pub fn set_agc_vga_override(&mut self, gain: u8) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut register = AGCControlRegisterBuilder::default().build().unwrap();
+        self.read_register_for_update(&mut register)?;
+        let register = AGCControlRegisterBuilder::default()
+            .vga_gain_oe(true)
+            .vga_gain(gain)
+            .lnamix_gainmode_o(register.lnamix_gainmode_o)
+            .build()
+            .map_err(|e| RadioError::InvalidConfiguration(e.message()))?;
+        self.write_register(&register)
+    }
+
+    /// Let the AGC set the VGA gain again
+    pub fn disable_agc_override(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut register = AGCControlRegisterBuilder::default().build().unwrap();
+        self.read_register_for_update(&mut register)?;
+        register.vga_gain_oe = false;
+        self.write_register(&register)
+    }
+
+    /// Override the LNA / mixer gain mode (or leave it to the AGC with
+    /// LnaGainMode::Automatic)
+    pub fn set_lna_gain_mode(&mut self, mode: LnaGainMode) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut register = AGCControlRegisterBuilder::default().build().unwrap();
+        self.read_register_for_update(&mut register)?;
+        register.lnamix_gainmode_o = mode as u8;
+        self.write_register(&register)
+    }
+
+    /// Read the LNA / mixer gain mode currently in use (selected by the AGC or
+    /// the override)
+    pub fn read_agc_state(&mut self) -> Result<LnaGainMode, RadioError<SPIE, GPIOE>> {
+        let mut register = AGCControlRegisterBuilder::default().build().unwrap();
+        let _ = self.read_register(&mut register)?;
+        Ok(LnaGainMode::from(register.lnamix_gainmode))
+    }
+
+    /// Whether the RX FIFO is empty.
+    pub fn rx_fifo_is_empty(&mut self) -> Result<bool, RadioError<SPIE, GPIOE>> {
+        Ok(!self.data_ready()?)
+    }
+
+    /// Whether the RX FIFO has overflowed, signalled by FIFOP being asserted
+    /// while FIFO is not (always false with NoPin).
+    pub fn rx_fifo_is_overflowed(&mut self) -> Result<bool, RadioError<SPIE, GPIOE>> {
+        Ok(self.fifop_asserted()? && !self.data_ready()?)
+    }
+
+    /// Whether the TX FIFO has underflowed during transmission
+    pub fn tx_fifo_is_underflowed(&mut self) -> Result<bool, RadioError<SPIE, GPIOE>> {
+        Ok(self.status()?.tx_underflow)
+    }
+
+    /// Clear a TX FIFO underflow by flushing the TX FIFO
+    pub fn clear_tx_underflow(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.flush_tx_fifo()
+    }
+
+    /// Send an acknowledgement frame for a given sequence number (for
+    /// protocols that acknowledge frames manually, with AUTOACK disabled),
+    /// waiting for the transmission to complete.
+    /// 
+    /// Note: The SACK / SACKPEND strobes (acknowledge_cleared and
+    /// acknowledge_set) always acknowledge the last received frame, so the
+    /// frame is written to the TX FIFO and sent with STXON instead.  As an
+    /// acknowledgement is sent without CCA, this is allowed while receiving
+    /// (as well as when idle).
+    pub fn send_ack(&mut self, sequence_number: u8, frame_pending: bool, delay: &mut dyn DelayNs) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        if !matches!(self.state, RadioState::Idle | RadioState::Receiving) {
+            return Err(RadioError::InvalidState { current: self.state, required: RadioState::Receiving });
+        }
+        let frame = frame::ack_frame(sequence_number, frame_pending);
+        let _ = self.flush_tx_fifo()?;
+        let _ = self.write_tx_fifo_bytes(&frame)?;
+        let _ = self.enable_tx()?;
+
+        let mut remaining_us = TX_TURNAROUND_US + (PREAMBLE_AND_SFD_BYTES + frame.len() as u32 + frame::FCS_LENGTH as u32) * BYTE_PERIOD_US;
+        loop {
+            let status = self.status()?;
+            if status.tx_underflow {
+                return Err(RadioError::FifoUnderflow);
+            }
+            if !status.tx_active && !self.sfd_active()? {
+                return Ok(status);
+            }
+            if remaining_us == 0 {
+                return Err(RadioError::Timeout);
+            }
+            let wait_us = min(LISTEN_POLL_US, remaining_us);
+            delay.delay_us(wait_us);
+            remaining_us -= wait_us;
+        }
+    }
+
+    /// Send an acknowledge frame, with pending field cleared.
+    /// 
+    /// Note: SACK is only valid in RX mode.
+    pub fn acknowledge_cleared(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.acknowledge(Strobe::Ack)
+    }
+
+    /// Send an acknowledge frame, with pending field set.
+    /// 
+    /// Note: SACKPEND is only valid in RX mode.
+    pub fn acknowledge_set(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.acknowledge(Strobe::AckPend)
+    }
+
+    fn acknowledge(&mut self, strobe: Strobe) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        if self.state != RadioState::Receiving {
+            return Err(RadioError::InvalidState { current: self.state, required: RadioState::Receiving });
+        }
+        let mut buffer = [strobe.opcode()];
+        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
+        // The acknowledgement is sent from RX, which the chip returns to
+        self.state = RadioState::Transmitting;
+        Ok(buffer[0].into())
+    }
+
+    /// Enable decryption in-line of the RX FIFO
+    pub fn enable_decryption(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut buffer = [Strobe::RxDecryption.opcode()];
+        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
+        Ok(buffer[0].into())
+    }
+
+    /// Enable encryption in-line of the TX FIFO
+    pub fn enable_encryption(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut buffer = [Strobe::TxEncryption.opcode()];
+        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
+        Ok(buffer[0].into())
+    }
+
+    /// AES Stand alone encryption.
+    pub fn aes_encryption(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut buffer = [Strobe::AesEncryption.opcode()];
+        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
+        Ok(buffer[0].into())
+    }
+
+    /// Write some register value into a given register
+    pub fn write_register(&mut self, register: &dyn register::Register) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut buffer = register.write_value();
+        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
+        #[cfg(feature = "cache")]
+        self.cache.store(register.address(), register.register_value());
+        if register.address() == self.io_configuration.address() {
+            self.io_configuration = register.register_value().into();
+        }
+        Ok(buffer[0].into())
+    }
+
+    /// Read a register before modifying it, using the cached value of the
+    /// register when there is one
+    fn read_register_for_update(&mut self, register: &mut dyn register::Register) -> Result<(), RadioError<SPIE, GPIOE>> {
+        #[cfg(feature = "cache")]
+        {
+            if let Some(value) = self.cache.get(register.address()) {
+                let value = value.to_be_bytes();
+                register.fill_from_buffer([0x00, value[0], value[1]]);
+                return Ok(());
+            }
+        }
+        let _ = self.read_register(register)?;
+        #[cfg(feature = "cache")]
+        self.cache.store(register.address(), register.register_value());
+        Ok(())
+    }
+
+    /// Forget the cached register values (e.g. after the radio has been
+    /// reset or reconfigured outside of the driver)
+    #[cfg(feature = "cache")]
+    pub fn invalidate_cache(&mut self) {
+        self.cache.invalidate();
+    }
+
+    /// Read the register value into itself and return the status
+    /// 
+    /// TODO: I'm not happy with this definition, so I may change it in the future.
+    /// Mostly, I thought it would be neat if register was a trait instead of an enum,
+    /// but that seems to have made definitions a bit more odd than I would have liked.
+    pub fn read_register(&mut self, register: &mut dyn register::Register) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut buffer = [0u8; 3];
+        buffer[0] = register.read_address();
+        self.spi.transfer_in_place(&mut buffer).map_err(RadioError::SpiError)?;
+        let status = buffer[0].into();
+        register.fill_from_buffer(buffer);
+        Ok(status)
+    }
+
+    /// Read every configuration register (in address order) into a
+    /// RegisterSnapshot, e.g. to print the radio's configuration while
+    /// debugging
+    pub fn dump_registers(&mut self) -> Result<RegisterSnapshot, RadioError<SPIE, GPIOE>> {
+        let mut snapshot = RegisterSnapshot {
+            main_control: MainControlRegisterBuilder::default().build().unwrap(),
+            modem_control_0: ModemControlRegister0Builder::default().build().unwrap(),
+            modem_control_1: ModemControlRegister1Builder::default().build().unwrap(),
+            rssi: RSSIRegisterBuilder::default().build().unwrap(),
+            transmit_control: TransmitControlRegisterBuilder::default().build().unwrap(),
+            receive_control_0: ReceiveControlRegister0Builder::default().build().unwrap(),
+            receive_control_1: ReceiveControlRegister1Builder::default().build().unwrap(),
+            frequency_synthesizer: FrequencySynthesizerRegisterBuilder::default().build().unwrap(),
+            security_control_0: SecurityControlRegister0Builder::default().build().unwrap(),
+            security_control_1: SecurityControlRegister1Builder::default().build().unwrap(),
+            battery_monitor: BatteryMonitorRegisterBuilder::default().build().unwrap(),
+            io_configuration: IOConfigurationRegisterBuilder::default().build().unwrap(),
+            io_configuration_1: IOConfigurationRegister1Builder::default().build().unwrap(),
+        };
+        let _ = self.read_register(&mut snapshot.main_control)?;
+        let _ = self.read_register(&mut snapshot.modem_control_0)?;
+        let _ = self.read_register(&mut snapshot.modem_control_1)?;
+        let _ = self.read_register(&mut snapshot.rssi)?;
+        let _ = self.read_register(&mut snapshot.transmit_control)?;
+        let _ = self.read_register(&mut snapshot.receive_control_0)?;
+        let _ = self.read_register(&mut snapshot.receive_control_1)?;
+        let _ = self.read_register(&mut snapshot.frequency_synthesizer)?;
+        let _ = self.read_register(&mut snapshot.security_control_0)?;
+        let _ = self.read_register(&mut snapshot.security_control_1)?;
+        let _ = self.read_register(&mut snapshot.battery_monitor)?;
+        let _ = self.read_register(&mut snapshot.io_configuration)?;
+        let _ = self.read_register(&mut snapshot.io_configuration_1)?;
+        Ok(snapshot)
+    }
+
+    /// Tune the radio to an IEEE 802.15.4 channel (11-26), updating only
+    /// FSCTRL.FREQ (FREQ = 357 + 5 * (channel - 11)) and verifying the write.
+    pub fn set_channel(&mut self, channel: u8) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let channel = Channel::try_from(channel).map_err(|e| RadioError::InvalidChannel(e.0))?;
+        let _ = self.write_channel(channel)?;
+        let mut register = FrequencySynthesizerRegisterBuilder::default().build().unwrap();
+        let status = self.read_register(&mut register)?;
+        if register.frequency != Frequency::from_channel(channel) {
+            return Err(RadioError::FailedConfiguration("Configuration of Channel Failed"));
+        }
+        Ok(status)
+    }
+
+    /// Tune the radio to an IEEE 802.15.4 channel, writing FSCTRL with its
+    /// default settings
+    pub fn set_ieee_channel(&mut self, channel: Ieee802154Channel) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.write_register(&frequency_synthesis::FrequencySynthesizerRegister::from(channel))
+    }
+
+    /// Read the IEEE 802.15.4 channel the radio is tuned to, or None if
+    /// FSCTRL.FREQ is not one of the 2.4 GHz channels
+    pub fn read_ieee_channel(&mut self) -> Result<Option<Ieee802154Channel>, RadioError<SPIE, GPIOE>> {
+        let mut register = FrequencySynthesizerRegisterBuilder::default().build().unwrap();
+        let _ = self.read_register(&mut register)?;
+        Ok(register.frequency.to_channel().map(Ieee802154Channel::from))
+    }
+
+    /// Set the frequency synthesizer to a given channel
+    fn write_channel(&mut self, channel: Channel) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        let mut register = FrequencySynthesizerRegisterBuilder::default().build().unwrap();
+        self.read_register_for_update(&mut register)?;
+        register.frequency = Frequency::from_channel(channel);
+        self.write_register(&register)
+    }
+
+    /// Read the RSSI (RSSI.RSSI_VAL) once it is valid, waiting (for at most
+    /// timeout_us) for the receiver to have been enabled for 8 symbol periods.
+    pub fn read_rssi(&mut self, timeout_us: u32, delay: &mut dyn DelayNs) -> Result<i8, RadioError<SPIE, GPIOE>> {
+        let mut waited_us = 0;
+        loop {
+            if let Some(rssi) = self.try_read_rssi()? {
+                return Ok(rssi);
+            }
+            if waited_us >= timeout_us {
+                return Err(RadioError::Timeout);
+            }
+            let wait_us = min(RSSI_VALID_DELAY_US, timeout_us - waited_us);
+            delay.delay_us(wait_us);
+            waited_us += wait_us;
+        }
+    }
+
+    /// Read the RSSI (RSSI.RSSI_VAL), returning None if it is not valid yet
+    /// (the receiver has been enabled for less than 8 symbol periods).
+    pub fn try_read_rssi(&mut self) -> Result<Option<i8>, RadioError<SPIE, GPIOE>> {
+        if !self.status()?.rssi_valid {
+            return Ok(None);
+        }
+        Ok(Some(self.read_rssi_value()?))
+    }
+
+    /// Clear channel assessment in software (rather than from the CCA pin),
+    /// following MDMCTRL0.CCA_MODE:
+    /// 1: clear when RSSI < CCA_THR - CCA_HYST
+    /// 2: clear when not receiving a frame (SFD is not active)
+    /// 3: clear when both of the above hold
+    /// 
+    /// Note: The RSSI is only valid once RX has been enabled for 8 symbol
+    /// periods, until then an InvalidState error is returned.
+    pub fn is_channel_clear(&mut self) -> Result<bool, RadioError<SPIE, GPIOE>> {
+        let mut modem_control = ModemControlRegister0Builder::default().build().unwrap();
+        let _ = self.read_register(&mut modem_control)?;
+        if modem_control.cca_mode == 0 || modem_control.cca_mode > 3 {
+            return Err(RadioError::InvalidConfiguration("Invalid CCA_MODE. 1<=CCA_MODE<=3"));
+        }
+
+        if !self.status()?.rssi_valid {
+            return Err(RadioError::InvalidState { current: self.state, required: RadioState::Receiving });
+        }
+        let mut rssi = RSSIRegisterBuilder::default().build().unwrap();
+        let _ = self.read_register(&mut rssi)?;
+        let below_threshold = (rssi.rssi_value as i16) < rssi.cca_threshold as i16 - modem_control.cca_hyst as i16;
+        let not_receiving = !self.sfd_active()?;
+
+        Ok(match modem_control.cca_mode {
+            1 => below_threshold,
+            2 => not_receiving,
+            _ => below_threshold && not_receiving,
+        })
+    }
+
+    /// Read the current RSSI value from the RSSI register
+    fn read_rssi_value(&mut self) -> Result<i8, RadioError<SPIE, GPIOE>> {
+        let mut register = RSSIRegisterBuilder::default().build().unwrap();
+        let _ = self.read_register(&mut register)?;
+        Ok(register.rssi_value)
+    }
+
+    /// Wait (for at most remaining_us, which is decremented by the time spent
+    /// waiting) for a frame with a valid CRC to be received and read it from
+    /// the RX FIFO into frame.
+    /// 
+    /// Returns the number of bytes read (not including the length byte), the
+    /// last two of which are the RSSI and the CRC OK / correlation bytes, or
+    /// None if no frame was received in time.
+    fn listen_for_frame(&mut self, frame: &mut [u8; 128], remaining_us: &mut u32, delay: &mut dyn DelayNs) -> Result<Option<usize>, RadioError<SPIE, GPIOE>> {
+        while *remaining_us > 0 {
+            if self.data_ready()? {
+                let mut length = [0u8];
+                let _ = self.receive(&mut length)?;
+                let length = (length[0] & 0x7F) as usize;
+                // Frame Control Field, Sequence Number, RSSI and CRC / Correlation
+                if length >= 5 {
+                    let wait_us = length as u32 * BYTE_PERIOD_US;
+                    delay.delay_us(wait_us);
+                    *remaining_us = remaining_us.saturating_sub(wait_us);
+                    let _ = self.receive(&mut frame[..length])?;
+                    let (_, crc_ok) = metadata::parse_lqi_crc_byte(frame[length - 1]);
+                    if crc_ok {
+                        return Ok(Some(length));
+                    }
+                    continue;
+                }
+                let _ = self.flush_rx_fifo()?;
+            }
+            let wait_us = min(LISTEN_POLL_US, *remaining_us);
+            delay.delay_us(wait_us);
+            *remaining_us -= wait_us;
+        }
+        Ok(None)
+    }
+
+    /// Wait for an acknowledgement of the frame with sequence number
+    /// expected_seq.  Waits (up to timeout_us in total) for the radio to
+    /// finish transmitting, then listens for an acknowledgement frame.
+    /// 
+    /// Returns whether a matching acknowledgement was received.
+    pub(crate) fn listen_for_ack(&mut self, expected_seq: u8, timeout_us: u32, delay: &mut dyn DelayNs) -> Result<bool, RadioError<SPIE, GPIOE>> {
+        let mut remaining_us = timeout_us;
+        // SFD is high while the frame is being sent and goes low at the end
+        // of the frame, TX_ACTIVE covers the preamble before SFD goes high
+        while self.sfd_active()? || self.status()?.tx_active {
+            if remaining_us == 0 {
+                return Ok(false);
+            }
+            let wait_us = min(LISTEN_POLL_US, remaining_us);
+            delay.delay_us(wait_us);
+            remaining_us -= wait_us;
+        }
+
+        let _ = self.enable_rx()?;
+        let mut frame = [0u8; 128];
+        while let Some(length) = self.listen_for_frame(&mut frame, &mut remaining_us, delay)? {
+            let Some(parsed) = ParsedFrame::parse(&frame[..(length - 2)]) else {
+                continue;
+            };
+            if parsed.frame_type == FrameType::Acknowledgement && parsed.sequence_number == expected_seq {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Write to a given location in RAM.
+    fn write_ram(&mut self, ram: Ram, data: &[u8]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        if data.len() != ram.length() {
+            return Err(RadioError::InvalidBufferLenth { expected: ram.length(), found: data.len() });
+        }
+        let mut buffer = [0u8; RAM_TRANSFER_SIZE];
+        let address = ram.write_address();
+        buffer[0] = address.0;
+        buffer[1] = address.1;
+        buffer[2..(2 + data.len())].copy_from_slice(data);
+        self.spi.transfer_in_place(&mut buffer[..(2 + data.len())]).map_err(RadioError::SpiError)?;
+        Ok(buffer[0].into())
+    }
+
+    /// Write to part of a given location in RAM, starting offset bytes into it.
+    fn write_ram_at(&mut self, ram: Ram, offset: usize, data: &[u8]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        if offset + data.len() > ram.length() {
+            return Err(RadioError::InvalidBufferLenth { expected: ram.length().saturating_sub(offset), found: data.len() });
+        }
+        let mut buffer = [0u8; RAM_TRANSFER_SIZE];
+        let address = ram.write_address_at(offset);
+        buffer[0] = address.0;
+        buffer[1] = address.1;
+        buffer[2..(2 + data.len())].copy_from_slice(data);
+        self.spi.transfer_in_place(&mut buffer[..(2 + data.len())]).map_err(RadioError::SpiError)?;
+        Ok(buffer[0].into())
+    }
+
+    /// Read from a given location in RAM.
+    fn read_ram(&mut self, ram: Ram, buffer: &mut [u8]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        if buffer.len() != ram.length() {
+            return Err(RadioError::InvalidBufferLenth { expected: ram.length(), found: buffer.len() });
+        }
+        let length = buffer.len();
+        let mut write_buffer = [0u8; RAM_TRANSFER_SIZE];
+        let address = ram.read_address();
+        write_buffer[0] = address.0;
+        write_buffer[1] = address.1;
+        self.spi.transfer_in_place(&mut write_buffer[..(2 + length)]).map_err(RadioError::SpiError)?;
+        buffer.copy_from_slice(&write_buffer[2..(2 + length)]);
+        Ok(write_buffer[0].into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use test_utils::{MockInputPin, MockSpi, NoDelay};
+
+    type MockRadio = Radio<MockSpi, core::convert::Infallible, MockInputPin, core::convert::Infallible, MockInputPin>;
+
+    fn mock_radio() -> MockRadio {
+        Radio::new(MockSpi::new(), MockInputPin::default(), MockInputPin::default())
+    }
+
+    fn test_receive_buffer(len: usize) {
+        let mut radio = mock_radio();
+        let mut response = vec![0b0100_0000];
+        response.extend((0..128).map(|i| i as u8));
+        radio.spi.respond(&response);
+
+        let mut buffer = vec![0xFFu8; len];
+        let status = radio.receive(&mut buffer).unwrap();
+
+        let data_len = min(len, 128);
+        assert_eq!(status, RadioStatus::from(0b0100_0000));
+        assert_eq!(radio.spi.transfers.len(), 1);
+        assert_eq!(radio.spi.transfers[0].len(), data_len + 1);
+        assert_eq!(radio.spi.transfers[0][0], Strobe::RxFifo.opcode());
+        assert_eq!(&buffer[..data_len], &response[1..=data_len]);
+        assert!(buffer[data_len..].iter().all(|byte| *byte == 0xFF));
+    }
+
+    #[test]
+    fn test_shutdown() {
+        let mut radio = mock_radio();
+        radio.state = RadioState::Idle;
+
+        radio.shutdown().unwrap();
+
+        assert!(!radio.is_powered_up());
+        assert_eq!(
+            radio.spi.transfers,
+            vec![
+                vec![Strobe::DisableRxTx.opcode()],
+                vec![Strobe::XOSCOff.opcode()],
+                vec![0x10 | 1 << 6, 0b0111_1000, 0x00],
+                vec![0x10 | 1 << 6, 0b1111_1000, 0x00],
+            ],
+        )
+    }
+
+    #[test]
+    fn test_send_with_cca() {
+        let mut radio = mock_radio();
+        radio.enable_rx().unwrap();
+
+        radio.send_with_cca(&[0x01]).unwrap();
+        radio.send_no_cca(&[0x01]).unwrap();
+
+        assert_eq!(radio.spi.transfers[3], vec![Strobe::EnableTxCCA.opcode()]);
+        assert_eq!(radio.spi.transfers[6], vec![Strobe::EnableTx.opcode()]);
+    }
+
+    #[test]
+    fn test_send_with_cca_requires_receiving() {
+        let mut radio = mock_radio();
+        radio.state = RadioState::Idle;
+
+        assert!(matches!(
+            radio.send_with_cca(&[0x01]),
+            Err(RadioError::InvalidState { current: RadioState::Idle, required: RadioState::Receiving })
+        ));
+        assert!(matches!(
+            radio.send(&[0x01], true, &mut NoDelay),
+            Err(RadioError::InvalidState { current: RadioState::Idle, required: RadioState::Receiving })
+        ));
+        assert!(radio.spi.transfers.is_empty());
+    }
+
+    #[test]
+    fn test_write_tx_fifo_with_length_byte() {
+        let mut radio = mock_radio();
+
+        radio.write_tx_fifo_with_length_byte(&[0x01, 0x02, 0x03]).unwrap();
+        radio.write_tx_fifo_with_length_byte(&[0xAA; 125]).unwrap();
+
+        assert_eq!(radio.spi.transfers[0], vec![Strobe::TxFifo.opcode(), 0x05, 0x01, 0x02, 0x03]);
+        assert_eq!(radio.spi.transfers[1][1], 127);
+        assert_eq!(radio.spi.transfers[1].len(), 127);
+        assert!(matches!(
+            radio.write_tx_fifo_with_length_byte(&[0u8; 126]),
+            Err(RadioError::InvalidBufferLenth { expected: 125, found: 126 }),
+        ));
+        assert_eq!(radio.spi.transfers.len(), 2);
+    }
+
+    #[test]
+    fn test_write_tx_fifo_bytes() {
+        let mut radio = mock_radio();
+
+        radio.write_tx_fifo_bytes(&[0x01, 0x02]).unwrap();
+        radio.write_tx_fifo_bytes(&[0x03]).unwrap();
+
+        assert_eq!(
+            radio.spi.transfers,
+            vec![
+                vec![Strobe::TxFifo.opcode(), 0x01, 0x02],
+                vec![Strobe::TxFifo.opcode(), 0x03],
+            ],
+        );
+        assert!(matches!(
+            radio.write_tx_fifo_bytes(&[0u8; 129]),
+            Err(RadioError::InvalidBufferLenth { expected: 128, found: 129 }),
+        ));
+    }
+
+    #[test]
+    fn test_read_rx_fifo_bytes() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x0A, 0x0B, 0x0C]);
+        let mut buffer = [0u8; 3];
+
+        radio.read_rx_fifo_bytes(&mut buffer).unwrap();
+
+        assert_eq!(buffer, [0x0A, 0x0B, 0x0C]);
+        assert_eq!(radio.spi.transfers, vec![vec![Strobe::RxFifo.opcode(), 0x00, 0x00, 0x00]]);
+        assert!(matches!(
+            radio.read_rx_fifo_bytes(&mut [0u8; 129]),
+            Err(RadioError::InvalidBufferLenth { expected: 128, found: 129 }),
+        ));
+    }
+
+    #[test]
+    fn test_configure_io_interrupt_polarity() {
+        let mut radio = mock_radio();
+        // BCN_ACCEPT set and FIFOP_THR = 64
+        radio.spi.respond(&[0x00, 0x08, 0x40]);
+
+        radio.configure_io_interrupt_polarity(true, false, true, true).unwrap();
+
+        assert_eq!(radio.spi.transfers.len(), 2);
+        assert_eq!(radio.spi.transfers[1], vec![0x1C | 1 << 6, 0b0000_1101, 0b1100_0000]);
+    }
+
+    #[test]
+    fn test_set_hssd_src() {
+        let mut radio = mock_radio();
+        // SFDMUX = 3, CCAMUX = 5
+        radio.spi.respond(&[0x00, 0x00, 0x65]);
+
+        radio.set_hssd_src(HssdSource::InputAdcIQ).unwrap();
+
+        assert_eq!(radio.spi.transfers[1], vec![0x1D | 1 << 6, 0x18, 0x65]);
+    }
+
+    #[test]
+    fn test_enable_battery_monitor() {
+        let mut radio = mock_radio();
+
+        radio.enable_battery_monitor(20, &mut NoDelay).unwrap();
+
+        assert_eq!(radio.spi.transfers[0], vec![0x1B | 1 << 6, 0x00, 0x34]);
+        assert!(matches!(
+            radio.enable_battery_monitor(32, &mut NoDelay),
+            Err(RadioError::InvalidConfiguration("Invalid BATTMON_VOLTAGE. 0<=BATTMON_VOLTAGE<=31")),
+        ));
+        assert_eq!(radio.spi.transfers.len(), 1);
+    }
+
+    #[test]
+    fn test_is_battery_ok() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x00, 0x74]);
+        radio.spi.respond(&[0x00, 0x00, 0x34]);
+
+        assert!(radio.is_battery_ok().unwrap());
+        assert!(!radio.is_battery_ok().unwrap());
+    }
+
+    #[test]
+    fn test_disable_battery_monitor() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x00, 0x74]);
+
+        radio.disable_battery_monitor().unwrap();
+
+        assert_eq!(radio.spi.transfers[1], vec![0x1B | 1 << 6, 0x00, 0x14]);
+    }
+
+    #[test]
+    fn test_set_agc_vga_override() {
+        let mut radio = mock_radio();
+        // VGA_GAIN = 0x7F, LNAMIX_GAINMODE_O = 2
+        radio.spi.respond(&[0x00, 0x07, 0xF8]);
+
+        radio.set_agc_vga_override(0x15).unwrap();
+
+        assert_eq!(radio.spi.transfers[0][0], 0x23);
+        assert_eq!(radio.spi.transfers[1], vec![0x23 | 1 << 6, 0x09, 0x58]);
+    }
+
+    #[test]
+    fn test_set_agc_vga_override_invalid_gain() {
+        let mut radio = mock_radio();
+
+        assert!(matches!(
+            radio.set_agc_vga_override(0x80),
+            Err(RadioError::InvalidConfiguration("Invalid VGA_GAIN. 0<=VGA_GAIN<=0x7F")),
+        ));
+        assert_eq!(radio.spi.transfers.len(), 1);
+    }
+
+    #[test]
+    fn test_disable_agc_override() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x09, 0x58]);
+
+        radio.disable_agc_override().unwrap();
+
+        assert_eq!(radio.spi.transfers[1], vec![0x23 | 1 << 6, 0x01, 0x58]);
+    }
+
+    #[test]
+    fn test_set_lna_gain_mode() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x07, 0xF3]);
+
+        radio.set_lna_gain_mode(LnaGainMode::Low).unwrap();
+
+        assert_eq!(radio.spi.transfers[1], vec![0x23 | 1 << 6, 0x07, 0xF4]);
+    }
+
+    #[test]
+    fn test_read_agc_state() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x07, 0xF2]);
+
+        assert_eq!(radio.read_agc_state().unwrap(), LnaGainMode::Medium);
+    }
+
+    fn test_set_channel_valid(channel: u8) {
+        let mut radio = mock_radio();
+        // LOCK_THR = 1 with FREQ set to the channel
+        let value = (1 << 14) | (357 + 5 * (channel as u16 - 11));
+        radio.spi.respond(&[0x00, 0x40, 0x00]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, (value >> 8) as u8, value as u8]);
+
+        radio.set_channel(channel).unwrap();
+
+        assert_eq!(radio.spi.transfers[1], vec![0x18 | 1 << 6, (value >> 8) as u8, value as u8]);
+    }
+
+    #[test]
+    fn test_set_channel() {
+        test_set_channel_valid(11);
+        test_set_channel_valid(18);
+        test_set_channel_valid(26);
+    }
+
+    #[test]
+    fn test_set_ieee_channel() {
+        let mut radio = mock_radio();
+
+        radio.set_ieee_channel(Ieee802154Channel::Channel26).unwrap();
+
+        // LOCK_THR = 1, FREQ = 432
+        assert_eq!(radio.spi.transfers, vec![vec![0x18 | 1 << 6, 0x41, 0xB0]]);
+    }
+
+    #[test]
+    fn test_read_ieee_channel() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x41, 0x88]);
+        radio.spi.respond(&[0x00, 0x41, 0x66]);
+
+        assert_eq!(radio.read_ieee_channel().unwrap(), Some(Ieee802154Channel::Channel18));
+        assert_eq!(radio.read_ieee_channel().unwrap(), None);
+    }
+
+    #[test]
+    fn test_set_channel_out_of_range() {
+        let mut radio = mock_radio();
+
+        assert!(matches!(radio.set_channel(10), Err(RadioError::InvalidChannel(10))));
+        assert!(matches!(radio.set_channel(27), Err(RadioError::InvalidChannel(27))));
+        assert!(radio.spi.transfers.is_empty());
+    }
+
+    #[test]
+    fn test_set_channel_failed() {
+        let mut radio = mock_radio();
+
+        assert!(matches!(radio.set_channel(18), Err(RadioError::FailedConfiguration(_))));
+    }
+
+    #[test]
+    fn test_flush_rx_fifo_reads_a_byte_first() {
+        let mut radio = mock_radio();
+
+        radio.flush_rx_fifo().unwrap();
+
+        assert_eq!(
+            radio.spi.transfers,
+            vec![
+                vec![Strobe::RxFifo.opcode(), 0x00],
+                vec![Strobe::FlushRx.opcode()],
+            ],
+        )
+    }
+
+    #[test]
+    fn test_read_rssi() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0b0100_0000]);
+        radio.spi.respond(&[0b0100_0010]);
+        radio.spi.respond(&[0x00, 0xE0, 0xC4]);
+
+        assert_eq!(radio.read_rssi(1000, &mut NoDelay).unwrap(), -60);
+        assert_eq!(radio.spi.transfers.len(), 3);
+    }
+
+    #[test]
+    fn test_read_rssi_timeout() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0b0100_0000]);
+        radio.spi.respond(&[0b0100_0000]);
+        radio.spi.respond(&[0b0100_0000]);
+
+        assert!(matches!(radio.read_rssi(200, &mut NoDelay), Err(RadioError::Timeout)));
+        assert_eq!(radio.spi.transfers.len(), 3);
+    }
+
+    #[test]
+    fn test_try_read_rssi() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0b0100_0000]);
+
+        assert_eq!(radio.try_read_rssi().unwrap(), None);
+    }
+
+    #[test]
+    fn test_set_tx_power() {
+        let mut radio = mock_radio();
+        // TXCTRL with TX_MIXBUF_CUR = 3, PA_CURRENT = 3, PA_LEVEL = 31
+        radio.spi.respond(&[0x00, 0xE0, 0xFF]);
+
+        radio.set_tx_power(TxPowerLevel::Low).unwrap();
+
+        assert_eq!(radio.spi.transfers, vec![
+            vec![0x15, 0x00, 0x00],
+            // Only PA_LEVEL = 7 changed
+            vec![0x15 | 1 << 6, 0xE0, 0xE7],
+        ]);
+    }
+
+    #[test]
+    fn test_set_tx_power_custom() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0xA0, 0xFF]);
+
+        radio.set_tx_power(TxPowerLevel::Custom { pa_level: 19, pa_current: 5 }).unwrap();
+
+        assert_eq!(radio.spi.transfers[1], vec![0x15 | 1 << 6, 0xA1, 0x73]);
+        assert!(radio.set_tx_power(TxPowerLevel::Custom { pa_level: 32, pa_current: 3 }).is_err());
+    }
+
+    #[test]
+    fn test_radio_state_transitions() {
+        let mut radio = mock_radio();
+        assert_eq!(radio.state(), RadioState::PoweredDown);
+
+        radio.start_receiving().unwrap();
+        assert_eq!(radio.state(), RadioState::Receiving);
+        radio.send_with_cca(&[0x01]).unwrap();
+        assert_eq!(radio.state(), RadioState::Transmitting);
+        // TX_ACTIVE still set
+        radio.spi.respond(&[0b0000_1000]);
+        radio.status().unwrap();
+        assert_eq!(radio.state(), RadioState::Transmitting);
+        // Back in RX once the transmission has completed
+        radio.status().unwrap();
+        assert_eq!(radio.state(), RadioState::Receiving);
+        radio.disable_rx_tx().unwrap();
+        assert_eq!(radio.state(), RadioState::Idle);
+        radio.send_no_cca(&[0x01]).unwrap();
+        assert_eq!(radio.state(), RadioState::Transmitting);
+        radio.status().unwrap();
+        assert_eq!(radio.state(), RadioState::Receiving);
+        radio.disable_rx_tx().unwrap();
+        // STXCAL, then a status byte with LOCK set
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0b0000_0100]);
+        radio.calibrate_tx(&mut NoDelay).unwrap();
+        assert_eq!(radio.state(), RadioState::Idle);
+        assert!(radio.is_powered_up());
+        radio.power_down().unwrap();
+        assert!(!radio.is_powered_up());
+        assert_eq!(radio.state(), RadioState::PoweredDown);
+    }
+
+    #[test]
+    fn test_start_receiving_while_receiving() {
+        let mut radio = mock_radio();
+        radio.start_receiving().unwrap();
+
+        assert!(matches!(
+            radio.start_receiving(),
+            Err(RadioError::InvalidState { current: RadioState::Receiving, required: RadioState::Idle })
+        ));
+        assert_eq!(radio.spi.transfers.len(), 1);
+    }
+
+    #[test]
+    fn test_send_without_cca_while_receiving() {
+        let mut radio = mock_radio();
+        radio.enable_rx().unwrap();
+
+        assert!(matches!(
+            radio.send_no_cca(&[0x01]),
+            Err(RadioError::InvalidState { current: RadioState::Receiving, required: RadioState::Idle })
+        ));
+        assert!(matches!(
+            radio.send(&[0x01], false, &mut NoDelay),
+            Err(RadioError::InvalidState { .. })
+        ));
+        assert_eq!(radio.spi.transfers.len(), 1);
+    }
+
+    #[test]
+    fn test_send_tx_frame() {
+        let mut radio = mock_radio();
+        let frame = TxFrameBuilder::new()
+            .dest_pan(0x1234)
+            .dest_short_address(0xFFFF)
+            .payload(&[0x01])
+            .build()
+            .unwrap();
+
+        radio.send_no_cca(&frame).unwrap();
+
+        assert_eq!(radio.spi.transfers[1][0], Strobe::TxFifo.opcode());
+        assert_eq!(&radio.spi.transfers[1][1..], frame.as_ref());
+    }
+
+    #[test]
+    fn test_send_frame_to() {
+        let mut radio = mock_radio();
+        radio.set_sequence_number(5);
+        radio.state = RadioState::Receiving;
+        // PAN ID 0x1234, short address 0x0001
+        radio.spi.respond(&[0x00, 0x00, 0x34, 0x12]);
+        radio.spi.respond(&[0x00, 0x00, 0x01, 0x00]);
+
+        radio.send_frame_to(Address::Short(ShortAddress(0x0002)), 0x1234, &[0xAA, 0xBB], true).unwrap();
+
+        assert_eq!(radio.spi.transfers.len(), 5);
+        assert_eq!(radio.spi.transfers[3], vec![
+            Strobe::TxFifo.opcode(),
+            // Length, frame control (data, PAN ID compression, short
+            // addresses), sequence number
+            0x0D, 0x41, 0x88, 0x05,
+            0x34, 0x12, 0x02, 0x00, 0x01, 0x00,
+            0xAA, 0xBB,
+        ]);
+        assert_eq!(radio.spi.transfers[4], vec![Strobe::EnableTxCCA.opcode()]);
+        assert_eq!(radio.next_sequence_number(), 6);
+    }
+
+    #[test]
+    fn test_send_frame_to_without_short_address() {
+        let mut radio = mock_radio();
+        // PAN ID 0x1234, no short address allocated (0xFFFE)
+        radio.spi.respond(&[0x00, 0x00, 0x34, 0x12]);
+        radio.spi.respond(&[0x00, 0x00, 0xFE, 0xFF]);
+        radio.spi.respond(&[0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]);
+
+        radio.send_frame_to(Address::Short(ShortAddress(0xFFFF)), 0x4321, &[], false).unwrap();
+
+        assert_eq!(radio.spi.transfers[4], vec![
+            Strobe::TxFifo.opcode(),
+            // Short destination, extended source in a different PAN
+            0x13, 0x01, 0xC8, 0x00,
+            0x21, 0x43, 0xFF, 0xFF,
+            0x34, 0x12, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+        ]);
+        assert_eq!(radio.spi.transfers[5], vec![Strobe::EnableTx.opcode()]);
+    }
+
+    #[test]
+    fn test_next_sequence_number_wraps() {
+        let mut radio = mock_radio();
+        assert_eq!(radio.next_sequence_number(), 0);
+        assert_eq!(radio.next_sequence_number(), 1);
+
+        radio.set_sequence_number(255);
+        assert_eq!(radio.next_sequence_number(), 255);
+        assert_eq!(radio.next_sequence_number(), 0);
+    }
+
+    #[test]
+    fn test_build_frame_sequence_number() {
+        let mut radio = mock_radio();
+        radio.set_sequence_number(41);
+
+        let first = radio.build_frame(TxFrameBuilder::new()).unwrap();
+        let second = radio.build_frame(TxFrameBuilder::new()).unwrap();
+        let explicit = radio.build_frame(TxFrameBuilder::new().sequence_number(7)).unwrap();
+        let third = radio.build_frame(TxFrameBuilder::new()).unwrap();
+
+        // Length byte, then the frame control field, then the sequence number
+        assert_eq!(first[3], 41);
+        assert_eq!(second[3], 42);
+        assert_eq!(explicit[3], 7);
+        assert_eq!(third[3], 43);
+        assert!(radio.spi.transfers.is_empty());
+    }
+
+    // Reads every register it modifies
+    #[cfg(not(feature = "cache"))]
+    #[test]
+    fn test_energy_scan() {
+        let mut radio = mock_radio();
+        radio.enable_rx().unwrap();
+        // FSCTRL (channel 11)
+        radio.spi.respond(&[0x00, 0x41, 0x65]);
+        // Channel 15: FSCTRL read, FSCTRL write, SRXON, RSSI read
+        radio.spi.respond(&[0x00, 0x41, 0x65]);
+        radio.spi.respond(&[0x00, 0x00, 0x00]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, 0xE0, 0xC4]);
+        radio.spi.respond(&[0x00]);
+        // Channel 20
+        radio.spi.respond(&[0x00, 0x41, 0x79]);
+        radio.spi.respond(&[0x00, 0x00, 0x00]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, 0xE0, 0x10]);
+
+        let energies = radio.energy_scan(&[15, 20], 128, &mut NoDelay).unwrap();
+
+        assert_eq!(energies.as_slice(), &[(15, -60), (20, 16)]);
+        // The original channel is restored and RX re-enabled
+        let transfers = &radio.spi.transfers;
+        assert_eq!(transfers[transfers.len() - 2], vec![0x18 | 1 << 6, 0x41, 0x65]);
+        assert_eq!(transfers[transfers.len() - 1], vec![Strobe::EnableRx.opcode()]);
+        assert_eq!(radio.state(), RadioState::Receiving);
+    }
+
+    #[test]
+    fn test_energy_scan_invalid_channel() {
+        let mut radio = mock_radio();
+
+        assert!(matches!(
+            radio.energy_scan(&[11, 27], 128, &mut NoDelay),
+            Err(RadioError::InvalidChannel(27)),
+        ));
+        assert!(matches!(
+            radio.energy_scan(&[11; 17], 128, &mut NoDelay),
+            Err(RadioError::InvalidBufferLenth { expected: 16, found: 17 }),
+        ));
+        assert!(radio.spi.transfers.is_empty());
+    }
+
+    #[test]
+    fn test_scan_all_channels() {
+        let mut radio = mock_radio();
+
+        let energies = radio.scan_all_channels(128, &mut NoDelay).unwrap();
+
+        assert_eq!(energies.len(), 16);
+        assert!(energies.iter().map(|(channel, _)| *channel).eq(11..=26));
+        assert_eq!(radio.state(), RadioState::Idle);
+    }
+
+    fn test_is_channel_clear(cca_mode: u8, rssi: u8, sfd: bool) -> bool {
+        let mut radio = mock_radio();
+        radio.sfd.high = sfd;
+        // MDMCTRL0 with CCA_HYST = 2
+        radio.spi.respond(&[0x00, 0x02, 0x02 | cca_mode << 6]);
+        // Status with RSSI_VALID
+        radio.spi.respond(&[0b0000_0010]);
+        // RSSI with CCA_THR = -32
+        radio.spi.respond(&[0x00, 0xE0, rssi]);
+        radio.is_channel_clear().unwrap()
+    }
+
+    #[test]
+    fn test_is_channel_clear_mode_1() {
+        // -35 < -32 - 2
+        assert!(test_is_channel_clear(1, 0xDD, true));
+        // -34 is within the hysteresis
+        assert!(!test_is_channel_clear(1, 0xDE, false));
+    }
+
+    #[test]
+    fn test_is_channel_clear_mode_2() {
+        assert!(test_is_channel_clear(2, 0x10, false));
+        assert!(!test_is_channel_clear(2, 0x80, true));
+    }
+
+    #[test]
+    fn test_is_channel_clear_mode_3() {
+        assert!(test_is_channel_clear(3, 0xDD, false));
+        assert!(!test_is_channel_clear(3, 0xDD, true));
+        assert!(!test_is_channel_clear(3, 0xDE, false));
+    }
+
+    #[test]
+    fn test_is_channel_clear_errors() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x02, 0x02]);
+        assert!(matches!(radio.is_channel_clear(), Err(RadioError::InvalidConfiguration(_))));
+
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x02, 0xC2]);
+        radio.spi.respond(&[0b0000_0000]);
+        assert!(matches!(
+            radio.is_channel_clear(),
+            Err(RadioError::InvalidState { current: RadioState::PoweredDown, required: RadioState::Receiving }),
+        ));
+    }
+
+    struct FixedRng(u32);
+
+    impl BackoffRng for FixedRng {
+        fn next_u32(&mut self) -> u32 {
+            self.0
+        }
+    }
+
+    struct RecordingDelay(Vec<u32>);
+
+    impl DelayNs for RecordingDelay {
+        fn delay_ns(&mut self, ns: u32) {
+            self.0.push(ns / 1_000);
+        }
+    }
+
+    fn respond_with_cca(radio: &mut MockRadio, rssi: u8) {
+        // MDMCTRL0 (CCA_MODE = 1, CCA_HYST = 2), status (RSSI_VALID) and RSSI
+        // (CCA_THR = -32)
+        radio.spi.respond(&[0x00, 0x02, 0x42]);
+        radio.spi.respond(&[0b0000_0010]);
+        radio.spi.respond(&[0x00, 0xE0, rssi]);
+    }
+
+    #[test]
+    fn test_csma_send() {
+        let mut radio = mock_radio();
+        // SRXON
+        radio.spi.respond(&[0x00]);
+        // Busy, then clear
+        respond_with_cca(&mut radio, 0x10);
+        respond_with_cca(&mut radio, 0xC0);
+        let mut delay = RecordingDelay(vec![]);
+
+        radio.csma_send(&[0x01, 0x02], 4, &mut FixedRng(11), &mut delay).unwrap();
+
+        // RSSI valid delay, then 11 % 8 and 11 % 16 backoff periods
+        assert_eq!(delay.0, vec![128, 3 * 320, 11 * 320]);
+        let transfers = &radio.spi.transfers;
+        assert_eq!(transfers[transfers.len() - 2], vec![Strobe::TxFifo.opcode(), 0x01, 0x02]);
+        assert_eq!(transfers[transfers.len() - 1], vec![Strobe::EnableTxCCA.opcode()]);
+    }
+
+    #[test]
+    fn test_csma_send_channel_busy() {
+        let mut radio = mock_radio();
+        radio.enable_rx().unwrap();
+        for _ in 0..3 {
+            respond_with_cca(&mut radio, 0x10);
+        }
+
+        assert!(matches!(
+            radio.csma_send(&[0x01], 3, &mut FixedRng(0), &mut NoDelay),
+            Err(RadioError::ChannelBusy),
+        ));
+        assert!(radio.spi.transfers.iter().all(|transfer| transfer[0] != Strobe::EnableTxCCA.opcode()));
+    }
+
+    #[test]
+    fn test_set_promiscuous_mode() {
+        let mut radio = mock_radio();
+        // MDMCTRL0 reset value with AUTOACK
+        radio.spi.respond(&[0x00, 0x0A, 0xF2]);
+
+        radio.set_promiscuous_mode(true).unwrap();
+
+        // ADR_DECODE and AUTOACK cleared
+        assert_eq!(radio.spi.transfers[1], vec![0x11 | 1 << 6, 0x02, 0xE2]);
+    }
+
+    #[test]
+    fn test_leave_promiscuous_mode() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x02, 0xE2]);
+
+        radio.set_promiscuous_mode(false).unwrap();
+
+        assert_eq!(radio.spi.transfers[1], vec![0x11 | 1 << 6, 0x0A, 0xE2]);
+    }
+
+    #[test]
+    fn test_is_promiscuous() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x02, 0xE2]);
+        radio.spi.respond(&[0x00, 0x0A, 0xE2]);
+
+        assert!(radio.is_promiscuous().unwrap());
+        assert!(!radio.is_promiscuous().unwrap());
+    }
+
+    #[test]
+    fn test_set_and_override() {
+        let mut radio = mock_radio();
+        let register = AndOverrideRegisterBuilder::default().lnamix_pd(false).build().unwrap();
+
+        radio.set_and_override(register).unwrap();
+
+        assert_eq!(radio.spi.transfers, vec![vec![0x21 | 1 << 6, 0xFF, 0xFE]]);
+    }
+
+    #[test]
+    fn test_read_and_override() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x7F, 0xFF]);
+
+        let register = radio.read_and_override().unwrap();
+
+        assert!(!register.vga_reset_n);
+        assert!(register.lnamix_pd);
+        assert_eq!(radio.spi.transfers, vec![vec![0x21, 0x00, 0x00]]);
+    }
+
+    #[test]
+    fn test_set_and_read_or_override() {
+        let mut radio = mock_radio();
+        let register = OrOverrideRegisterBuilder::default().build().unwrap();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, 0x00, 0x00]);
+
+        radio.set_or_override(register).unwrap();
+        let read = radio.read_or_override().unwrap();
+
+        assert_eq!(radio.spi.transfers[0][0], 0x22 | 1 << 6);
+        assert_eq!(radio.spi.transfers[1][0], 0x22);
+        assert_eq!(read, register);
+    }
+
+    #[test]
+    fn test_set_fifop_threshold() {
+        let mut radio = mock_radio();
+        // IOCFG0 with BCN_ACCEPT and FIFOP_THR = 64
+        radio.spi.respond(&[0x00, 0x08, 0x40]);
+
+        radio.set_fifop_threshold(127).unwrap();
+
+        assert_eq!(radio.spi.transfers, vec![
+            vec![0x1C, 0x00, 0x00],
+            vec![0x1C | 1 << 6, 0x08, 0x7F],
+        ]);
+    }
+
+    #[test]
+    fn test_set_fifop_threshold_invalid() {
+        let mut radio = mock_radio();
+
+        assert!(matches!(
+            radio.set_fifop_threshold(128),
+            Err(RadioError::InvalidConfiguration("Invalid FIFOP_THR. 0<=FIFO_THR<=127")),
+        ));
+        assert!(radio.spi.transfers.is_empty());
+    }
+
+    #[test]
+    fn test_enable_rx_interrupt_mode() {
+        let mut radio = mock_radio();
+        // IOCFG0 with FIFOP active low and FIFOP_THR = 64
+        radio.spi.respond(&[0x00, 0x02, 0x40]);
+
+        radio.enable_rx_interrupt_mode(20).unwrap();
+
+        assert_eq!(radio.spi.transfers, vec![
+            vec![0x1C, 0x00, 0x00],
+            vec![0x1C | 1 << 6, 0x00, 0x14],
+            vec![Strobe::EnableRx.opcode()],
+        ]);
+        assert_eq!(radio.state, RadioState::Receiving);
+
+        let mut radio = mock_radio();
+        assert!(matches!(
+            radio.enable_rx_interrupt_mode(128),
+            Err(RadioError::InvalidConfiguration("Invalid FIFOP_THR. 0<=FIFO_THR<=127")),
+        ));
+        assert!(radio.spi.transfers.is_empty());
+    }
+
+    #[test]
+    fn test_fifop_asserted() {
+        let mut radio = mock_radio();
+        assert!(!radio.fifop_asserted().unwrap());
+
+        let mut radio = Radio::new_with_fifop(
+            MockSpi::new(),
+            MockInputPin::default(),
+            MockInputPin::default(),
+            MockInputPin { high: true },
+        );
+        assert!(radio.fifop_asserted().unwrap());
+        assert!(radio.spi.transfers.is_empty());
+
+        let (_, _, _, fifop) = radio.split_with_fifop();
+        assert!(fifop.high);
+    }
+
+    #[test]
+    fn test_no_pin_never_asserted() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x00, 0x40]);
+        radio.set_fifop_polarity(true).unwrap();
+
+        assert!(!radio.fifop_asserted().unwrap());
+        assert!(!radio.rx_fifo_is_overflowed().unwrap());
+    }
+
+    #[test]
+    fn test_pin_polarity() {
+        let mut radio = Radio::new_with_fifop(
+            MockSpi::new(),
+            MockInputPin::default(),
+            MockInputPin::default(),
+            MockInputPin::default(),
+        );
+        assert!(!radio.data_ready().unwrap());
+        assert!(!radio.fifop_asserted().unwrap());
+
+        // IOCFG0 read for the update, then FIFO, FIFOP and SFD set active low
+        radio.spi.respond(&[0x00, 0x00, 0x40]);
+        radio.configure_io_interrupt_polarity(true, true, true, false).unwrap();
+        assert!(radio.data_ready().unwrap());
+        assert!(radio.fifop_asserted().unwrap());
+        assert!(radio.sfd_active().unwrap());
+        radio.sfd.high = true;
+        assert!(!radio.sfd_active().unwrap());
+
+        // A reset restores the default (active high) polarities
+        radio.full_reset(&mut NoDelay).unwrap();
+        assert!(!radio.data_ready().unwrap());
+        assert!(radio.sfd_active().unwrap());
+    }
+
+    #[test]
+    fn test_send_ack_sfd_active_low() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x00, 0x40]);
+        radio.set_sfd_polarity(true).unwrap();
+        radio.enable_rx().unwrap();
+        // SFD inactive (high) once the acknowledgement has been sent
+        radio.sfd.high = true;
+
+        radio.send_ack(0x01, false, &mut NoDelay).unwrap();
+    }
+
+    #[test]
+    fn test_on_fifop_interrupt() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x05]);
+        radio.spi.respond(&[0x00, 0x02, 0x00, 0x2A, 0xD0, 0xE5]);
+
+        let frame = radio.on_fifop_interrupt().unwrap();
+
+        assert_eq!(radio.spi.transfers, vec![
+            vec![Strobe::RxFifo.opcode(), 0x00],
+            vec![Strobe::RxFifo.opcode(), 0x00, 0x00, 0x00, 0x00, 0x00],
+        ]);
+        assert_eq!(frame.bytes(), &[0x02, 0x00, 0x2A, 0xD0, 0xE5]);
+        assert!(frame.crc_valid);
+    }
+
+    // Reads every register it modifies
+    #[cfg(not(feature = "cache"))]
+    #[test]
+    fn test_set_io_configuration_bits() {
+        let mut radio = mock_radio();
+        for _ in 0..5 {
+            radio.spi.respond(&[0x00, 0x00, 0x40]);
+            radio.spi.respond(&[0x00]);
+        }
+
+        radio.set_fifo_polarity(true).unwrap();
+        radio.set_fifop_polarity(true).unwrap();
+        radio.set_sfd_polarity(true).unwrap();
+        radio.set_cca_polarity(true).unwrap();
+        radio.set_bcn_accept(true).unwrap();
+
+        let writes: Vec<_> = radio.spi.transfers.iter().skip(1).step_by(2).cloned().collect();
+        assert_eq!(writes, vec![
+            vec![0x1C | 1 << 6, 0x04, 0x40],
+            vec![0x1C | 1 << 6, 0x02, 0x40],
+            vec![0x1C | 1 << 6, 0x01, 0x40],
+            vec![0x1C | 1 << 6, 0x00, 0xC0],
+            vec![0x1C | 1 << 6, 0x08, 0x40],
+        ]);
+    }
+
+    #[test]
+    fn test_send_ack() {
+        let mut radio = mock_radio();
+        radio.enable_rx().unwrap();
+
+        radio.send_ack(0x56, true, &mut NoDelay).unwrap();
+
+        assert_eq!(radio.spi.transfers[1..], [
+            vec![Strobe::FlushTx.opcode()],
+            vec![Strobe::TxFifo.opcode(), 0x05, 0x12, 0x00, 0x56],
+            vec![Strobe::EnableTx.opcode()],
+            vec![Strobe::ReadStatus.opcode()],
+        ]);
+    }
+
+    #[test]
+    fn test_send_ack_timeout() {
+        let mut radio = mock_radio();
+        radio.state = RadioState::Receiving;
+        radio.sfd.high = true;
+
+        assert!(matches!(radio.send_ack(0x01, false, &mut NoDelay), Err(RadioError::Timeout)));
+    }
+
+    #[test]
+    fn test_send_ack_invalid_state() {
+        let mut radio = mock_radio();
+
+        assert!(matches!(
+            radio.send_ack(0x01, false, &mut NoDelay),
+            Err(RadioError::InvalidState { current: RadioState::PoweredDown, required: RadioState::Receiving })
+        ));
+        radio.state = RadioState::Transmitting;
+        assert!(matches!(
+            radio.send_ack(0x01, false, &mut NoDelay),
+            Err(RadioError::InvalidState { current: RadioState::Transmitting, required: RadioState::Receiving })
+        ));
+        assert!(radio.spi.transfers.is_empty());
+    }
+
+    #[test]
+    fn test_acknowledge_requires_receiving() {
+        let mut radio = mock_radio();
+        radio.state = RadioState::Idle;
+
+        assert!(matches!(
+            radio.acknowledge_cleared(),
+            Err(RadioError::InvalidState { current: RadioState::Idle, required: RadioState::Receiving })
+        ));
+        assert!(radio.spi.transfers.is_empty());
+
+        radio.enable_rx().unwrap();
+        radio.acknowledge_set().unwrap();
+        assert_eq!(radio.spi.transfers[1], vec![Strobe::AckPend.opcode()]);
+        assert_eq!(radio.state(), RadioState::Transmitting);
+    }
+
+    #[test]
+    fn test_set_short_address_little_endian() {
+        let mut radio = mock_radio();
+
+        radio.set_short_address(0x1234).unwrap();
+        radio.set_pan_id(0xABCD).unwrap();
+
+        assert_eq!(radio.spi.transfers[0][2..], [0x34, 0x12]);
+        assert_eq!(radio.spi.transfers[1][2..], [0xCD, 0xAB]);
+    }
+
+    #[test]
+    fn test_read_short_address_little_endian() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x00, 0x34, 0x12]);
+        radio.spi.respond(&[0x00, 0x00, 0xCD, 0xAB]);
+
+        assert_eq!(radio.read_short_address().unwrap(), 0x1234);
+        assert_eq!(radio.read_pan_id().unwrap(), 0xABCD);
+    }
+
+    #[test]
+    fn test_split() {
+        let mut radio = mock_radio();
+        radio.sfd.high = true;
+        radio.power_down().unwrap();
+        assert_eq!(radio.spi().transfers.len(), 2);
+        radio.spi_mut().transfers.clear();
+
+        let (spi, sfd, fifo) = radio.split();
+
+        assert!(spi.transfers.is_empty());
+        assert!(sfd.high);
+        assert!(!fifo.high);
+    }
+
+    #[test]
+    fn test_dump_registers() {
+        let mut radio = mock_radio();
+        // MDMCTRL0 = 0x0AE2 (default), FSCTRL = 0x4165 (default)
+        radio.spi.respond(&[0x00, 0x00, 0x00]);
+        radio.spi.respond(&[0x00, 0x0A, 0xE2]);
+        for _ in 0..5 {
+            radio.spi.respond(&[0x00, 0x00, 0x00]);
+        }
+        radio.spi.respond(&[0x00, 0x41, 0x65]);
+
+        let snapshot = radio.dump_registers().unwrap();
+
+        let addresses: Vec<u8> = radio.spi.transfers.iter().map(|transfer| transfer[0]).collect();
+        assert_eq!(addresses, vec![0x10, 0x11, 0x12, 0x13, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1A, 0x1B, 0x1C, 0x1D]);
+        assert_eq!(snapshot.modem_control_0, ModemControlRegister0Builder::default().build().unwrap());
+        assert_eq!(snapshot.frequency_synthesizer, FrequencySynthesizerRegisterBuilder::default().build().unwrap());
+    }
+
+    #[test]
+    fn test_aes_encrypt_block() {
+        // FIPS-197 Appendix C.1 (key 000102...0f loaded in key slot 1)
+        let plaintext = [
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77,
+            0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF,
+        ];
+        let ciphertext = [
+            0x69, 0xC4, 0xE0, 0xD8, 0x6A, 0x7B, 0x04, 0x30,
+            0xD8, 0xCD, 0xB7, 0x80, 0x70, 0xB4, 0xC5, 0x5A,
+        ];
+        let mut radio = mock_radio();
+        // SECCTRL0 read (SEC_SA_KEYSEL = 0), SECCTRL0 write, SABUF write,
+        // SAES, status (not busy)
+        radio.spi.respond(&[0x00, 0x03, 0x44]);
+        radio.spi.respond(&[0x00, 0x00, 0x00]);
+        radio.spi.respond(&[0x00; 18]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00]);
+        let mut response = vec![0x00, 0x00];
+        response.extend_from_slice(&ciphertext);
+        radio.spi.respond(&response);
+
+        assert_eq!(radio.aes_encrypt_block(KeySlot::Key1, plaintext, &mut NoDelay).unwrap(), ciphertext);
+
+        // SEC_SA_KEYSEL set to select key 1
+        assert_eq!(radio.spi.transfers[1], vec![0x19 | 1 << 6, 0x03, 0xC4]);
+        assert_eq!(&radio.spi.transfers[2][2..], &plaintext);
+        assert_eq!(radio.spi.transfers[3], vec![Strobe::AesEncryption.opcode()]);
+    }
+
+    #[test]
+    fn test_reset_subsystem() {
+        let mut radio = mock_radio();
+        // RESETn, ENC_RESETn, DEMOD_RESETn, MOD_RESETn, FS_RESETn all high
+        radio.spi.respond(&[0x00, 0xF8, 0x00]);
+
+        radio.reset_subsystem(ResetMask::ENCRYPTION | ResetMask::FREQUENCY_SYNTHESIZER).unwrap();
+
+        assert_eq!(radio.spi.transfers.len(), 3);
+        assert_eq!(radio.spi.transfers[1], vec![0x10 | 1 << 6, 0xB0, 0x00]);
+        assert_eq!(radio.spi.transfers[2], vec![0x10 | 1 << 6, 0xF8, 0x00]);
+    }
+
+    #[test]
+    fn test_reset_subsystem_all() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0xF8, 0x00]);
+
+        radio.reset_subsystem(ResetMask::ALL).unwrap();
+
+        // The chip itself (RESETn) is never reset
+        assert_eq!(radio.spi.transfers[1], vec![0x10 | 1 << 6, 0x80, 0x00]);
+        assert_eq!(radio.spi.transfers[2], vec![0x10 | 1 << 6, 0xF8, 0x00]);
+    }
+
+    #[test]
+    fn test_safe_power_down_waits_for_tx() {
+        let mut radio = mock_radio();
+        radio.state = RadioState::Idle;
+        // TX_ACTIVE set twice, then cleared
+        radio.spi.respond(&[0b0100_1100]);
+        radio.spi.respond(&[0b0100_1100]);
+        radio.spi.respond(&[0b0100_0100]);
+
+        radio.safe_power_down(&mut NoDelay).unwrap();
+
+        assert!(!radio.is_powered_up());
+        assert_eq!(radio.state(), RadioState::PoweredDown);
+        let opcodes: Vec<u8> = radio.spi.transfers.iter().map(|transfer| transfer[0]).collect();
+        assert_eq!(opcodes, vec![
+            Strobe::ReadStatus.opcode(),
+            Strobe::ReadStatus.opcode(),
+            Strobe::ReadStatus.opcode(),
+            Strobe::DisableRxTx.opcode(),
+            Strobe::ReadStatus.opcode(),
+            Strobe::XOSCOff.opcode(),
+        ]);
+    }
+
+    #[test]
+    fn test_safe_power_down_synthesizer_still_locked() {
+        let mut radio = mock_radio();
+        radio.state = RadioState::Idle;
+        radio.spi.respond(&[0b0100_0100]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0b0100_0100]);
+
+        assert!(matches!(
+            radio.safe_power_down(&mut NoDelay),
+            Err(RadioError::FailedConfiguration(_)),
+        ));
+        assert!(radio.is_powered_up());
+        assert_eq!(radio.spi.transfers.len(), 3);
+    }
+
+    #[test]
+    fn test_set_modem_control_1() {
+        let mut radio = mock_radio();
+        let config = ModemControlRegister1Builder::default()
+            .corr_threshold(25)
+            .demod_average_mode(true)
+            .build()
+            .unwrap();
+
+        radio.set_modem_control_1(config).unwrap();
+
+        assert_eq!(radio.spi.transfers[0], vec![0x12 | 1 << 6, 0x06, 0x60]);
+    }
+
+    #[test]
+    fn test_set_modem_control_1_invalid() {
+        let mut radio = mock_radio();
+        let mut config = ModemControlRegister1Builder::default().build().unwrap();
+        config.corr_threshold = 32;
+
+        assert!(matches!(radio.set_modem_control_1(config), Err(RadioError::InvalidConfiguration(_))));
+        config.corr_threshold = 20;
+        config.tx_mode = 4;
+        assert!(matches!(radio.set_modem_control_1(config), Err(RadioError::InvalidConfiguration(_))));
+        assert!(radio.spi.transfers.is_empty());
+    }
+
+    #[test]
+    fn test_read_modem_control_1() {
+        let mut radio = mock_radio();
+        // CORR_THR = 20 (default)
+        radio.spi.respond(&[0x00, 0x05, 0x00]);
+
+        let register = radio.read_modem_control_1().unwrap();
+
+        assert_eq!(radio.spi.transfers[0][0], 0x12);
+        assert_eq!(register.corr_threshold, 20);
+        assert_eq!(register, ModemControlRegister1Builder::default().build().unwrap());
+    }
+
+    #[test]
+    fn test_set_fsm_constants() {
+        let mut radio = mock_radio();
+        let constants = FiniteStateMachineConstantsBuilder::default()
+            .tc_paon_to_tx(15)
+            .build()
+            .unwrap();
+
+        radio.set_fsm_constants(constants).unwrap();
+
+        assert_eq!(radio.spi.transfers[0], vec![0x20 | 1 << 6, 0x7B, 0xD4]);
+    }
+
+    #[test]
+    fn test_set_fsm_constants_invalid() {
+        let mut radio = mock_radio();
+        let mut constants = FiniteStateMachineConstantsBuilder::default().build().unwrap();
+        constants.tc_switch_to_tx = 8;
+
+        assert!(matches!(radio.set_fsm_constants(constants), Err(RadioError::InvalidConfiguration(_))));
+        constants.tc_switch_to_tx = 6;
+        constants.tc_rxchain_to_rx = 8;
+        assert!(matches!(radio.set_fsm_constants(constants), Err(RadioError::InvalidConfiguration(_))));
+        assert!(radio.spi.transfers.is_empty());
+    }
+
+    #[test]
+    fn test_read_fsm_constants() {
+        let mut radio = mock_radio();
+        // Reset value
+        radio.spi.respond(&[0x00, 0x7A, 0x94]);
+
+        let constants = radio.read_fsm_constants().unwrap();
+
+        assert_eq!(radio.spi.transfers[0][0], 0x20);
+        assert_eq!(constants, FiniteStateMachineConstantsBuilder::default().build().unwrap());
+    }
+
+    #[test]
+    fn test_configure_correlation_threshold_readback_failure() {
+        let mut radio = mock_radio();
+        let config = config::ConfigurationBuilder::default()
+            .correlation_threshold(12)
+            .build()
+            .unwrap();
+        let modem_config = ModemControlRegister0Builder::default().build().unwrap().register_value().to_be_bytes();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, modem_config[0], modem_config[1]]);
+        // MDMCTRL1 read back with the default CORR_THR (20)
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, 0x05, 0x00]);
+
+        assert!(matches!(
+            radio.configure_minimal(config, &mut NoDelay),
+            Err(RadioError::FailedConfiguration("Configuration of Correlation Threshold Failed")),
+        ));
+        // CORR_THR = 12
+        assert_eq!(radio.spi.transfers[2], vec![0x12 | 1 << 6, 0x03, 0x00]);
+    }
+
+    #[test]
+    fn test_configure_io() {
+        let mut radio = mock_radio();
+        let config = IOConfigurationRegisterBuilder::default()
+            .fifop_threshold(20)
+            .fifop_polarity(true)
+            .build()
+            .unwrap();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, 0x02, 0x14]);
+
+        radio.configure_io(config).unwrap();
+
+        assert_eq!(radio.spi.transfers[0], vec![0x1C | 1 << 6, 0x02, 0x14]);
+        assert_eq!(radio.spi.transfers[1][0], 0x1C);
+    }
+
+    #[test]
+    fn test_configure_io_readback_failure() {
+        let mut radio = mock_radio();
+        let config = IOConfigurationRegisterBuilder::default().fifop_threshold(20).build().unwrap();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, 0x00, 0x40]);
+
+        assert!(matches!(
+            radio.configure_io(config),
+            Err(RadioError::FailedConfiguration("Configuration of IO Failed")),
+        ));
+    }
+
+    #[test]
+    fn test_configure_io_invalid() {
+        let mut radio = mock_radio();
+        let mut config = IOConfigurationRegisterBuilder::default().build().unwrap();
+        config.fifop_threshold = 128;
+        let mut config_1 = IOConfigurationRegister1Builder::default().build().unwrap();
+        config_1.cca_mux = 32;
+
+        assert!(matches!(radio.configure_io(config), Err(RadioError::InvalidConfiguration(_))));
+        assert!(matches!(radio.configure_io1(config_1), Err(RadioError::InvalidConfiguration(_))));
+        assert!(radio.spi.transfers.is_empty());
+    }
+
+    #[test]
+    fn test_configure_io1() {
+        let mut radio = mock_radio();
+        let config = IOConfigurationRegister1Builder::default()
+            .sfd_mux(3)
+            .cca_mux(5)
+            .build()
+            .unwrap();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, 0x00, 0x65]);
+
+        radio.configure_io1(config).unwrap();
+
+        assert_eq!(radio.spi.transfers[0], vec![0x1D | 1 << 6, 0x00, 0x65]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, 0x00, 0x00]);
+        assert!(matches!(
+            radio.configure_io1(config),
+            Err(RadioError::FailedConfiguration("Configuration of IO 1 Failed")),
+        ));
+    }
+
+    #[test]
+    fn test_set_sync_word_bytes() {
+        let mut radio = mock_radio();
+
+        radio.set_sync_word_bytes([0x0F, 0xA7]).unwrap();
+
+        assert_eq!(radio.spi.transfers[0], vec![0x14 | 1 << 6, 0xA7, 0x0F]);
+    }
+
+    #[test]
+    fn test_read_sync_word_bytes() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0xA7, 0x0F]);
+
+        assert_eq!(radio.read_sync_word_bytes().unwrap(), [0x0F, 0xA7]);
+        assert_eq!(radio.spi.transfers[0][0], 0x14);
+    }
+
+    #[cfg(feature = "cache")]
+    #[test]
+    fn test_read_modify_write_uses_cache() {
+        let mut radio = mock_radio();
+        // SECCTRL0 = 0x0344 (SEC_SA_KEYSEL = 0)
+        radio.spi.respond(&[0x00, 0x03, 0x44]);
+
+        radio.set_standalone_key(true).unwrap();
+        radio.set_tx_key(false).unwrap();
+
+        // The second update uses the value written by the first
+        assert_eq!(radio.spi.transfers.len(), 3);
+        assert_eq!(radio.spi.transfers[1], vec![0x19 | 1 << 6, 0x03, 0xC4]);
+        assert_eq!(radio.spi.transfers[2], vec![0x19 | 1 << 6, 0x03, 0x84]);
+
+        radio.invalidate_cache();
+        radio.spi.respond(&[0x00, 0x03, 0x44]);
+        radio.set_rx_key(true).unwrap();
+
+        assert_eq!(radio.spi.transfers.len(), 5);
+        assert_eq!(radio.spi.transfers[3][0], 0x19);
+        assert_eq!(radio.spi.transfers[4], vec![0x19 | 1 << 6, 0x03, 0x64]);
+    }
+
+    #[cfg(feature = "cache")]
+    #[test]
+    fn test_reset_invalidates_cache() {
+        let mut radio = mock_radio();
+        // IOCFG0 with the FIFOP polarity inverted
+        radio.spi.respond(&[0x00, 0x02, 0x40]);
+        radio.set_fifop_threshold(0x10).unwrap();
+
+        radio.full_reset(&mut NoDelay).unwrap();
+        // The default IOCFG0 is read back from the chip, not the cache
+        radio.spi.respond(&[0x00, 0x00, 0x40]);
+        radio.set_fifop_threshold(0x20).unwrap();
+
+        let transfers = radio.spi.transfers.len();
+        assert_eq!(radio.spi.transfers[transfers - 2][0], 0x1C);
+        assert_eq!(radio.spi.transfers[transfers - 1], vec![0x1C | 1 << 6, 0x00, 0x20]);
+    }
+
+    #[test]
+    fn test_receive_frame_bounded() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x04]);
+        radio.spi.respond(&[0x00, 0x01, 0x02, 0x03, 0x84]);
+
+        let frame = radio.receive_frame_bounded::<16>().unwrap();
+
+        assert_eq!(frame.as_slice(), &[0x01, 0x02, 0x03, 0x84]);
+        assert_eq!(radio.spi.transfers[1].len(), 5);
+        // CRC OK, LQI 4
+        assert_eq!(radio.last_lqi, 0x04);
+    }
+
+    #[test]
+    fn test_receive_frame_bounded_too_small() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x04]);
+
+        assert!(matches!(
+            radio.receive_frame_bounded::<3>(),
+            Err(RadioError::BufferTooSmall { needed: 4, capacity: 3 }),
+        ));
+        // The whole frame is read out of the FIFO
+        assert_eq!(radio.spi.transfers[1].len(), 5);
+    }
+
+    #[test]
+    fn test_full_reset() {
+        let mut radio = mock_radio();
+
+        radio.full_reset(&mut NoDelay).unwrap();
+
+        assert_eq!(
+            radio.spi.transfers,
+            vec![
+                vec![0x10 | 1 << 6, 0b0111_1000, 0x00],
+                vec![0x10 | 1 << 6, 0b1111_1000, 0x00],
+                vec![Strobe::ReadStatus.opcode()],
+            ],
+        )
+    }
+
+    #[test]
+    fn test_check_enc_busy_idle() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0b0100_0000]);
+
+        radio.check_enc_busy_with_timeout(20, &mut NoDelay).unwrap();
+
+        assert_eq!(radio.spi.transfers.len(), 1);
+    }
+
+    #[test]
+    fn test_check_enc_busy_timeout() {
+        let mut radio = mock_radio();
+        for _ in 0..10 {
+            radio.spi.respond(&[0b0101_0000]);
+        }
+
+        assert!(matches!(
+            radio.check_enc_busy_with_timeout(20, &mut NoDelay),
+            Err(RadioError::Timeout),
+        ));
+        assert_eq!(radio.spi.transfers.len(), 5);
+    }
+
+    #[test]
+    fn test_radio_builder_missing_spi() {
+        let result = RadioBuilder::<MockSpi, _, MockInputPin, _, MockInputPin>::new()
+            .sfd(MockInputPin::default())
+            .fifo(MockInputPin::default())
+            .build(&mut NoDelay);
+
+        assert!(matches!(result, Err(RadioError::InvalidConfiguration("Missing SPI device"))));
+    }
+
+    #[test]
+    fn test_power_up() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0b0000_0000]);
+        radio.spi.respond(&[0b0000_0000]);
+        radio.spi.respond(&[0b0100_0000]);
+        radio.spi.respond(&[0b0100_0000]);
+
+        let status = radio.power_up(&mut NoDelay).unwrap();
+
+        assert!(status.xosx_stable);
+        assert!(radio.is_powered_up());
+        assert_eq!(
+            radio.spi.transfers,
+            vec![
+                vec![Strobe::XOSCOn.opcode()],
+                vec![Strobe::ReadStatus.opcode()],
+                vec![Strobe::ReadStatus.opcode()],
+                vec![Strobe::ReadStatus.opcode()],
+            ],
+        )
+    }
+
+    #[test]
+    fn test_wait_for_oscillator_timeout() {
+        let mut radio = mock_radio();
+
+        assert!(matches!(
+            radio.wait_for_oscillator(&mut NoDelay, 100),
+            Err(RadioError::Timeout),
+        ));
+        assert_eq!(radio.spi.transfers.len(), 3);
+    }
+
+    #[test]
+    fn test_recalibrate_rx() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0b0000_0100]);
+        radio.spi.respond(&[0x00, 0x01, 0x65]);
+        radio.spi.respond(&[0x00, 0x21, 0x65]);
+
+        radio.recalibrate_rx(&mut NoDelay).unwrap();
+
+        assert_eq!(radio.spi.transfers.len(), 5);
+        assert_eq!(radio.spi.transfers[0], vec![Strobe::CalibrateFrequency.opcode()]);
+        assert_eq!(radio.spi.transfers[1], vec![Strobe::ReadStatus.opcode()]);
+        assert_eq!(radio.spi.transfers[4], vec![Strobe::EnableRx.opcode()]);
+    }
+
+    #[test]
+    fn test_recalibrate_rx_timeout() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0b0000_0100]);
+
+        assert!(matches!(
+            radio.recalibrate_rx(&mut NoDelay),
+            Err(RadioError::Timeout),
+        ));
+        assert_eq!(radio.spi.transfers[0], vec![Strobe::CalibrateFrequency.opcode()]);
+        assert!(radio.spi.transfers[2..].iter().all(|transfer| transfer[0] == 0x18));
+    }
+
+    #[test]
+    fn test_wait_for_pll_lock() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0b0000_0100]);
+
+        radio.wait_for_pll_lock(&mut NoDelay, 100).unwrap();
+
+        assert_eq!(radio.spi.transfers.len(), 2);
+        assert!(radio.spi.transfers.iter().all(|transfer| transfer == &vec![Strobe::ReadStatus.opcode()]));
+    }
+
+    #[test]
+    fn test_wait_for_pll_lock_timeout() {
+        let mut radio = mock_radio();
+
+        assert!(matches!(
+            radio.wait_for_pll_lock(&mut NoDelay, 100),
+            Err(RadioError::Timeout),
+        ));
+        // Polled every 10us for 100us
+        assert_eq!(radio.spi.transfers.len(), 11);
+    }
+
+    #[test]
+    fn test_verify_chip_id() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x23, 0x3D]);
+        radio.spi.respond(&[0x00, 0x30, 0x00]);
+        radio.spi.respond(&[0x00, 0x23, 0x3D]);
+
+        radio.verify_chip_id().unwrap();
+    }
+
+    #[test]
+    fn test_verify_chip_id_wrong_device() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x73, 0x3D]);
+        radio.spi.respond(&[0x00, 0x30, 0x00]);
+        radio.spi.respond(&[0x00, 0x73, 0x3D]);
+
+        assert!(matches!(
+            radio.verify_chip_id(),
+            Err(RadioError::WrongDevice { found_part: 0x007, found_manufacturer: 0x33D }),
+        ));
+    }
+
+    #[test]
+    fn test_verify_chip_id_wrong_manufacturer() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x20, 0x49]);
+        radio.spi.respond(&[0x00, 0x30, 0x00]);
+        radio.spi.respond(&[0x00, 0x20, 0x49]);
+
+        assert!(matches!(
+            radio.verify_chip_id(),
+            Err(RadioError::WrongDevice { found_part: 0x002, found_manufacturer: 0x049 }),
+        ));
+    }
+
+    #[test]
+    fn test_read_part_number_and_version() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x23, 0x3D]);
+        radio.spi.respond(&[0x00, 0x30, 0x00]);
+
+        assert_eq!(radio.read_part_number_and_version().unwrap(), (0x002, 3));
+        assert_eq!(radio.spi.transfers[0][0], 0x1E);
+        assert_eq!(radio.spi.transfers[1][0], 0x1F);
+    }
+
+    #[test]
+    fn test_is_cc2420() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x23, 0x3D]);
+        radio.spi.respond(&[0x00, 0x30, 0x00]);
+        assert!(radio.is_cc2420().unwrap());
+
+        radio.spi.respond(&[0x00, 0x23, 0x3D]);
+        radio.spi.respond(&[0x00, 0x20, 0x00]);
+        assert!(!radio.is_cc2420().unwrap());
+
+        radio.spi.respond(&[0x00, 0x53, 0x3D]);
+        radio.spi.respond(&[0x00, 0x30, 0x00]);
+        assert!(!radio.is_cc2420().unwrap());
+    }
+
+    #[test]
+    fn test_write_register_byte_order() {
+        let mut radio = mock_radio();
+        let register = SyncWordRegisterBuilder::default().sync_word(0xA70F).build().unwrap();
+
+        radio.write_register(&register).unwrap();
+
+        assert_eq!(radio.spi.transfers, vec![vec![0x14 | 1 << 6, 0xA7, 0x0F]]);
+    }
+
+    #[test]
+    fn test_read_register_byte_order() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0xA7, 0x0F]);
+        let mut register = SyncWordRegisterBuilder::default().build().unwrap();
+
+        radio.read_register(&mut register).unwrap();
+
+        assert_eq!(register.sync_word, 0xA70F);
+    }
+
+    #[test]
+    fn test_read_receive_control_registers() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x3C, 0x01]);
+        radio.spi.respond(&[0x00, 0x01, 0x00]);
+        let mut control0 = ReceiveControlRegister0Builder::default().build().unwrap();
+        let mut control1 = ReceiveControlRegister1Builder::default().build().unwrap();
+
+        radio.read_register(&mut control0).unwrap();
+        radio.read_register(&mut control1).unwrap();
+
+        assert_eq!(control0.rx_mix_buf_current, 3);
+        assert_eq!(control0.high_lna_gain, 3);
+        assert_eq!(control0.med_lna_gain, 0);
+        assert_eq!(control0.low_lna_gain, 0);
+        assert_eq!(control0.high_lna_current, 0);
+        assert_eq!(control0.med_lna_current, 0);
+        assert_eq!(control0.low_lna_current, 1);
+        assert!(control1.med_hgm);
+        assert!(!control1.med_lowgain);
+        assert_eq!(control1.lna_cap_array, 0);
+    }
+
+    #[test]
+    fn test_configure_validates_before_spi() {
+        let mut radio = mock_radio();
+        let config = config::ConfigurationBuilder::default()
+            .auto_acknowledge(true)
+            .enable_crc(false)
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            radio.configure(config, &mut NoDelay, 1_000),
+            Err(RadioError::InvalidConfiguration(_)),
+        ));
+        assert!(radio.spi.transfers.is_empty());
+    }
+
+    fn respond_with_modem_control_1(radio: &mut MockRadio, config: Configuration) {
+        let modem_control_1 = ModemControlRegister1Builder::default()
+            .corr_threshold(config.correlation_threshold)
+            .build()
+            .unwrap()
+            .register_value()
+            .to_be_bytes();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, modem_control_1[0], modem_control_1[1]]);
+    }
+
+    fn respond_with_io_config(radio: &mut MockRadio, config: Configuration) {
+        let io_configuration = IOConfigurationRegisterBuilder::default()
+            .fifop_threshold(config.fifop_threshold)
+            .build()
+            .unwrap()
+            .register_value()
+            .to_be_bytes();
+        // Read for the FIFOP_THR update (BCN_ACCEPT and SFD active low), write,
+        // readback
+        radio.spi.respond(&[0x00, 0x09, 0x40]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, io_configuration[0], io_configuration[1]]);
+    }
+
+    fn respond_with_security_keys(radio: &mut MockRadio, config: Configuration) {
+        let security_control = SecurityControlRegister0Builder::default().build().unwrap().register_value().to_be_bytes();
+        let security_lengths = SecurityControlRegister1Builder::default()
+            .sec_txl(config.security.tx_cleartext_bytes)
+            .sec_rxl(config.security.rx_cleartext_bytes)
+            .build()
+            .unwrap()
+            .register_value()
+            .to_be_bytes();
+        // SECCTRL0 with in-line security disabled
+        radio.spi.respond(&[0x00, security_control[0], security_control[1]]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00]);
+        for bytes in [config.security.tx_key.0, config.security.rx_key.0] {
+            radio.spi.respond(&[[0x00, 0x00].as_slice(), &bytes].concat());
+        }
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00]);
+        for bytes in [config.tx_nonce, config.rx_nonce] {
+            radio.spi.respond(&[[0x00, 0x00].as_slice(), &bytes].concat());
+        }
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, security_lengths[0], security_lengths[1]]);
+    }
+
+    fn respond_with_security_config(radio: &mut MockRadio, config: Configuration) {
+        respond_with_security_keys(radio, config);
+        let security_control = SecurityControlRegister0Builder::default()
+            .sec_m(config.security.security_level.sec_m())
+            .sec_mode(config.security.security_mode.sec_mode())
+            .build()
+            .unwrap()
+            .register_value()
+            .to_be_bytes();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, security_control[0], security_control[1]]);
+    }
+
+    fn secure_configuration() -> Configuration {
+        config::ConfigurationBuilder::default()
+            .security(SecureConfiguration {
+                tx_key: EncryptionKey([0x01; 16]),
+                rx_key: EncryptionKey([0x02; 16]),
+                security_mode: SecurityMode::Ccm,
+                security_level: SecurityLevel::Mic8,
+                tx_cleartext_bytes: 9,
+                rx_cleartext_bytes: 11,
+            })
+            .tx_nonce([0x03; 16])
+            .rx_nonce([0x04; 16])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_configure_security() {
+        let mut radio = mock_radio();
+        let config = secure_configuration();
+        let modem_config = ModemControlRegister0Builder::default().build().unwrap().register_value().to_be_bytes();
+        let sync_word = u16::from_le_bytes(config.sync_word).to_be_bytes();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, modem_config[0], modem_config[1]]);
+        respond_with_modem_control_1(&mut radio, config);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, sync_word[0], sync_word[1]]);
+        respond_with_io_config(&mut radio, config);
+        respond_with_security_config(&mut radio, config);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0b0100_0000]);
+        radio.spi.respond(&[0b0100_0000]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0b0100_0100]);
+
+        let parts = ConfigureParts { addresses: false, keys: true };
+        radio.configure_parts(config, parts, &mut NoDelay).unwrap();
+
+        // Keys and nonces are written before in-line security is enabled
+        let tx_nonce_address = Ram::TxNonce.write_address();
+        assert_eq!(radio.spi.transfers[14][..2], [tx_nonce_address.0, tx_nonce_address.1]);
+        // SEC_TXL = 9, SEC_RXL = 11
+        assert_eq!(radio.spi.transfers[18], vec![0x1A | 1 << 6, 0x09, 0x0B]);
+        // SEC_M = 3, SEC_MODE = 3 (CCM)
+        assert_eq!(radio.spi.transfers[20], vec![0x19 | 1 << 6, 0x03, 0xCF]);
+    }
+
+    #[test]
+    fn test_configure_security_readback_failure() {
+        let mut radio = mock_radio();
+        let config = secure_configuration();
+        let modem_config = ModemControlRegister0Builder::default().build().unwrap().register_value().to_be_bytes();
+        let sync_word = u16::from_le_bytes(config.sync_word).to_be_bytes();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, modem_config[0], modem_config[1]]);
+        respond_with_modem_control_1(&mut radio, config);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, sync_word[0], sync_word[1]]);
+        respond_with_io_config(&mut radio, config);
+        respond_with_security_keys(&mut radio, config);
+        // SECCTRL0 read back with in-line security disabled
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, 0x03, 0xC4]);
+
+        let parts = ConfigureParts { addresses: false, keys: true };
+        assert!(matches!(
+            radio.configure_parts(config, parts, &mut NoDelay),
+            Err(RadioError::FailedConfiguration("Configuration of Security Mode Failed")),
+        ));
+    }
+
+    #[test]
+    fn test_configure_minimal_skips_security() {
+        let mut radio = mock_radio();
+        let config = secure_configuration();
+        let modem_config = ModemControlRegister0Builder::default().build().unwrap().register_value().to_be_bytes();
+        let sync_word = u16::from_le_bytes(config.sync_word).to_be_bytes();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, modem_config[0], modem_config[1]]);
+        respond_with_modem_control_1(&mut radio, config);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, sync_word[0], sync_word[1]]);
+        respond_with_io_config(&mut radio, config);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0b0100_0000]);
+        radio.spi.respond(&[0b0100_0000]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0b0100_0100]);
+
+        radio.configure_minimal(config, &mut NoDelay).unwrap();
+
+        // Neither SECCTRL0 nor SECCTRL1 is touched without the keys
+        assert!(radio.spi.transfers.iter().all(|transfer| !matches!(transfer[0] & 0x3F, 0x19 | 0x1A)));
+    }
+
+    #[test]
+    fn test_configure_coordinator_is_associated() {
+        let mut radio = mock_radio();
+        let config = config::ConfigurationBuilder::default()
+            .pan_coordinator(true)
+            .short_address([0x00, 0x00])
+            .pan_identifier([0x00, 0x00])
+            .ieee_address([0x00; 8])
+            .build()
+            .unwrap();
+        let modem_config = ModemControlRegister0Builder::default()
+            .pan_coordinator(true)
+            .build()
+            .unwrap()
+            .register_value()
+            .to_be_bytes();
+        let sync_word = u16::from_le_bytes(config.sync_word).to_be_bytes();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, modem_config[0], modem_config[1]]);
+        respond_with_modem_control_1(&mut radio, config);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, sync_word[0], sync_word[1]]);
+        respond_with_io_config(&mut radio, config);
+        // Addresses are read back as written (all zero)
+        for _ in 0..6 {
+            radio.spi.respond(&[0x00]);
+        }
+        respond_with_security_config(&mut radio, config);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0b0100_0000]);
+        radio.spi.respond(&[0b0100_0000]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0b0100_0100]);
+
+        assert_eq!(radio.association_status(), &AssociationStatus::NotAssociated);
+        radio.configure(config, &mut NoDelay, 1_000).unwrap();
+
+        assert_eq!(
+            radio.association_status(),
+            &AssociationStatus::Associated { pan_id: PanId(0x0000), short_addr: ShortAddress(0x0000) },
+        );
+    }
+
+    #[test]
+    fn test_configure_oscillator_timeout() {
+        let mut radio = mock_radio();
+        let config = config::ConfigurationBuilder::default()
+            .short_address([0x00, 0x00])
+            .pan_identifier([0x00, 0x00])
+            .ieee_address([0x00; 8])
+            .build()
+            .unwrap();
+        let modem_config = ModemControlRegister0Builder::default().build().unwrap().register_value().to_be_bytes();
+        let sync_word = u16::from_le_bytes(config.sync_word).to_be_bytes();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, modem_config[0], modem_config[1]]);
+        respond_with_modem_control_1(&mut radio, config);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, sync_word[0], sync_word[1]]);
+        respond_with_io_config(&mut radio, config);
+        for _ in 0..6 {
+            radio.spi.respond(&[0x00]);
+        }
+        respond_with_security_config(&mut radio, config);
+        // The oscillator never reports stable (the status reads echo SNOP)
+
+        assert!(matches!(
+            radio.configure(config, &mut NoDelay, 100),
+            Err(RadioError::Timeout),
+        ));
+        assert!(!radio.is_powered_up());
+        // XOSCON followed by three status polls
+        assert_eq!(radio.spi.transfers[radio.spi.transfers.len() - 4], vec![Strobe::XOSCOn.opcode()]);
+        let polls = &radio.spi.transfers[radio.spi.transfers.len() - 3..];
+        assert!(polls.iter().all(|transfer| transfer == &vec![Strobe::ReadStatus.opcode()]));
+    }
+
+    fn respond_with_radio_config(radio: &mut MockRadio, config: RadioConfig) {
+        let mut frequency_synthesizer = FrequencySynthesizerRegisterBuilder::default().build().unwrap();
+        frequency_synthesizer.frequency = Frequency::from_channel(config.channel);
+        let transmit_control = TransmitControlRegisterBuilder::default()
+            .pa_level(config.output_power.pa_level())
+            .build()
+            .unwrap();
+        let rssi = RSSIRegisterBuilder::default().cca_threshold(config.cca_threshold).build().unwrap();
+        let modem_config = ModemControlRegister0Builder::default().cca_mode(config.cca_mode).build().unwrap();
+        for value in [
+            frequency_synthesizer.register_value(),
+            transmit_control.register_value(),
+            rssi.register_value(),
+            modem_config.register_value(),
+        ] {
+            let bytes = value.to_be_bytes();
+            radio.spi.respond(&[0x00, bytes[0], bytes[1]]);
+        }
+    }
+
+    #[test]
+    fn test_read_radio_config() {
+        let mut radio = mock_radio();
+        let config = RadioConfig {
+            channel: Channel::try_from(20).unwrap(),
+            output_power: OutputPower::DbmMinus10,
+            cca_threshold: -40,
+            cca_mode: 1,
+        };
+        respond_with_radio_config(&mut radio, config);
+
+        assert_eq!(radio.read_radio_config().unwrap(), config);
+    }
+
+    #[test]
+    fn test_apply_radio_config() {
+        let mut radio = mock_radio();
+        let config = RadioConfig {
+            channel: Channel::try_from(26).unwrap(),
+            output_power: OutputPower::DbmMinus3,
+            cca_threshold: -20,
+            cca_mode: 2,
+        };
+        // FSCTRL, TXCTRL, RSSI and MDMCTRL0 writes
+        for _ in 0..7 {
+            radio.spi.respond(&[0x00]);
+        }
+        respond_with_radio_config(&mut radio, config);
+
+        radio.apply_radio_config(config, &mut NoDelay).unwrap();
+
+        assert_eq!(radio.spi.transfers.len(), 12);
+        assert_eq!(radio.spi.transfers[4], vec![0x13 | 1 << 6, 0xEC, 0x00]);
+    }
+
+    #[test]
+    fn test_apply_radio_config_invalid_cca_mode() {
+        let mut radio = mock_radio();
+        let config = RadioConfig { cca_mode: 0, ..Default::default() };
+
+        assert!(matches!(
+            radio.apply_radio_config(config, &mut NoDelay),
+            Err(RadioError::InvalidConfiguration(_)),
+        ));
+        assert!(radio.spi.transfers.is_empty());
+    }
+
+    #[test]
+    fn test_configure_minimal_skips_ram() {
+        let mut radio = mock_radio();
+        let config = config::ConfigurationBuilder::default().build().unwrap();
+        let modem_config = ModemControlRegister0Builder::default().build().unwrap().register_value().to_be_bytes();
+        let sync_word = u16::from_le_bytes(config.sync_word).to_be_bytes();
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, modem_config[0], modem_config[1]]);
+        respond_with_modem_control_1(&mut radio, config);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, sync_word[0], sync_word[1]]);
+        respond_with_io_config(&mut radio, config);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0b0100_0000]);
+        radio.spi.respond(&[0b0100_0000]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0b0100_0100]);
+
+        radio.configure_minimal(config, &mut NoDelay).unwrap();
+
+        assert!(radio.is_powered_up());
+        assert_eq!(radio.spi.transfers.len(), 14);
+        // Only FIFOP_THR is updated, BCN_ACCEPT and the SFD polarity are kept
+        assert_eq!(radio.spi.transfers[7], vec![0x1C | 1 << 6, 0x09, config.fifop_threshold]);
+        assert!(radio.spi.transfers.iter().all(|transfer| transfer[0] & 0x80 == 0));
+    }
+
+    #[test]
+    fn test_apply_receive_filter() {
+        let mut radio = mock_radio();
+        let filter = ReceiveFilter {
+            pan_id: PanId(0x1234),
+            short_addr: ShortAddress(0x0001),
+            ieee_addr: IEEEAddress([1, 2, 3, 4, 5, 6, 7, 8]),
+            address_decode: true,
+            accept_beacons: true,
+        };
+        // MDMCTRL0 reset value (ADR_DECODE set)
+        radio.spi.respond(&[0x00, 0x0A, 0xE2]);
+        for _ in 0..4 {
+            radio.spi.respond(&[0x00]);
+        }
+        radio.spi.respond(&[0x00, 0x00, 0x34, 0x12]);
+        radio.spi.respond(&[0x00, 0x00, 0x01, 0x00]);
+        radio.spi.respond(&[0x00, 0x00, 1, 2, 3, 4, 5, 6, 7, 8]);
+        // IOCFG0 reset value
+        radio.spi.respond(&[0x00, 0x00, 0x40]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, 0x0A, 0xE2]);
+
+        radio.apply_receive_filter(filter, &mut NoDelay).unwrap();
+
+        let pan_id_address = Ram::PanID.write_address();
+        assert_eq!(radio.spi.transfers[1], vec![0x11 | 1 << 6, 0x02, 0xE2]);
+        // The PAN ID of the node is kept, beacons are accepted with BCN_ACCEPT
+        assert_eq!(radio.spi.transfers[2], vec![pan_id_address.0, pan_id_address.1, 0x34, 0x12]);
+        assert_eq!(radio.spi.transfers[9], vec![0x1C | 1 << 6, 0x08, 0x40]);
+        assert_eq!(radio.spi.transfers[10], vec![0x11 | 1 << 6, 0x0A, 0xE2]);
+    }
+
+    #[test]
+    fn test_apply_receive_filter_verification_failure() {
+        let mut radio = mock_radio();
+        let filter = ReceiveFilter {
+            pan_id: PanId(0x1234),
+            address_decode: true,
+            ..Default::default()
+        };
+        // MDMCTRL0 reset value (ADR_DECODE set)
+        radio.spi.respond(&[0x00, 0x0A, 0xE2]);
+
+        assert!(matches!(
+            radio.apply_receive_filter(filter, &mut NoDelay),
+            Err(RadioError::FailedConfiguration("Configuration of Pan ID Failed")),
+        ));
+        // Address decoding is disabled and never re-enabled
+        let modem_writes: Vec<_> = radio.spi.transfers.iter().filter(|transfer| transfer[0] == (0x11 | 1 << 6)).collect();
+        assert_eq!(modem_writes, vec![&vec![0x11 | 1 << 6, 0x02, 0xE2]]);
+    }
+
+    #[test]
+    fn test_set_security_mode() {
+        let mut radio = mock_radio();
+        // SECCTRL0 reset value
+        radio.spi.respond(&[0x00, 0x03, 0xE4]);
+
+        radio.set_security_mode(SecurityMode::Ccm, SecurityLevel::Mic8).unwrap();
+
+        // SEC_M = 3, SEC_MODE = 3
+        assert_eq!(radio.spi.transfers[1], vec![0x19 | 1 << 6, 0x03, 0xEF]);
+    }
+
+    #[test]
+    fn test_disable_security() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x03, 0xEF]);
+
+        radio.disable_security().unwrap();
+
+        // Only SEC_MODE is cleared
+        assert_eq!(radio.spi.transfers[1], vec![0x19 | 1 << 6, 0x03, 0xEC]);
+    }
+
+    #[test]
+    fn test_apply_security_configuration_invalid_length() {
+        let mut radio = mock_radio();
+        let sec = SecureConfiguration {
+            tx_cleartext_bytes: 128,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            radio.apply_security_configuration(sec, &mut NoDelay),
+            Err(RadioError::InvalidConfiguration(_)),
+        ));
+        assert!(radio.spi.transfers.is_empty());
+    }
+
+    #[test]
+    fn test_rx_fifo_is_empty() {
+        let mut radio = mock_radio();
+        assert!(radio.rx_fifo_is_empty().unwrap());
+
+        radio.fifo.high = true;
+        assert!(!radio.rx_fifo_is_empty().unwrap());
+    }
+
+    #[test]
+    fn test_tx_fifo_is_underflowed() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0b0110_0000]);
+
+        assert!(radio.tx_fifo_is_underflowed().unwrap());
+        assert_eq!(radio.spi.transfers, vec![vec![Strobe::ReadStatus.opcode()]]);
+    }
+
+    #[test]
+    fn test_receive_frame_overflow() {
+        let mut radio = Radio::new_with_fifop(
+            MockSpi::new(),
+            MockInputPin::default(),
+            MockInputPin::default(),
+            MockInputPin { high: true },
+        );
+
+        assert!(radio.rx_fifo_is_overflowed().unwrap());
+        assert!(matches!(radio.receive_frame(), Err(RadioError::FifoOverflow)));
+        assert!(matches!(radio.receive_frame_bounded::<16>(), Err(RadioError::FifoOverflow)));
+        assert!(radio.spi.transfers.is_empty());
+
+        // FIFOP asserted with data in the RX FIFO is a complete frame
+        radio.fifo.high = true;
+        assert!(!radio.rx_fifo_is_overflowed().unwrap());
+    }
+
+    #[test]
+    fn test_send_ack_underflow() {
+        let mut radio = mock_radio();
+        radio.state = RadioState::Receiving;
+        // Flush, write TX FIFO, STXON, then a status byte with TX_UNDERFLOW set
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0b0010_0000]);
+
+        assert!(matches!(radio.send_ack(0x01, false, &mut NoDelay), Err(RadioError::FifoUnderflow)));
+    }
+
+    #[test]
+    fn test_send_underflow() {
+        let mut radio = mock_radio();
+        // Flush, first chunk, STXON, second chunk, then STXON reporting an
+        // underflow
+        for _ in 0..4 {
+            radio.spi.respond(&[0x00]);
+        }
+        radio.spi.respond(&[0b0010_0000]);
+        radio.sfd.high = true;
+
+        assert!(matches!(radio.send(&[0x00; 200], false, &mut NoDelay), Err(RadioError::FifoUnderflow)));
+    }
+
+    #[test]
+    fn test_listen_for_ack() {
+        let mut radio = mock_radio();
+        radio.fifo.high = true;
+        // Status (TX finished), enable RX, length byte, then the ACK frame
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, 0x05]);
+        radio.spi.respond(&[0x00, 0x02, 0x00, 0x07, 0xD0, 0x80]);
+
+        assert!(radio.listen_for_ack(0x07, 1000, &mut NoDelay).unwrap());
+    }
+
+    #[test]
+    fn test_listen_for_ack_wrong_sequence_number() {
+        let mut radio = mock_radio();
+        radio.fifo.high = true;
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00]);
+        radio.spi.respond(&[0x00, 0x05]);
+        radio.spi.respond(&[0x00, 0x02, 0x00, 0x08, 0xD0, 0x80]);
+
+        assert!(!radio.listen_for_ack(0x07, 200, &mut NoDelay).unwrap());
+    }
+
+    fn respond_with_frame(radio: &mut MockRadio, frame: &[u8], link_quality: u8) {
+        let length = frame[0] as usize;
+        let mut response = vec![0x00];
+        response.extend_from_slice(&frame[1..(length - 1)]);
+        response.extend_from_slice(&[0x00, 0x80 | link_quality]);
+        radio.spi.respond(&[0x00, frame[0]]);
+        radio.spi.respond(&response);
+    }
+
+    fn respond_with_beacon(radio: &mut MockRadio, address: ShortAddress, link_quality: u8) {
+        let mut builder = frame::beacon_frame_builder();
+        builder.superframe_spec.association_permit = true;
+        let beacon = builder.build(0, PanId(0x1234), address.into()).unwrap();
+        respond_with_frame(radio, &beacon, link_quality);
+    }
+
+    #[test]
+    fn test_active_scan() {
+        let mut radio = mock_radio();
+        radio.fifo.high = true;
+        // Read PAN ID, set PAN ID, read / write FSCTRL, enable RX, send the
+        // beacon request (flush, write TX FIFO, transmit)
+        for _ in 0..8 {
+            radio.spi.respond(&[0x00]);
+        }
+        // TX_ACTIVE, the beacon request is being sent
+        radio.spi.respond(&[0b0000_1000]);
+        respond_with_beacon(&mut radio, ShortAddress(0x0001), 40);
+        respond_with_beacon(&mut radio, ShortAddress(0x0002), 100);
+        respond_with_beacon(&mut radio, ShortAddress(0x0001), 40);
+
+        let descriptors = radio.active_scan(&[Channel::try_from(15).unwrap()], 0, &mut NoDelay).unwrap();
+
+        assert_eq!(descriptors.len(), 2);
+        assert_eq!(descriptors[0].coordinator_address, Address::Short(ShortAddress(0x0002)));
+        assert_eq!(descriptors[0].link_quality, 100);
+        assert_eq!(descriptors[1].coordinator_address, Address::Short(ShortAddress(0x0001)));
+        assert_eq!(descriptors[1].coordinator_pan, PanId(0x1234));
+        assert_eq!(descriptors[1].channel, Channel::try_from(15).unwrap());
+        // RX is disabled, then the PAN ID is restored
+        let pan_id_address = Ram::PanID.write_address();
+        let cleanup = &radio.spi.transfers[(radio.spi.transfers.len() - 2)..];
+        assert_eq!(cleanup[0], vec![Strobe::DisableRxTx.opcode()]);
+        assert_eq!(cleanup[1][..2], [pan_id_address.0, pan_id_address.1]);
+    }
+
+    #[test]
+    fn test_active_scan_channel_busy() {
+        let mut radio = mock_radio();
+
+        // The beacon request is never sent (TX_ACTIVE and SFD stay low)
+        assert!(matches!(
+            radio.active_scan(&[Channel::try_from(15).unwrap()], 0, &mut NoDelay),
+            Err(RadioError::ChannelBusy),
+        ));
+        // RX is still disabled and the PAN ID restored
+        let pan_id_address = Ram::PanID.write_address();
+        let cleanup = &radio.spi.transfers[(radio.spi.transfers.len() - 2)..];
+        assert_eq!(cleanup[0], vec![Strobe::DisableRxTx.opcode()]);
+        assert_eq!(cleanup[1][..2], [pan_id_address.0, pan_id_address.1]);
+        assert_eq!(radio.state(), RadioState::Idle);
+    }
+
+    #[test]
+    fn test_scan_and_associate_no_coordinator() {
+        let mut radio = mock_radio();
+        // Every beacon request is sent
+        radio.sfd.high = true;
+        // PAN ID 0x1234
+        radio.spi.respond(&[0x00, 0x00, 0x34, 0x12]);
+
+        assert_eq!(radio.scan_and_associate(None, &mut NoDelay).unwrap(), AssociationResult::TimedOut);
+
+        // The PAN ID is restored after scanning with the broadcast PAN ID
+        let pan_id_address = Ram::PanID.write_address();
+        let pan_id_writes: Vec<_> = radio.spi.transfers.iter()
+            .filter(|transfer| transfer.starts_with(&[pan_id_address.0, pan_id_address.1]))
+            .collect();
+        assert_eq!(pan_id_writes.first().unwrap()[2..], [0xFF, 0xFF]);
+        assert_eq!(pan_id_writes.last().unwrap()[2..], [0x34, 0x12]);
+        assert_eq!(radio.association_status(), &AssociationStatus::NotAssociated);
+    }
+
+    #[test]
+    fn test_scan_and_associate_channel_busy() {
+        let mut radio = mock_radio();
+        // PAN ID 0x1234
+        radio.spi.respond(&[0x00, 0x00, 0x34, 0x12]);
+
+        // The first beacon request is never sent
+        assert!(matches!(radio.scan_and_associate(None, &mut NoDelay), Err(RadioError::ChannelBusy)));
+
+        let pan_id_address = Ram::PanID.write_address();
+        let pan_id_write = radio.spi.transfers.iter()
+            .rev()
+            .find(|transfer| transfer.starts_with(&[pan_id_address.0, pan_id_address.1]))
+            .unwrap();
+        assert_eq!(pan_id_write[2..], [0x34, 0x12]);
+        assert_eq!(radio.association_status(), &AssociationStatus::NotAssociated);
+    }
+
+    #[test]
+    fn test_orphan_scan() {
+        let mut radio = mock_radio();
+        radio.fifo.high = true;
+        // Read the IEEE address, read / write FSCTRL, enable RX, send the
+        // orphan notification (flush, write TX FIFO, transmit)
+        for _ in 0..7 {
+            radio.spi.respond(&[0x00]);
+        }
+        // TX_ACTIVE, the orphan notification is being sent
+        radio.spi.respond(&[0b0000_1000]);
+        let realignment = frame::coordinator_realignment_frame(
+            0,
+            Channel::try_from(20).unwrap(),
+            PanId(0x1234),
+            IEEEAddress([0x11; 8]),
+            ShortAddress(0x0000),
+            Some((IEEEAddress::default(), ShortAddress(0x0042))),
+        );
+        respond_with_frame(&mut radio, &realignment, 60);
+
+        let descriptor = radio.orphan_scan(0, &mut NoDelay).unwrap().unwrap();
+
+        assert_eq!(descriptor.coordinator_pan, PanId(0x1234));
+        assert_eq!(descriptor.coordinator_address, Address::Extended(IEEEAddress([0x11; 8])));
+        assert_eq!(descriptor.channel, Channel::try_from(20).unwrap());
+        assert_eq!(descriptor.link_quality, 60);
+    }
+
+    #[test]
+    fn test_orphan_scan_no_coordinator() {
+        let mut radio = mock_radio();
+        radio.sfd.high = true;
+
+        assert_eq!(radio.orphan_scan(0, &mut NoDelay).unwrap(), None);
+    }
+
+    #[test]
+    fn test_orphan_scan_sequence_numbers() {
+        let mut radio = mock_radio();
+        radio.sfd.high = true;
+        radio.set_sequence_number(0xF8);
+
+        radio.orphan_scan(0, &mut NoDelay).unwrap();
+
+        // One orphan notification per channel, continuing the data sequence
+        let sequence_numbers: Vec<u8> = radio.spi.transfers.iter()
+            .filter(|transfer| transfer[0] == Strobe::TxFifo.opcode())
+            .map(|transfer| transfer[4])
+            .collect();
+        let expected: Vec<u8> = (0..16).map(|i| 0xF8u8.wrapping_add(i)).collect();
+        assert_eq!(sequence_numbers, expected);
+        assert_eq!(radio.next_sequence_number(), 0x08);
+    }
+
+    #[test]
+    fn test_orphan_scan_channel_busy() {
+        let mut radio = mock_radio();
+
+        // The orphan notification is never sent (TX_ACTIVE and SFD stay low)
+        assert!(matches!(radio.orphan_scan(0, &mut NoDelay), Err(RadioError::ChannelBusy)));
+        assert_eq!(radio.spi.transfers.iter().filter(|transfer| transfer[0] == Strobe::TxFifo.opcode()).count(), 1);
+    }
+
+    #[test]
+    fn test_send_timeout() {
+        let mut radio = mock_radio();
+        radio.state = RadioState::Receiving;
+
+        // SFD never goes active, so the second chunk is never written
+        assert!(matches!(radio.send(&[0x00; 200], true, &mut NoDelay), Err(RadioError::Timeout)));
+        assert_eq!(radio.spi.transfers.iter().filter(|transfer| transfer[0] == Strobe::TxFifo.opcode()).count(), 1);
+    }
+
+    fn test_send_length(len: usize) {
+        let mut radio = mock_radio();
+        radio.sfd.high = true;
+        let data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+
+        radio.send(&data, false, &mut NoDelay).unwrap();
+
+        let mut expected = vec![vec![Strobe::FlushTx.opcode()]];
+        for chunk in data.chunks(128) {
+            let mut write = vec![Strobe::TxFifo.opcode()];
+            write.extend_from_slice(chunk);
+            expected.push(write);
+            expected.push(vec![Strobe::EnableTx.opcode()]);
+        }
+        assert_eq!(radio.spi.transfers, expected);
+    }
+
+    #[test]
+    fn test_send_0_bytes() {
+        test_send_length(0);
+    }
+
+    #[test]
+    fn test_send_128_bytes() {
+        test_send_length(128);
+    }
+
+    #[test]
+    fn test_send_200_bytes() {
+        test_send_length(200);
+    }
+
+    #[test]
+    fn test_send_256_bytes() {
+        test_send_length(256);
+    }
+
+    #[test]
+    fn test_send_384_bytes() {
+        test_send_length(384);
+    }
+
+    #[cfg(feature = "replay-protection")]
+    #[test]
+    fn test_check_replay() {
+        let mut radio = mock_radio();
+        assert!(radio.check_replay(ShortAddress(0x0001), 1));
+        assert!(radio.check_replay(ShortAddress(0x0001), 1));
+
+        radio.enable_replay_protection();
+        assert!(radio.check_replay(ShortAddress(0x0001), 1));
+        assert!(!radio.check_replay(ShortAddress(0x0001), 1));
+        assert!(radio.check_replay(ShortAddress(0x0001), 2));
+
+        radio.disable_replay_protection();
+        radio.enable_replay_protection();
+        assert!(radio.check_replay(ShortAddress(0x0001), 1));
+    }
+
+    #[test]
+    fn test_receive_returns_spi_status() {
+        let mut radio = mock_radio();
+        // Status byte with XOSC16M_STABLE, LOCK and RSSI_VALID set, followed
+        // by data which would decode to a status with none of them set
+        radio.spi.respond(&[0b0100_0110, 0x00, 0x00]);
+        let mut buffer = [0u8; 2];
+
+        let status = radio.receive(&mut buffer).unwrap();
+
+        assert!(status.xosx_stable);
+        assert!(status.lock);
+        assert!(status.rssi_valid);
+    }
+
+    #[test]
+    fn test_receive_frame() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x05]);
+        radio.spi.respond(&[0x00, 0x02, 0x00, 0x2A, 0xD0, 0xE5]);
+
+        let frame = radio.receive_frame().unwrap();
+
+        assert_eq!(radio.spi.transfers, vec![
+            vec![Strobe::RxFifo.opcode(), 0x00],
+            vec![Strobe::RxFifo.opcode(), 0x00, 0x00, 0x00, 0x00, 0x00],
+        ]);
+        assert_eq!(frame.bytes(), &[0x02, 0x00, 0x2A, 0xD0, 0xE5]);
+        assert_eq!(frame.lqi, 0x65);
+        assert!(frame.crc_valid);
+    }
+
+    #[test]
+    fn test_last_frame_rssi_lqi() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x05]);
+        radio.spi.respond(&[0x00, 0x02, 0x00, 0x2A, 0xD0, 0xE5]);
+        // RSSI_VAL = -48
+        radio.spi.respond(&[0x00, 0x80, 0xD0]);
+
+        let _ = radio.receive_frame().unwrap();
+
+        assert_eq!(radio.last_frame_rssi_lqi().unwrap(), (-48, 0x65));
+        assert_eq!(radio.spi.transfers[2][0], 0x13);
+    }
+
+    #[test]
+    fn test_receive_frame_too_short() {
+        let mut radio = mock_radio();
+        radio.spi.respond(&[0x00, 0x01]);
+
+        assert!(matches!(
+            radio.receive_frame(),
+            Err(RadioError::InvalidFrame(frame::FrameError::FrameTooShort)),
+        ));
+    }
+
+    #[test]
+    fn test_receive_1_byte() {
+        test_receive_buffer(1);
+    }
+
+    #[test]
+    fn test_receive_64_bytes() {
+        test_receive_buffer(64);
+    }
+
+    #[test]
+    fn test_receive_128_bytes() {
+        test_receive_buffer(128);
+    }
+
+    #[test]
+    fn test_receive_129_bytes() {
+        test_receive_buffer(129);
+    }
+}
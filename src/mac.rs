@@ -0,0 +1,634 @@
+//!
+//! IEEE 802.15.4 MAC Layer Structures
+//! 
+
+use crate::address::{Address, IEEEAddress, PanId, ShortAddress};
+use crate::bounded_vec::{BoundedVec, CapacityError};
+use crate::channel::Channel;
+use crate::frame::{FrameType, MacCommand, ParsedFrame};
+
+/// Duration of one symbol (in us) for the 2.4 GHz O-QPSK PHY
+pub const SYMBOL_PERIOD_US: u32 = 16;
+
+/// aBaseSuperframeDuration, the number of symbols in a superframe with
+/// superframe order 0
+pub const BASE_SUPERFRAME_DURATION: u32 = 960;
+
+/// macResponseWaitTime, the maximum number of symbols to wait for a response
+/// to an association request (32 * aBaseSuperframeDuration)
+pub const RESPONSE_WAIT_TIME: u32 = 32 * BASE_SUPERFRAME_DURATION;
+
+/// macAckWaitDuration, the maximum number of symbols to wait for an
+/// acknowledgement after transmitting a frame (2.4 GHz O-QPSK PHY)
+pub const ACK_WAIT_DURATION: u32 = 54;
+
+/// aUnitBackoffPeriod, the number of symbols in a CSMA-CA backoff period
+pub const UNIT_BACKOFF_PERIOD: u32 = 20;
+
+/// macMinBE, the initial backoff exponent of CSMA-CA
+pub const MIN_BACKOFF_EXPONENT: u8 = 3;
+
+/// macMaxBE, the largest backoff exponent of CSMA-CA
+pub const MAX_BACKOFF_EXPONENT: u8 = 5;
+
+/// Source of random numbers for CSMA-CA backoffs (implemented by the user, so
+/// the driver does not depend on a particular random number generator).
+/// 
+/// Note: This is not rand_core::RngCore, a generator implementing that trait
+/// can be wrapped in a type forwarding next_u32.
+pub trait BackoffRng {
+    /// Return the next random u32
+    fn next_u32(&mut self) -> u32;
+}
+
+/// A random number of backoff periods in [0, 2^backoff_exponent - 1]
+pub fn random_backoff_periods(rng: &mut impl BackoffRng, backoff_exponent: u8) -> u32 {
+    rng.next_u32() % (1 << backoff_exponent.min(MAX_BACKOFF_EXPONENT))
+}
+
+/// The number of symbols spent listening on a channel during a scan of
+/// duration n (aBaseSuperframeDuration * (2^n + 1)), per 802.15.4 §7.5.2.1
+pub fn scan_duration_symbols(scan_duration: u8) -> u32 {
+    BASE_SUPERFRAME_DURATION * ((1 << scan_duration.min(14)) + 1)
+}
+
+/// Superframe specification field of a beacon frame (802.15.4 §7.2.2.1.2)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct SuperframeSpec {
+    // Interval between beacons (15 for a beaconless PAN)
+    pub beacon_order: u8,
+    // Length of the active portion of the superframe
+    pub superframe_order: u8,
+    // Last superframe slot of the contention access period
+    pub final_cap_slot: u8,
+    // Whether battery life extension is in use
+    pub battery_life_extension: bool,
+    // Whether the beacon is sent by the PAN coordinator
+    pub pan_coordinator: bool,
+    // Whether the coordinator is accepting association requests
+    pub association_permit: bool,
+}
+
+impl From<SuperframeSpec> for u16 {
+    fn from(value: SuperframeSpec) -> Self {
+        let mut spec = (value.beacon_order & 0x0F) as u16;
+        spec |= ((value.superframe_order & 0x0F) as u16) << 4;
+        spec |= ((value.final_cap_slot & 0x0F) as u16) << 8;
+        if value.battery_life_extension {
+            spec |= 1 << 12;
+        }
+        if value.pan_coordinator {
+            spec |= 1 << 14;
+        }
+        if value.association_permit {
+            spec |= 1 << 15;
+        }
+        spec
+    }
+}
+
+impl From<u16> for SuperframeSpec {
+    fn from(value: u16) -> Self {
+        Self {
+            beacon_order: (value & 0x0F) as u8,
+            superframe_order: ((value >> 4) & 0x0F) as u8,
+            final_cap_slot: ((value >> 8) & 0x0F) as u8,
+            battery_life_extension: (value & 1 << 12) != 0,
+            pan_coordinator: (value & 1 << 14) != 0,
+            association_permit: (value & 1 << 15) != 0,
+        }
+    }
+}
+
+/// Maximum number of PAN descriptors returned by an active scan
+pub const MAX_SCAN_RESULTS: usize = 16;
+
+/// Description of a PAN, assembled from a beacon received during a scan
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct PanDescriptor {
+    // PAN identifier of the coordinator
+    pub coordinator_pan: PanId,
+    // Address of the coordinator that sent the beacon
+    pub coordinator_address: Address,
+    // Channel the beacon was received on
+    pub channel: Channel,
+    // Superframe specification field of the beacon
+    pub superframe_spec: u16,
+    // Link quality (LQI) of the received beacon
+    pub link_quality: u8,
+}
+
+impl PanDescriptor {
+    /// Build a PAN descriptor from a received beacon frame, returning None
+    /// if the frame is not a beacon
+    pub(crate) fn from_beacon(frame: &ParsedFrame, channel: Channel, link_quality: u8) -> Option<Self> {
+        if frame.frame_type != FrameType::Beacon || frame.payload.len() < 2 {
+            return None;
+        }
+        Some(Self {
+            coordinator_pan: frame.src_pan?,
+            coordinator_address: frame.src_addr?,
+            channel,
+            superframe_spec: u16::from_le_bytes([frame.payload[0], frame.payload[1]]),
+            link_quality,
+        })
+    }
+
+    /// Build a PAN descriptor from a received coordinator realignment frame,
+    /// returning None if the frame is not a coordinator realignment command
+    pub(crate) fn from_realignment(frame: &ParsedFrame, link_quality: u8) -> Option<Self> {
+        if frame.frame_type != FrameType::MacCommand || frame.payload.len() < 8 ||
+            frame.payload[0] != MacCommand::CoordinatorRealignment as u8 {
+            return None;
+        }
+        Some(Self {
+            coordinator_pan: PanId(u16::from_le_bytes([frame.payload[1], frame.payload[2]])),
+            coordinator_address: frame.src_addr?,
+            channel: Channel::try_from(frame.payload[5]).ok()?,
+            superframe_spec: 0,
+            link_quality,
+        })
+    }
+
+    /// Whether the coordinator is accepting association requests
+    pub fn association_permit(&self) -> bool {
+        (self.superframe_spec & 1 << 15) != 0
+    }
+}
+
+/// Association Status values of the association response command
+/// (802.15.4 §7.3.2.3)
+pub(crate) const ASSOCIATION_SUCCESSFUL: u8 = 0x00;
+
+/// Outcome of an attempt to join a PAN
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AssociationResult {
+    // The coordinator accepted the association and allocated a short address
+    Success(ShortAddress),
+    // The coordinator rejected the association (PAN at capacity or access
+    // denied)
+    Denied,
+    // No suitable coordinator was found or it did not respond in time
+    TimedOut,
+}
+
+/// Whether the radio has joined a PAN
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AssociationStatus {
+    // Not a member of any PAN
+    #[default]
+    NotAssociated,
+    // An association attempt is in progress
+    Associating,
+    // Associated with (or coordinating) a PAN
+    Associated { pan_id: PanId, short_addr: ShortAddress },
+}
+
+/// Maximum number of pending addresses (short and extended combined) that
+/// can be listed in a single beacon frame
+pub const MAX_PENDING_ADDRESSES: usize = 7;
+
+/// Addresses of the devices a coordinator has pending (indirect) data for.
+/// These are advertised in the pending address fields of beacon frames so
+/// the devices know to poll the coordinator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct PendingAddressList {
+    // Devices with pending data, identified by short address
+    short: [Option<ShortAddress>; MAX_PENDING_ADDRESSES],
+    // Devices with pending data, identified by IEEE address
+    ieee: [Option<IEEEAddress>; MAX_PENDING_ADDRESSES],
+}
+
+impl PendingAddressList {
+    /// Create an empty pending address list
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The total number of pending addresses (short and IEEE)
+    pub fn len(&self) -> usize {
+        self.short_count() + self.ieee_count()
+    }
+
+    /// Whether no addresses are pending
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Add a short address to the list.  Adding an address that is already
+    /// pending has no effect.
+    pub fn add_short(&mut self, addr: ShortAddress) -> Result<(), CapacityError> {
+        if self.has_short(addr) {
+            return Ok(());
+        }
+        if self.len() >= MAX_PENDING_ADDRESSES {
+            return Err(CapacityError);
+        }
+        let slot = self.short.iter_mut().find(|slot| slot.is_none()).ok_or(CapacityError)?;
+        *slot = Some(addr);
+        Ok(())
+    }
+
+    /// Remove a short address from the list, returning whether it was present
+    pub fn remove_short(&mut self, addr: ShortAddress) -> bool {
+        match self.short.iter_mut().find(|slot| **slot == Some(addr)) {
+            Some(slot) => {
+                *slot = None;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Whether a short address is in the list
+    pub fn has_short(&self, addr: ShortAddress) -> bool {
+        self.short.contains(&Some(addr))
+    }
+
+    /// Add an IEEE address to the list.  Adding an address that is already
+    /// pending has no effect.
+    pub fn add_ieee(&mut self, addr: IEEEAddress) -> Result<(), CapacityError> {
+        if self.has_ieee(addr) {
+            return Ok(());
+        }
+        if self.len() >= MAX_PENDING_ADDRESSES {
+            return Err(CapacityError);
+        }
+        let slot = self.ieee.iter_mut().find(|slot| slot.is_none()).ok_or(CapacityError)?;
+        *slot = Some(addr);
+        Ok(())
+    }
+
+    /// Remove an IEEE address from the list, returning whether it was present
+    pub fn remove_ieee(&mut self, addr: IEEEAddress) -> bool {
+        match self.ieee.iter_mut().find(|slot| **slot == Some(addr)) {
+            Some(slot) => {
+                *slot = None;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Whether an IEEE address is in the list
+    pub fn has_ieee(&self, addr: IEEEAddress) -> bool {
+        self.ieee.contains(&Some(addr))
+    }
+
+    /// Serialise the pending address specification and address list fields
+    /// of a beacon frame (802.15.4 §7.2.2.1.6 and §7.2.2.1.7).
+    /// 
+    /// Format:
+    /// - Pending address specification: number of short addresses in bits
+    ///   `[2:0]` and number of extended addresses in bits `[6:4]`
+    /// - Short addresses (2 bytes each, little-endian)
+    /// - Extended addresses (8 bytes each, in over-the-air byte order)
+    pub fn encode_for_beacon(&self) -> BoundedVec<u8, 57> {
+        let mut buffer = BoundedVec::new();
+        let specification = (self.short_count() as u8) | ((self.ieee_count() as u8) << 4);
+        // The list holds at most 7 addresses, so this always fits
+        let _ = buffer.push(specification);
+        for addr in self.short.iter().flatten() {
+            let _ = buffer.extend_from_slice(&addr.0.to_le_bytes());
+        }
+        for addr in self.ieee.iter().flatten() {
+            let _ = buffer.extend_from_slice(&addr.0);
+        }
+        buffer
+    }
+
+    fn short_count(&self) -> usize {
+        self.short.iter().flatten().count()
+    }
+
+    fn ieee_count(&self) -> usize {
+        self.ieee.iter().flatten().count()
+    }
+}
+
+/// Maximum number of GTS descriptors in a beacon frame
+pub const MAX_GTS_DESCRIPTORS: usize = 7;
+
+/// Direction of a Guaranteed Time Slot, relative to the device
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum GtsDirection {
+    #[default]
+    Transmit,
+    Receive,
+}
+
+/// Whether a GTS request allocates a new GTS or deallocates an existing one
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum GtsRequestType {
+    #[default]
+    Deallocation,
+    Allocation,
+}
+
+/// GTS characteristics field of a GTS request command (802.15.4 §7.3.9.2)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct GtsCharacteristics {
+    // Number of superframe slots requested (0-15)
+    pub length: u8,
+    // Direction of the GTS
+    pub direction: GtsDirection,
+    // Allocation or deallocation
+    pub request_type: GtsRequestType,
+}
+
+impl From<GtsCharacteristics> for u8 {
+    fn from(value: GtsCharacteristics) -> Self {
+        let mut byte = value.length & 0x0F;
+        if value.direction == GtsDirection::Receive {
+            byte |= 1 << 4;
+        }
+        if value.request_type == GtsRequestType::Allocation {
+            byte |= 1 << 5;
+        }
+        byte
+    }
+}
+
+impl From<u8> for GtsCharacteristics {
+    fn from(value: u8) -> Self {
+        Self {
+            length: value & 0x0F,
+            direction: if (value & 1 << 4) != 0 { GtsDirection::Receive } else { GtsDirection::Transmit },
+            request_type: if (value & 1 << 5) != 0 { GtsRequestType::Allocation } else { GtsRequestType::Deallocation },
+        }
+    }
+}
+
+/// A GTS allocated to a device, as listed in a beacon frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct GtsDescriptor {
+    // Short address of the device the GTS is allocated to
+    pub device_short_addr: ShortAddress,
+    // First superframe slot of the GTS (0-15)
+    pub starting_slot: u8,
+    // Number of contiguous superframe slots in the GTS (0-15)
+    pub length: u8,
+    // Direction of the GTS (not part of the descriptor itself, it is
+    // encoded in the GTS directions field)
+    pub direction: GtsDirection,
+}
+
+/// The GTSs a coordinator has allocated, advertised in the GTS fields of
+/// beacon frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct GtsSlotList {
+    // Allocated GTSs
+    slots: [Option<GtsDescriptor>; MAX_GTS_DESCRIPTORS],
+    // Whether the coordinator is accepting GTS requests
+    permit: bool,
+}
+
+impl GtsSlotList {
+    /// Create an empty GTS list
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether the coordinator is accepting GTS requests
+    pub fn set_permit(&mut self, permit: bool) {
+        self.permit = permit;
+    }
+
+    /// The number of allocated GTSs
+    pub fn len(&self) -> usize {
+        self.slots.iter().flatten().count()
+    }
+
+    /// Whether no GTSs are allocated
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Add an allocated GTS to the list
+    pub fn add_slot(&mut self, descriptor: GtsDescriptor) -> Result<(), CapacityError> {
+        let slot = self.slots.iter_mut().find(|slot| slot.is_none()).ok_or(CapacityError)?;
+        *slot = Some(descriptor);
+        Ok(())
+    }
+
+    /// Remove the GTS in the given direction allocated to a device, returning
+    /// whether it was present
+    pub fn remove_slot(&mut self, addr: ShortAddress, direction: GtsDirection) -> bool {
+        let found = self.slots.iter_mut().find(|slot| {
+            slot.is_some_and(|descriptor| descriptor.device_short_addr == addr && descriptor.direction == direction)
+        });
+        match found {
+            Some(slot) => {
+                *slot = None;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Serialise the GTS specification, directions and list fields of a
+    /// beacon frame (802.15.4 §7.2.2.1.3 - §7.2.2.1.5).
+    /// 
+    /// Format:
+    /// - GTS specification: descriptor count in bits `[2:0]` and GTS permit in
+    ///   bit 7
+    /// - GTS directions (only if there are descriptors): bit n is set if
+    ///   descriptor n is receive-only
+    /// - GTS descriptors (3 bytes each): short address (little-endian), then
+    ///   starting slot in bits `[3:0]` and length in bits `[7:4]`
+    pub fn encode_for_beacon(&self) -> BoundedVec<u8, 23> {
+        let mut buffer = BoundedVec::new();
+        let mut specification = self.len() as u8;
+        if self.permit {
+            specification |= 1 << 7;
+        }
+        // The list holds at most 7 descriptors, so this always fits
+        let _ = buffer.push(specification);
+        if self.is_empty() {
+            return buffer;
+        }
+
+        let directions = self.slots.iter()
+            .flatten()
+            .enumerate()
+            .filter(|(_, descriptor)| descriptor.direction == GtsDirection::Receive)
+            .fold(0u8, |mask, (i, _)| mask | 1 << i);
+        let _ = buffer.push(directions);
+        for descriptor in self.slots.iter().flatten() {
+            let _ = buffer.extend_from_slice(&descriptor.device_short_addr.0.to_le_bytes());
+            let _ = buffer.push((descriptor.starting_slot & 0x0F) | (descriptor.length & 0x0F) << 4);
+        }
+        buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Counter(u32);
+
+    impl BackoffRng for Counter {
+        fn next_u32(&mut self) -> u32 {
+            self.0 = self.0.wrapping_add(13);
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_random_backoff_periods() {
+        let mut rng = Counter(0);
+
+        assert!((0..100).all(|_| random_backoff_periods(&mut rng, 3) < 8));
+        assert!((0..100).all(|_| random_backoff_periods(&mut rng, 5) < 32));
+        // The exponent is capped at macMaxBE
+        assert!((0..100).all(|_| random_backoff_periods(&mut rng, 8) < 32));
+    }
+
+    #[test]
+    fn test_superframe_spec_round_trip() {
+        let spec = SuperframeSpec {
+            beacon_order: 15,
+            superframe_order: 15,
+            final_cap_slot: 15,
+            battery_life_extension: false,
+            pan_coordinator: true,
+            association_permit: true,
+        };
+
+        assert_eq!(u16::from(spec), 0b1100_1111_1111_1111);
+        assert_eq!(SuperframeSpec::from(0b1100_1111_1111_1111), spec);
+    }
+
+    #[test]
+    fn test_pending_address_list_add_remove_short() {
+        let mut list = PendingAddressList::new();
+
+        assert_eq!(list.add_short(ShortAddress(0x1234)), Ok(()));
+        assert!(list.has_short(ShortAddress(0x1234)));
+        assert!(list.remove_short(ShortAddress(0x1234)));
+        assert!(!list.has_short(ShortAddress(0x1234)));
+        assert!(!list.remove_short(ShortAddress(0x1234)));
+    }
+
+    #[test]
+    fn test_pending_address_list_add_remove_ieee() {
+        let mut list = PendingAddressList::new();
+        let address = IEEEAddress([1, 2, 3, 4, 5, 6, 7, 8]);
+
+        assert_eq!(list.add_ieee(address), Ok(()));
+        assert!(list.has_ieee(address));
+        assert!(list.remove_ieee(address));
+        assert!(!list.has_ieee(address));
+    }
+
+    #[test]
+    fn test_pending_address_list_capacity() {
+        let mut list = PendingAddressList::new();
+        for i in 0..4 {
+            list.add_short(ShortAddress(i)).unwrap();
+        }
+        for i in 0..3 {
+            list.add_ieee(IEEEAddress([i; 8])).unwrap();
+        }
+
+        assert_eq!(list.len(), 7);
+        assert_eq!(list.add_short(ShortAddress(10)), Err(CapacityError));
+        assert_eq!(list.add_ieee(IEEEAddress([10; 8])), Err(CapacityError));
+    }
+
+    #[test]
+    fn test_pending_address_list_encode_empty() {
+        let list = PendingAddressList::new();
+
+        assert_eq!(list.encode_for_beacon().as_slice(), &[0x00]);
+    }
+
+    #[test]
+    // The specification byte is grouped by field
+    #[allow(clippy::unusual_byte_groupings)]
+    fn test_pending_address_list_encode() {
+        let mut list = PendingAddressList::new();
+        list.add_short(ShortAddress(0x1234)).unwrap();
+        list.add_short(ShortAddress(0xABCD)).unwrap();
+        list.add_ieee(IEEEAddress([1, 2, 3, 4, 5, 6, 7, 8])).unwrap();
+
+        assert_eq!(
+            list.encode_for_beacon().as_slice(),
+            &[
+                0b0_001_0_010,
+                0x34, 0x12,
+                0xCD, 0xAB,
+                1, 2, 3, 4, 5, 6, 7, 8,
+            ],
+        )
+    }
+
+    #[test]
+    fn test_gts_characteristics_round_trip() {
+        let characteristics = GtsCharacteristics {
+            length: 3,
+            direction: GtsDirection::Receive,
+            request_type: GtsRequestType::Allocation,
+        };
+
+        assert_eq!(u8::from(characteristics), 0b0011_0011);
+        assert_eq!(GtsCharacteristics::from(0b0011_0011), characteristics);
+    }
+
+    #[test]
+    fn test_gts_slot_list_encode_empty() {
+        let mut list = GtsSlotList::new();
+        list.set_permit(true);
+
+        assert_eq!(list.encode_for_beacon().as_slice(), &[0b1000_0000]);
+    }
+
+    #[test]
+    // The specification byte is grouped by field
+    #[allow(clippy::unusual_byte_groupings)]
+    fn test_gts_slot_list_encode() {
+        let mut list = GtsSlotList::new();
+        list.add_slot(GtsDescriptor {
+            device_short_addr: ShortAddress(0x1234),
+            starting_slot: 14,
+            length: 2,
+            direction: GtsDirection::Transmit,
+        }).unwrap();
+        list.add_slot(GtsDescriptor {
+            device_short_addr: ShortAddress(0xABCD),
+            starting_slot: 12,
+            length: 2,
+            direction: GtsDirection::Receive,
+        }).unwrap();
+
+        assert_eq!(
+            list.encode_for_beacon().as_slice(),
+            &[
+                0b0_0000_010,
+                0b0000_0010,
+                0x34, 0x12, 0x2E,
+                0xCD, 0xAB, 0x2C,
+            ],
+        )
+    }
+
+    #[test]
+    fn test_gts_slot_list_add_remove() {
+        let mut list = GtsSlotList::new();
+        let descriptor = GtsDescriptor {
+            device_short_addr: ShortAddress(0x1234),
+            starting_slot: 15,
+            length: 1,
+            direction: GtsDirection::Receive,
+        };
+        for _ in 0..MAX_GTS_DESCRIPTORS {
+            list.add_slot(descriptor).unwrap();
+        }
+
+        assert_eq!(list.add_slot(descriptor), Err(CapacityError));
+        assert!(!list.remove_slot(ShortAddress(0x1234), GtsDirection::Transmit));
+        assert!(list.remove_slot(ShortAddress(0x1234), GtsDirection::Receive));
+        assert_eq!(list.len(), MAX_GTS_DESCRIPTORS - 1);
+    }
+}
@@ -0,0 +1,172 @@
+//!
+//! Metadata (link quality and CRC status) appended by the CC2420 to
+//! received frames
+//! 
+
+/// LQI below which a frame is considered to be at the sensitivity limit
+const LQI_SENSITIVITY_LIMIT: i16 = 50;
+
+/// Estimated received energy (in dBm) at LQI_SENSITIVITY_LIMIT
+const SENSITIVITY_LIMIT_DBM: i16 = -91;
+
+/// Estimate the received signal energy (in dBm) from the link quality
+/// indicator of a frame.
+/// 
+/// Uses the approximate linear mapping -91 + (LQI - 50) / 3, clamped to the
+/// range of an i8.
+pub fn lqi_to_energy_estimate(lqi: u8) -> i8 {
+    let estimate = SENSITIVITY_LIMIT_DBM + (lqi as i16 - LQI_SENSITIVITY_LIMIT) / 3;
+    estimate.clamp(i8::MIN as i16, i8::MAX as i16) as i8
+}
+
+/// Split the last byte the CC2420 appends to a received frame (when AUTOCRC
+/// is enabled) into the link quality indicator (correlation value, bits
+/// `[6:0]`) and whether the CRC was valid (bit 7).
+pub fn parse_lqi_crc_byte(byte: u8) -> (u8, bool) {
+    (byte & 0x7F, (byte & 0x80) != 0)
+}
+
+/// Metadata the CC2420 appends (in place of the FCS, when AUTOCRC is
+/// enabled) to a received frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct RxFrameMetadata {
+    // RSSI of the frame (offset as RSSI.RSSI_VAL)
+    pub rssi: i8,
+    // Link quality indicator (correlation value)
+    pub link_quality: u8,
+    // Whether the CRC of the frame was valid
+    pub crc_ok: bool,
+}
+
+impl RxFrameMetadata {
+    /// Parse the last two bytes of a received frame
+    pub fn from_bytes(bytes: [u8; 2]) -> Self {
+        let (link_quality, crc_ok) = parse_lqi_crc_byte(bytes[1]);
+        Self {
+            rssi: bytes[0] as i8,
+            link_quality,
+            crc_ok,
+        }
+    }
+}
+
+/// Rolling average of the link quality and RSSI of the last N received
+/// frames
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LqiRssiSampler<const N: usize> {
+    lqi_samples: [u8; N],
+    rssi_samples: [i8; N],
+    // Number of samples stored (at most N)
+    count: usize,
+    // Index the next sample is stored at
+    head: usize,
+}
+
+impl<const N: usize> Default for LqiRssiSampler<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> LqiRssiSampler<N> {
+    pub const fn new() -> Self {
+        Self {
+            lqi_samples: [0; N],
+            rssi_samples: [0; N],
+            count: 0,
+            head: 0,
+        }
+    }
+
+    /// Add a sample, replacing the oldest once N samples are stored
+    pub fn add_sample(&mut self, lqi: u8, rssi_offset: i8) {
+        if N == 0 {
+            return;
+        }
+        self.lqi_samples[self.head] = lqi;
+        self.rssi_samples[self.head] = rssi_offset;
+        self.head = (self.head + 1) % N;
+        self.count = (self.count + 1).min(N);
+    }
+
+    /// Add the link quality and RSSI of a received frame
+    pub fn sample_from_frame(&mut self, metadata: &RxFrameMetadata) {
+        self.add_sample(metadata.link_quality, metadata.rssi);
+    }
+
+    /// Average link quality of the stored samples (0 when there are none)
+    pub fn average_lqi(&self) -> u8 {
+        if self.count == 0 {
+            return 0;
+        }
+        let sum: u32 = self.lqi_samples[..self.count].iter().map(|lqi| *lqi as u32).sum();
+        (sum / self.count as u32) as u8
+    }
+
+    /// Average RSSI of the stored samples (0 when there are none)
+    pub fn average_rssi(&self) -> i8 {
+        if self.count == 0 {
+            return 0;
+        }
+        let sum: i32 = self.rssi_samples[..self.count].iter().map(|rssi| *rssi as i32).sum();
+        (sum / self.count as i32) as i8
+    }
+
+    /// Number of samples stored (at most N)
+    pub fn sample_count(&self) -> usize {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lqi_to_energy_estimate() {
+        assert_eq!(lqi_to_energy_estimate(50), -91);
+        assert_eq!(lqi_to_energy_estimate(110), -71);
+        assert_eq!(lqi_to_energy_estimate(0), -107);
+        assert_eq!(lqi_to_energy_estimate(127), -66);
+    }
+
+    #[test]
+    fn test_rx_frame_metadata_from_bytes() {
+        assert_eq!(
+            RxFrameMetadata::from_bytes([0xEC, 0b1_1101010]),
+            RxFrameMetadata { rssi: -20, link_quality: 0b1101010, crc_ok: true },
+        );
+    }
+
+    #[test]
+    fn test_lqi_rssi_sampler_average() {
+        let mut sampler = LqiRssiSampler::<4>::new();
+        assert_eq!(sampler.average_lqi(), 0);
+        assert_eq!(sampler.average_rssi(), 0);
+
+        sampler.add_sample(100, -20);
+        sampler.sample_from_frame(&RxFrameMetadata { rssi: -30, link_quality: 110, crc_ok: true });
+
+        assert_eq!(sampler.sample_count(), 2);
+        assert_eq!(sampler.average_lqi(), 105);
+        assert_eq!(sampler.average_rssi(), -25);
+    }
+
+    #[test]
+    fn test_lqi_rssi_sampler_replaces_oldest() {
+        let mut sampler = LqiRssiSampler::<2>::new();
+        sampler.add_sample(10, -10);
+        sampler.add_sample(20, -20);
+        sampler.add_sample(30, -30);
+
+        assert_eq!(sampler.sample_count(), 2);
+        assert_eq!(sampler.average_lqi(), 25);
+        assert_eq!(sampler.average_rssi(), -25);
+    }
+
+    #[test]
+    fn test_parse_lqi_crc_byte() {
+        assert_eq!(parse_lqi_crc_byte(0b1_1101010), (0b1101010, true));
+        assert_eq!(parse_lqi_crc_byte(0b0_0110010), (0b0110010, false));
+    }
+}
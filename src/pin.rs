@@ -0,0 +1,65 @@
+//!
+//! Placeholder for radio pins that are not connected to the MCU
+//!
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use embedded_hal::digital::{self, InputPin};
+
+/// Input pin that is not connected (e.g. the FIFOP pin when only the SFD and
+/// FIFO pins are wired up).  It never reads as high or low, so it is never
+/// reported as asserted whatever the configured polarity.
+/// 
+/// Note: This deliberately breaks the InputPin convention that is_low is the
+/// inverse of is_high (both return false), so NoPin should only be passed to
+/// the radio, not to code that reads a level with one method and infers the
+/// other.
+pub struct NoPin<E>(PhantomData<E>);
+
+impl<E> NoPin<E> {
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<E> Default for NoPin<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> fmt::Debug for NoPin<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NoPin")
+    }
+}
+
+impl<E: digital::Error> digital::ErrorType for NoPin<E> {
+    type Error = E;
+}
+
+impl<E: digital::Error> InputPin for NoPin<E> {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    // Not !is_high, an unconnected pin is not active low either
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::convert::Infallible;
+
+    #[test]
+    fn test_no_pin() {
+        let mut pin = NoPin::<Infallible>::new();
+        assert_eq!(pin.is_high(), Ok(false));
+        assert_eq!(pin.is_low(), Ok(false));
+    }
+}
@@ -0,0 +1,117 @@
+//!
+//! Named transmit power levels for the CC2420 (TXCTRL.PA_LEVEL and
+//! TXCTRL.PA_CURRENT)
+//! 
+
+use crate::config::OutputPower;
+
+/// Largest value of the 5 bit TXCTRL.PA_LEVEL field
+pub const MAX_PA_LEVEL: u8 = 31;
+
+/// Largest value of the 3 bit TXCTRL.PA_CURRENT field
+pub const MAX_PA_CURRENT: u8 = 7;
+
+/// Nominal PA current programming (TXCTRL.PA_CURRENT reset value)
+const NOMINAL_PA_CURRENT: u8 = 3;
+
+/// Transmit power of the radio
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TxPowerLevel {
+    // 0 dBm (OutputPower::Dbm0)
+    #[default]
+    Max,
+    // -3 dBm (OutputPower::DbmMinus3)
+    High,
+    // -7 dBm (OutputPower::DbmMinus7)
+    Medium,
+    // -15 dBm (OutputPower::DbmMinus15)
+    Low,
+    // -25 dBm (OutputPower::DbmMinus25)
+    Min,
+    // Raw TXCTRL.PA_LEVEL (0..=31) and TXCTRL.PA_CURRENT (0..=7) values
+    Custom { pa_level: u8, pa_current: u8 },
+}
+
+impl TxPowerLevel {
+    /// The entry of the datasheet output power table for this power level,
+    /// None for custom levels
+    pub fn output_power(&self) -> Option<OutputPower> {
+        match self {
+            Self::Max => Some(OutputPower::Dbm0),
+            Self::High => Some(OutputPower::DbmMinus3),
+            Self::Medium => Some(OutputPower::DbmMinus7),
+            Self::Low => Some(OutputPower::DbmMinus15),
+            Self::Min => Some(OutputPower::DbmMinus25),
+            Self::Custom { .. } => None,
+        }
+    }
+
+    /// The TXCTRL.PA_LEVEL value for this power level
+    pub fn pa_level(&self) -> u8 {
+        match self {
+            Self::Custom { pa_level, .. } => *pa_level,
+            _ => self.output_power().map_or(MAX_PA_LEVEL, |power| power.pa_level()),
+        }
+    }
+
+    /// The TXCTRL.PA_CURRENT value for this power level
+    pub fn pa_current(&self) -> u8 {
+        match self {
+            Self::Custom { pa_current, .. } => *pa_current,
+            _ => NOMINAL_PA_CURRENT,
+        }
+    }
+
+    /// The typical output power (in dBm) from the datasheet, None for custom
+    /// levels
+    pub fn dbm(&self) -> Option<i8> {
+        self.output_power().map(|power| power.dbm())
+    }
+
+    /// Whether the PA fields fit in TXCTRL
+    pub fn is_valid(&self) -> bool {
+        self.pa_level() <= MAX_PA_LEVEL && self.pa_current() <= MAX_PA_CURRENT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tx_power_level_values() {
+        assert_eq!((TxPowerLevel::Max.pa_level(), TxPowerLevel::Max.pa_current()), (31, 3));
+        assert_eq!((TxPowerLevel::High.pa_level(), TxPowerLevel::High.pa_current()), (23, 3));
+        assert_eq!((TxPowerLevel::Medium.pa_level(), TxPowerLevel::Medium.pa_current()), (15, 3));
+        assert_eq!((TxPowerLevel::Low.pa_level(), TxPowerLevel::Low.pa_current()), (7, 3));
+        assert_eq!((TxPowerLevel::Min.pa_level(), TxPowerLevel::Min.pa_current()), (3, 3));
+
+        let custom = TxPowerLevel::Custom { pa_level: 19, pa_current: 5 };
+        assert_eq!((custom.pa_level(), custom.pa_current()), (19, 5));
+    }
+
+    #[test]
+    fn test_tx_power_level_output_power() {
+        assert_eq!(TxPowerLevel::Max.output_power(), Some(OutputPower::Dbm0));
+        assert_eq!(TxPowerLevel::Min.output_power(), Some(OutputPower::DbmMinus25));
+        assert_eq!(TxPowerLevel::Custom { pa_level: 31, pa_current: 3 }.output_power(), None);
+    }
+
+    #[test]
+    fn test_tx_power_level_dbm() {
+        assert_eq!(TxPowerLevel::Max.dbm(), Some(0));
+        assert_eq!(TxPowerLevel::High.dbm(), Some(-3));
+        assert_eq!(TxPowerLevel::Medium.dbm(), Some(-7));
+        assert_eq!(TxPowerLevel::Low.dbm(), Some(-15));
+        assert_eq!(TxPowerLevel::Min.dbm(), Some(-25));
+        assert_eq!(TxPowerLevel::Custom { pa_level: 31, pa_current: 3 }.dbm(), None);
+    }
+
+    #[test]
+    fn test_tx_power_level_is_valid() {
+        assert!(TxPowerLevel::Max.is_valid());
+        assert!(TxPowerLevel::Custom { pa_level: 31, pa_current: 7 }.is_valid());
+        assert!(!TxPowerLevel::Custom { pa_level: 32, pa_current: 3 }.is_valid());
+        assert!(!TxPowerLevel::Custom { pa_level: 31, pa_current: 8 }.is_valid());
+    }
+}
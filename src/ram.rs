@@ -21,16 +21,28 @@ pub enum Ram {
 impl Ram {
     /// The start address of a given RAM address sector (for reading)
     pub fn read_address(self) -> (u8, u8) {
-        let value = self as u16;
+        self.read_address_at(0)
+    }
+
+    /// The start address of a given RAM address sector (for writing)
+    pub fn write_address(self) -> (u8, u8) {
+        self.write_address_at(0)
+    }
+
+    /// The address of the byte at offset into a given RAM address sector
+    /// (for reading)
+    pub fn read_address_at(self, offset: usize) -> (u8, u8) {
+        let value = self as u16 + offset as u16;
         (
             ((0x7F & value) | (1 << 7)) as u8,
             (((0x3 << 7) & value) >> 1 | (1 << 5)) as u8,
         )
     }
 
-    /// The start address of a given RAM address sector (for writing)
-    pub fn write_address(self) -> (u8, u8) {
-        let value = self as u16;
+    /// The address of the byte at offset into a given RAM address sector
+    /// (for writing)
+    pub fn write_address_at(self, offset: usize) -> (u8, u8) {
+        let value = self as u16 + offset as u16;
         (
             ((0x7F & value) | (1 << 7)) as u8,
             (((0x3 << 7) & value) >> 1) as u8,
@@ -111,4 +123,22 @@ mod tests {
             (0b1000_0000, 0b0000_0000)
         )
     }
+
+    #[test]
+    fn test_get_write_address_at_offset() {
+        let write_address = Ram::TxNonce.write_address_at(12);
+        assert_eq!(
+            write_address,
+            (0b1100_1100, 0b1000_0000)
+        )
+    }
+
+    #[test]
+    fn test_get_read_address_at_offset() {
+        let read_address = Ram::TxNonce.read_address_at(12);
+        assert_eq!(
+            read_address,
+            (0b1100_1100, 0b1010_0000)
+        )
+    }
 }
\ No newline at end of file
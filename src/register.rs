@@ -72,9 +72,10 @@ pub trait Register {
     fn read_address(&self) -> u8 { self.address() }
     // Get the write address of a register
     fn write_address(&self) -> u8 { self.address() | 1 << 6 }
-    /// Get the write_value of a register
+    /// Get the write_value of a register (the register value is sent MSB
+    /// first)
     fn write_value(&self) -> [u8; 3] {
-        let register_value = self.register_value().to_le_bytes();
+        let register_value = self.register_value().to_be_bytes();
         [self.write_address(), register_value[0], register_value[1]]
     }
 }
\ No newline at end of file
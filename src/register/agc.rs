@@ -4,12 +4,12 @@
 
 use super::Register;
 
-use alloc::string::String;
+use crate::error::BuildError;
 
 use derive_builder::Builder;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(validate = "Self::validate"))]
+#[builder(no_std, build_fn(validate = "Self::validate", error = "BuildError"))]
 pub struct AGCControlRegister {
     // Use the VGA_GAIN value during RX instead of the AGC value.
     #[builder(default = "false")]
@@ -49,7 +49,7 @@ impl Register for AGCControlRegister {
     fn address(&self) -> u8 { 0x23 }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -65,22 +65,22 @@ impl From<u16> for AGCControlRegister {
 }
 
 impl AGCControlRegisterBuilder {
-    fn validate(&self) -> Result<(), String> {
+    fn validate(&self) -> Result<(), &'static str> {
         if let Some(vga_gain) = self.vga_gain {
             if vga_gain > 0x7F {
-                return Err("Invalid VGA_GAIN. 0<=VGA_GAIN<=0x7F".into());
+                return Err("Invalid VGA_GAIN. 0<=VGA_GAIN<=0x7F");
             }
         }
 
         if let Some(lnamix_gainmode_o) = self.lnamix_gainmode_o {
             if lnamix_gainmode_o > 3 {
-                return Err("Invalid LNAMIX_GAINMODE_O. 0<=LNAMIX_GAINMODE_O<=3".into());
+                return Err("Invalid LNAMIX_GAINMODE_O. 0<=LNAMIX_GAINMODE_O<=3");
             }
         }
 
         if let Some(lnamix_gainmode) = self.lnamix_gainmode {
             if lnamix_gainmode > 3 {
-                return Err("Invalid LNAMIX_GAINMODE. 0<=LNAMIX_GAINMODE<=3".into());
+                return Err("Invalid LNAMIX_GAINMODE. 0<=LNAMIX_GAINMODE<=3");
             }
         }
 
@@ -88,7 +88,9 @@ impl AGCControlRegisterBuilder {
     }
 }
 
+// The expected register values are grouped by field
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     use crate::register::agc;
 
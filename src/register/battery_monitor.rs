@@ -4,18 +4,20 @@
 
 use super::Register;
 
-use alloc::string::String;
+use crate::error::BuildError;
 
 use derive_builder::Builder;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(validate = "Self::validate"))]
+#[builder(no_std, build_fn(validate = "Self::validate", error = "BuildError"))]
 pub struct BatteryMonitorRegister {
     // Battery monitor comparator output, read only. BATT_OK is valid
     // 5 us after BATTMON_EN has been asserted and 
     // BATTMON_VOLTAGE has been programmed.
-    #[builder(default = "true")]
-    pub battmon_ok: bool,
+    // Only populated when the register is read from the radio, so it
+    // cannot be set through the builder (see battmon_ok()).
+    #[builder(setter(skip), default = "false")]
+    battmon_ok: bool,
     // Battery monitor enabled
     // 0: Battery monitor is disabled
     // 1: Battery monitor is enabled
@@ -43,7 +45,18 @@ impl Register for BatteryMonitorRegister {
     fn address(&self) -> u8 { 0x1B }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
+    }
+}
+
+impl BatteryMonitorRegister {
+    /// The battery monitor comparator output (BATT_OK).
+    /// 
+    /// Note: This is a read-only status bit, so the value is only meaningful
+    /// for a register that has been read from the radio (and is always false
+    /// for a register created with the builder).
+    pub fn battmon_ok(&self) -> bool {
+        self.battmon_ok
     }
 }
 
@@ -58,10 +71,10 @@ impl From<u16> for BatteryMonitorRegister {
 }
 
 impl BatteryMonitorRegisterBuilder {
-    fn validate(&self) -> Result<(), String> {
+    fn validate(&self) -> Result<(), &'static str> {
         if let Some(battmon_voltage) = self.battmon_voltage {
             if battmon_voltage > 31 {
-                return Err("Invalid BATTMON_VOLTAGE. 0<=BATTMON_VOLTAGE<=31".into());
+                return Err("Invalid BATTMON_VOLTAGE. 0<=BATTMON_VOLTAGE<=31");
             }
         }
 
@@ -69,7 +82,9 @@ impl BatteryMonitorRegisterBuilder {
     }
 }
 
+// The expected register values are grouped by field
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     use super::*;
 
@@ -104,7 +119,6 @@ mod tests {
         let value = 0b0_1_11111;
 
         let expected_battery_monitor = BatteryMonitorRegisterBuilder::default()
-            .battmon_ok(false)
             .battmon_en(true)
             .battmon_voltage(0x1F)
             .build()
@@ -115,4 +129,28 @@ mod tests {
             value.into(),
         )
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_battmon_ok_from_u16() {
+        let value: u16 = 0b1_0_00000;
+        let battery_monitor: BatteryMonitorRegister = value.into();
+
+        assert!(battery_monitor.battmon_ok());
+        assert_eq!(
+            battery_monitor.register_value(),
+            0b0_0_00000,
+        )
+    }
+
+    #[test]
+    fn test_invalid_battmon_voltage() {
+        let result = BatteryMonitorRegisterBuilder::default()
+            .battmon_voltage(32)
+            .build();
+
+        assert_eq!(
+            result.unwrap_err().message(),
+            "Invalid BATTMON_VOLTAGE. 0<=BATTMON_VOLTAGE<=31",
+        )
+    }
+}
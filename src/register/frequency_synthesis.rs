@@ -5,12 +5,13 @@
 
 use super::Register;
 
-use alloc::string::String;
+use crate::channel::{Channel, Frequency, Ieee802154Channel};
+use crate::error::BuildError;
 
 use derive_builder::Builder;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(validate = "Self::validate"))]
+#[builder(no_std, build_fn(validate = "Self::validate", error = "BuildError"))]
 pub struct FrequencySynthesizerRegister {
     // Number of consecutive reference clock periods with successful
     // synchronisation windows required to indicate lock:
@@ -48,8 +49,8 @@ pub struct FrequencySynthesizerRegister {
     // See the Frequency and Channel Programming section on page
     // 50 for further information
     // default = 357 = 2405 MHz
-    #[builder(default = "357")]
-    pub frequency: u16,
+    #[builder(default = "Frequency::default()")]
+    pub frequency: Frequency,
 }
 
 impl Register for FrequencySynthesizerRegister {
@@ -74,7 +75,7 @@ impl Register for FrequencySynthesizerRegister {
             value |= 1 << 10;
         }
 
-        value |= self.frequency;
+        value |= self.frequency.raw();
 
         value
     }
@@ -82,7 +83,7 @@ impl Register for FrequencySynthesizerRegister {
     fn address(&self) -> u8 { 0x18 }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -94,29 +95,35 @@ impl From<u16> for FrequencySynthesizerRegister {
             cal_running: (((1 << 12) & value) != 0),
             lock_length: (((1 << 11) & value) != 0),
             lock_status: (((1 << 10) & value) != 0),
-            frequency: value & 0x1FF,
+            frequency: Frequency::try_from(value & Frequency::MAX).unwrap(),
         }
     }
 }
 
+/// The default register value tuned to the channel
+impl From<Ieee802154Channel> for FrequencySynthesizerRegister {
+    fn from(value: Ieee802154Channel) -> Self {
+        let mut register = FrequencySynthesizerRegisterBuilder::default().build().unwrap();
+        register.frequency = Frequency::from_channel(Channel::from(value));
+        register
+    }
+}
+
 impl FrequencySynthesizerRegisterBuilder {
-    fn validate(&self) -> Result<(), String> {
+    fn validate(&self) -> Result<(), &'static str> {
         if let Some(lock_threshold) = self.lock_threshold {
             if lock_threshold > 3 {
-                return Err("Invalid LOCK_THR. 0<=LOCK_THR<=3".into());
+                return Err("Invalid LOCK_THR. 0<=LOCK_THR<=3");
             }
         }
 
-        if let Some(frequency) = self.frequency {
-            if frequency >= (1 << 10) {
-                return Err("Invalid FREQ. 0<=FREQ<=(1 << 10)".into());
-            }
-        }
         Ok(())
     }
 }
 
+// The expected register values are grouped by field
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     use super::*;
 
@@ -149,7 +156,7 @@ mod tests {
     #[test]
     fn test_frequency_value() {
         let frequency_synthesis = FrequencySynthesizerRegisterBuilder::default()
-            .frequency(32)
+            .frequency(Frequency::try_from(32).unwrap())
             .build()
             .unwrap();
 
@@ -169,7 +176,7 @@ mod tests {
             .cal_running(true)
             .lock_length(true)
             .lock_status(true)
-            .frequency(271)
+            .frequency(Frequency::try_from(271).unwrap())
             .build()
             .unwrap();
 
@@ -178,4 +185,22 @@ mod tests {
             value.into(),
         )
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_frequency_from_u16_upper_bits() {
+        let value: u16 = 0b01_0_0_0_0_1111111111;
+        let frequency_synthesis: FrequencySynthesizerRegister = value.into();
+
+        assert_eq!(frequency_synthesis.frequency.raw(), 1023);
+        assert!(!frequency_synthesis.lock_status);
+    }
+
+    #[test]
+    fn test_frequency_synthesis_from_ieee_channel() {
+        let register = FrequencySynthesizerRegister::from(Ieee802154Channel::Channel26);
+        let mut expected = FrequencySynthesizerRegisterBuilder::default().build().unwrap();
+        expected.frequency = Frequency::try_from(432).unwrap();
+
+        assert_eq!(register, expected);
+    }
+}
@@ -5,12 +5,12 @@
 
 use super::Register;
 
-use alloc::string::String;
+use crate::error::BuildError;
 
 use derive_builder::Builder;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(validate = "Self::validate"))]
+#[builder(no_std, build_fn(validate = "Self::validate", error = "BuildError"))]
 pub struct FiniteStateMachineConstants {
     // The time in 5 us steps between the time the RX chain is enabled
     // and the demodulator and AGC is enabled. The RX chain is
@@ -57,7 +57,7 @@ impl Register for FiniteStateMachineConstants {
     fn address(&self) -> u8 { 0x20 }
     
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -74,12 +74,44 @@ impl From<u16> for FiniteStateMachineConstants {
 }
 
 impl FiniteStateMachineConstantsBuilder {
-    fn validate(&self) -> Result<(), String> {
+    fn validate(&self) -> Result<(), &'static str> {
+        if let Some(tc_rxchain_to_rx) = self.tc_rxchain_to_rx {
+            if tc_rxchain_to_rx > 0b111 {
+                return Err("Invalid TC_RXCHAIN2RX. 0<=TC_RXCHAIN2RX<=7");
+            }
+        }
+
+        if let Some(tc_switch_to_tx) = self.tc_switch_to_tx {
+            if tc_switch_to_tx > 0b111 {
+                return Err("Invalid TC_SWITCH2TX. 0<=TC_SWITCH2TX<=7");
+            }
+        }
+
+        if let Some(tc_paon_to_tx) = self.tc_paon_to_tx {
+            if tc_paon_to_tx > 0b1111 {
+                return Err("Invalid TC_PAON2TX. 0<=TC_PAON2TX<=15");
+            }
+        }
+
+        if let Some(tc_txend_to_switch) = self.tc_txend_to_switch {
+            if tc_txend_to_switch > 0b111 {
+                return Err("Invalid TC_TXEND2SWITCH. 0<=TC_TXEND2SWITCH<=7");
+            }
+        }
+
+        if let Some(tc_txend_to_paoff) = self.tc_txend_to_paoff {
+            if tc_txend_to_paoff > 0b111 {
+                return Err("Invalid TC_TXEND2PAOFF. 0<=TC_TXEND2PAOFF<=7");
+            }
+        }
+
         Ok(())
     }
 }
 
+// The expected register values are grouped by field
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     use super::*;
 
@@ -148,6 +180,43 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_invalid_fsm_constants() {
+        let result = FiniteStateMachineConstantsBuilder::default()
+            .tc_rxchain_to_rx(8)
+            .build();
+        assert_eq!(result.unwrap_err().message(), "Invalid TC_RXCHAIN2RX. 0<=TC_RXCHAIN2RX<=7");
+
+        let result = FiniteStateMachineConstantsBuilder::default()
+            .tc_switch_to_tx(8)
+            .build();
+        assert_eq!(result.unwrap_err().message(), "Invalid TC_SWITCH2TX. 0<=TC_SWITCH2TX<=7");
+
+        let result = FiniteStateMachineConstantsBuilder::default()
+            .tc_paon_to_tx(16)
+            .build();
+        assert_eq!(result.unwrap_err().message(), "Invalid TC_PAON2TX. 0<=TC_PAON2TX<=15");
+
+        let result = FiniteStateMachineConstantsBuilder::default()
+            .tc_txend_to_switch(8)
+            .build();
+        assert_eq!(result.unwrap_err().message(), "Invalid TC_TXEND2SWITCH. 0<=TC_TXEND2SWITCH<=7");
+
+        let result = FiniteStateMachineConstantsBuilder::default()
+            .tc_txend_to_paoff(8)
+            .build();
+        assert_eq!(result.unwrap_err().message(), "Invalid TC_TXEND2PAOFF. 0<=TC_TXEND2PAOFF<=7");
+
+        assert!(FiniteStateMachineConstantsBuilder::default()
+            .tc_rxchain_to_rx(7)
+            .tc_switch_to_tx(7)
+            .tc_paon_to_tx(15)
+            .tc_txend_to_switch(7)
+            .tc_txend_to_paoff(7)
+            .build()
+            .is_ok());
+    }
+
     #[test]
     fn test_fsm_constants_from_u16() {
         let value = 0b101_101_0101_101_101;
@@ -4,12 +4,12 @@
 
 use super::Register;
 
-use alloc::string::String;
+use crate::error::BuildError;
 
 use derive_builder::Builder;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(validate = "Self::validate"))]
+#[builder(no_std, build_fn(validate = "Self::validate", error = "BuildError"))]
 pub struct IOConfigurationRegister {
     // Accept all beacon frames when address recognition is enabled.
     // This bit should be set when the PAN identifier programmed into
@@ -78,7 +78,7 @@ impl Register for IOConfigurationRegister {
     fn address(&self) -> u8 { 0x1C }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -96,10 +96,10 @@ impl From<u16> for IOConfigurationRegister {
 }
 
 impl IOConfigurationRegisterBuilder {
-    fn validate(&self) -> Result<(), String> {
+    fn validate(&self) -> Result<(), &'static str> {
         if let Some(fifop_threshold) = self.fifop_threshold {
             if fifop_threshold > 0x7F {
-                return Err("Invalid FIFOP_THR. 0<=FIFO_THR<=127".into());
+                return Err("Invalid FIFOP_THR. 0<=FIFO_THR<=127");
             }
         }
 
@@ -108,7 +108,7 @@ impl IOConfigurationRegisterBuilder {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(validate = "Self::validate"))]
+#[builder(no_std, build_fn(validate = "Self::validate", error = "BuildError"))]
 pub struct IOConfigurationRegister1 {
     // The HSSD Module is used as follows:
     // 0: Off
@@ -149,7 +149,7 @@ impl Register for IOConfigurationRegister1 {
     fn address(&self) -> u8 { 0x1D }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -164,22 +164,22 @@ impl From<u16> for IOConfigurationRegister1 {
 }
 
 impl IOConfigurationRegister1Builder {
-    fn validate(&self) -> Result<(), String> {
+    fn validate(&self) -> Result<(), &'static str> {
         if let Some(hssd_src) = self.hssd_src {
             if hssd_src == 4 || hssd_src == 5 || hssd_src > 7 {
-                return Err("Invalid HSSD_SRC. 0<=HSSD_SRC<=3 U 6<=HSSD_SRC<=7".into());
+                return Err("Invalid HSSD_SRC. 0<=HSSD_SRC<=3 U 6<=HSSD_SRC<=7");
             }
         }
 
         if let Some(sfd_mux) = self.sfd_mux {
             if sfd_mux > 31 {
-                return Err("Invalid SFDMUX. 0<=SFDMUX<=31".into());
+                return Err("Invalid SFDMUX. 0<=SFDMUX<=31");
             }
         }
 
         if let Some(cca_mux) = self.cca_mux {
             if cca_mux > 31 {
-                return Err("Invalid CCAMUX. 0<=CCAMUX<=31".into());
+                return Err("Invalid CCAMUX. 0<=CCAMUX<=31");
             }
         }
 
@@ -187,7 +187,9 @@ impl IOConfigurationRegister1Builder {
     }
 }
 
+// The expected register values are grouped by field
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     use super::*;
 
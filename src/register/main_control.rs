@@ -3,11 +3,12 @@
 //! 
 
 use super::Register;
+use crate::error::BuildError;
 use derive_builder::Builder;
 
 /// Main Control Register
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(error(validation_error = false)))]
+#[builder(no_std, build_fn(error = "BuildError"))]
 pub struct MainControlRegister {
     // Active low reset of the entire circuit should be applied
     // before doing anything else
@@ -34,39 +35,39 @@ pub struct MainControlRegister {
 
 impl Register for MainControlRegister {
     fn register_value(&self) -> u16 {
-        let mut value = 0b11111000_00000000;
-        
-        if !self.reset_n {
-            value &= !(1 << 15);
+        let mut value = 0;
+
+        if self.reset_n {
+            value |= 1 << 15;
         }
 
-        if !self.enc_reset_n {
-            value &= !(1 << 14);
+        if self.enc_reset_n {
+            value |= 1 << 14;
         }
 
-        if !self.demod_reset_n {
-            value &= !(1 << 13);
+        if self.demod_reset_n {
+            value |= 1 << 13;
         }
 
-        if !self.mod_reset_n {
-            value &= !(1 << 12);
+        if self.mod_reset_n {
+            value |= 1 << 12;
         }
 
-        if !self.fs_reset_n {
-            value &= !(1 << 11);
+        if self.fs_reset_n {
+            value |= 1 << 11;
         }
 
         if self.xosc16m_bypass {
             value |= 1;
         }
-        
+
         value
     }
 
     fn address(&self) -> u8 { 0x10 }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -184,4 +185,24 @@ mod tests {
             value.into(),
         )
     }
+
+    #[test]
+    fn test_main_control_round_trip() {
+        for bits in 0..64u8 {
+            let main_control_register = MainControlRegisterBuilder::default()
+                .reset_n((bits & 1 << 5) != 0)
+                .enc_reset_n((bits & 1 << 4) != 0)
+                .demod_reset_n((bits & 1 << 3) != 0)
+                .mod_reset_n((bits & 1 << 2) != 0)
+                .fs_reset_n((bits & 1 << 1) != 0)
+                .xosc16m_bypass((bits & 1) != 0)
+                .build()
+                .unwrap();
+
+            assert_eq!(
+                main_control_register,
+                main_control_register.register_value().into(),
+            )
+        }
+    }
 }
\ No newline at end of file
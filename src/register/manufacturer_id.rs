@@ -4,12 +4,13 @@
 
 use super::Register;
 
-use alloc::string::String;
+use crate::error::BuildError;
 
 use derive_builder::Builder;
 
+/// This is a read-only register, writing it is a programming error.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(error(validation_error = false)))]
+#[builder(no_std, build_fn(error = "BuildError"))]
 pub struct LowerManufacturerID {
     // The device part number.  CC2420 has part number 0x002,
     #[builder(default = "2")]
@@ -27,10 +28,15 @@ impl Register for LowerManufacturerID {
         0
     }
 
+    fn write_value(&self) -> [u8; 3] {
+        debug_assert!(false, "LowerManufacturerID is a read-only register");
+        [self.write_address(), 0, 0]
+    }
+
     fn address(&self) -> u8 { 0x1E }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -43,8 +49,9 @@ impl From<u16> for LowerManufacturerID {
     }
 }
 
+/// This is a read-only register, writing it is a programming error.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(error(validation_error = false)))]
+#[builder(no_std, build_fn(error = "BuildError"))]
 pub struct UpperManufacturerID {
     // Version number. Current version is 3.
     // Note that previous CC2420 versions will have lower rest
@@ -61,10 +68,15 @@ impl Register for UpperManufacturerID {
         0
     }
 
+    fn write_value(&self) -> [u8; 3] {
+        debug_assert!(false, "UpperManufacturerID is a read-only register");
+        [self.write_address(), 0, 0]
+    }
+
     fn address(&self) -> u8 { 0x1F }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -112,4 +124,20 @@ mod tests {
             value.into(),
         )
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "read-only register")]
+    fn test_lower_manufacturer_id_write_value() {
+        let register = LowerManufacturerIDBuilder::default().build().unwrap();
+        register.write_value();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "read-only register")]
+    fn test_upper_manufacturer_id_write_value() {
+        let register = UpperManufacturerIDBuilder::default().build().unwrap();
+        register.write_value();
+    }
+}
@@ -4,13 +4,13 @@
 
 use super::Register;
 
-use alloc::string::String;
+use crate::error::BuildError;
 
 use derive_builder::Builder;
 
 /// Modem Control Register Definition
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(validate = "Self::validate"))]
+#[builder(no_std, build_fn(validate = "Self::validate", error = "BuildError"))]
 pub struct ModemControlRegister0 {
     // Mode for accepting reserved IEEE 802.15.4 frame types when
     // address recognition is enabled (MDMCTRL0.ADR_DECODe = 1)
@@ -97,7 +97,7 @@ impl Register for ModemControlRegister0 {
     fn address(&self) -> u8 { 0x11 }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -117,22 +117,22 @@ impl From<u16> for ModemControlRegister0 {
 }
 
 impl ModemControlRegister0Builder {
-    fn validate(&self) -> Result<(), String> {
+    fn validate(&self) -> Result<(), &'static str> {
         if let Some(cca_hyst) = self.cca_hyst {
             if cca_hyst > 7 {
-                return Err("Invalid CCA Hyst, Expected 0<=CCA_HYST<=7".into());
+                return Err("Invalid CCA Hyst, Expected 0<=CCA_HYST<=7");
             }
         }
 
         if let Some(cca_mode) = self.cca_mode {
             if cca_mode == 0 || cca_mode > 3 {
-                return Err("Invalid CCA Mode, Expected 1<=CCA_MODE<=3".into());
+                return Err("Invalid CCA Mode, Expected 1<=CCA_MODE<=3");
             }
         }
 
         if let Some(preamble_length) = self.preamble_length {
             if preamble_length > 15 {
-                return Err("Invalid Preamble Length, Expected 0<=PREAMBLE_LENGTH<=15".into());
+                return Err("Invalid Preamble Length, Expected 0<=PREAMBLE_LENGTH<=15");
             }
         }
 
@@ -141,22 +141,22 @@ impl ModemControlRegister0Builder {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(validate = "Self::validate"))]
+#[builder(no_std, build_fn(validate = "Self::validate", error = "BuildError"))]
 pub struct ModemControlRegister1 {
     // Demodulator correlator threshold value, required before SFD search.
     #[builder(default = "20")]
-    corr_threshold: u8,
+    pub corr_threshold: u8,
     // Frequency Offset Average Filter Behavior
     // 0: Lock frequency offset filter after preamble match
     // 1: Continuously update frequency offset filter.
     #[builder(default = "false")]
-    demod_average_mode: bool,
+    pub demod_average_mode: bool,
     // Set one of two RF modulation modes for RX / TX
     // 0: IEEE 802.15.4 compliant mode
     // 1: Reversed phase, non-IEEE compliant (could be used to set
     // up a system which will no receive 802.15.4 packets)
     #[builder(default = "false")]
-    modulation_mode: bool,
+    pub modulation_mode: bool,
     // Set test modes for TX
     // 0: Buffered mode, use TXFIFO (normal operation)
     // 1: Serial mode, use transmit data on serial interface, infinite
@@ -166,7 +166,7 @@ pub struct ModemControlRegister1 {
     // 3: Send random data from CFC, infinite transmission.  For lab testing
     // only.
     #[builder(default = "0")]
-    tx_mode: u8,
+    pub tx_mode: u8,
     // Set test mode of RX
     // 0: Buffered mode, use RXFIFO (normal operation)
     // 1: Receive serial mode, output received data on pins. Infinite
@@ -175,7 +175,7 @@ pub struct ModemControlRegister1 {
     // reception. For lab testing only
     // 3: Reserved
     #[builder(default = "0")]
-    rx_mode: u8,
+    pub rx_mode: u8,
 }
 
 impl Register for ModemControlRegister1 {
@@ -199,10 +199,10 @@ impl Register for ModemControlRegister1 {
         value
     }
 
-    fn address(&self) -> u8 { 0x11 }
+    fn address(&self) -> u8 { 0x12 }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -219,22 +219,22 @@ impl From<u16> for ModemControlRegister1 {
 }
 
 impl ModemControlRegister1Builder {
-    fn validate(&self) -> Result<(), String> {
+    fn validate(&self) -> Result<(), &'static str> {
         if let Some(corr_threshold) = self.corr_threshold {
             if corr_threshold > 31 {
-                return Err("Invalid Correlation Threshold, Expected 0<=CORR_THR<=31".into());
+                return Err("Invalid Correlation Threshold, Expected 0<=CORR_THR<=31");
             }
         }
 
         if let Some(tx_mode) = self.tx_mode {
             if tx_mode > 3 {
-                return Err("Invalid Tx Mode, Expected 0<=TX_MODE<=3".into());
+                return Err("Invalid Tx Mode, Expected 0<=TX_MODE<=3");
             }
         }
 
         if let Some(rx_mode) = self.rx_mode {
             if rx_mode > 2 {
-                return Err("Invalid Rx Mode, Expected 0<=RX_MODE<=2".into());
+                return Err("Invalid Rx Mode, Expected 0<=RX_MODE<=2");
             }
         }
 
@@ -242,7 +242,9 @@ impl ModemControlRegister1Builder {
     }
 }
 
+// The expected register values are grouped by field
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     use super::*;
 
@@ -455,4 +457,14 @@ mod tests {
             value.into(),
         )
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_modem_control_register_addresses() {
+        let modem_control_0 = ModemControlRegister0Builder::default().build().unwrap();
+        let modem_control_1 = ModemControlRegister1Builder::default().build().unwrap();
+
+        // MDMCTRL1 follows MDMCTRL0, it must not alias it
+        assert_eq!(modem_control_0.address(), 0x11);
+        assert_eq!(modem_control_1.address(), 0x12);
+    }
+}
@@ -3,11 +3,12 @@
 //! 
 
 use super::Register;
+use crate::error::BuildError;
 
 use derive_builder::Builder;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(error(validation_error = false)))]
+#[builder(no_std, build_fn(error = "BuildError"))]
 pub struct AndOverrideRegister {
     // The VGA_RESET_N signal is used to reset the peak detectors
     // in the VGA in the RX chain.
@@ -139,7 +140,7 @@ impl Register for AndOverrideRegister {
     fn address(&self) -> u8 { 0x21 }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -167,7 +168,7 @@ impl From<u16> for AndOverrideRegister {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(error(validation_error = false)))]
+#[builder(no_std, build_fn(error = "BuildError"))]
 pub struct OrOverrideRegister {
     // The VGA_RESET_N signal is used to reset the peak detectors
     // in the VGA in the RX chain.
@@ -299,7 +300,7 @@ impl Register for OrOverrideRegister {
     fn address(&self) -> u8 { 0x22 }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -4,12 +4,12 @@
 
 use super::Register;
 
-use alloc::string::String;
+use crate::error::BuildError;
 
 use derive_builder::Builder;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(validate = "Self::validate"))]
+#[builder(no_std, build_fn(validate = "Self::validate", error = "BuildError"))]
 pub struct ReceiveControlRegister0 {
     // RX mixer buffer bias current.
     // 0: 690 uA
@@ -73,7 +73,7 @@ impl Register for ReceiveControlRegister0 {
     fn address(&self) -> u8 { 0x16 }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -92,46 +92,46 @@ impl From<u16> for ReceiveControlRegister0 {
 }
 
 impl ReceiveControlRegister0Builder {
-    fn validate(&self) -> Result<(), String> {
+    fn validate(&self) -> Result<(), &'static str> {
         if let Some(rx_mix_buf_current) = self.rx_mix_buf_current {
             if rx_mix_buf_current > 3 {
-                return Err("Invalid RXMIXBUF_COR. 0<=RXMIXBUF_CUR<=3".into());
+                return Err("Invalid RXMIXBUF_COR. 0<=RXMIXBUF_CUR<=3");
             }
         }
 
         if let Some(high_lna_gain) = self.high_lna_gain {
             if high_lna_gain > 3 {
-                return Err("Invalid HIGH_LNA_GAIN. 0<=HIGH_LNA_GAIN<=3".into());
+                return Err("Invalid HIGH_LNA_GAIN. 0<=HIGH_LNA_GAIN<=3");
             }
         }
 
         if let Some(med_lna_gain) = self.med_lna_gain {
             if med_lna_gain > 3 {
-                return Err("Invalid MED_LNA_GAIN. 0<=MED_LNA_GAIN<=3".into());
+                return Err("Invalid MED_LNA_GAIN. 0<=MED_LNA_GAIN<=3");
             }
         }
 
         if let Some(low_lna_gain) = self.low_lna_gain {
             if low_lna_gain > 3 {
-                return Err("Invalid LOW_LNA_GAIN. 0<=LOW_LNA_GAIN<=3".into());
+                return Err("Invalid LOW_LNA_GAIN. 0<=LOW_LNA_GAIN<=3");
             }
         }
 
         if let Some(high_lna_current) = self.high_lna_current {
             if high_lna_current > 3 {
-                return Err("Invalid HIGH_LNA_CURRENT. 0<=HIGH_LNA_GAIN<=3".into());
+                return Err("Invalid HIGH_LNA_CURRENT. 0<=HIGH_LNA_GAIN<=3");
             }
         }
 
         if let Some(med_lna_current) = self.med_lna_current {
             if med_lna_current > 3 {
-                return Err("Invalid MED_LNA_CURRENT. 0<=MED_LNA_GAIN<=3".into());
+                return Err("Invalid MED_LNA_CURRENT. 0<=MED_LNA_GAIN<=3");
             }
         }
 
         if let Some(low_lna_current) = self.low_lna_current {
             if low_lna_current > 3 {
-                return Err("Invalid LOW_LNA_CURRENT. 0<=LOW_LNA_CURRENT<=3".into());
+                return Err("Invalid LOW_LNA_CURRENT. 0<=LOW_LNA_CURRENT<=3");
             }
         }
 
@@ -140,7 +140,7 @@ impl ReceiveControlRegister0Builder {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(validate = "Self::validate"))]
+#[builder(no_std, build_fn(validate = "Self::validate", error = "BuildError"))]
 pub struct ReceiveControlRegister1 {
     // Controls reference bias current to RX bandpass filters:
     // 0: 4 uA (Reset value) Use 1 instead
@@ -236,7 +236,7 @@ impl Register for ReceiveControlRegister1 {
     fn address(&self) -> u8 { 0x17 }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -248,7 +248,7 @@ impl From<u16> for ReceiveControlRegister1 {
             low_lowgain: (((1 << 11) & value) != 0),
             med_lowgain: (((1 << 10) & value) != 0),
             high_hgm: (((1 << 9) & value) != 0),
-            med_hgm: (((1 << 10) & value) != 0),
+            med_hgm: (((1 << 8) & value) != 0),
             lna_cap_array: (((0b11 << 6) & value) >> 6) as u8,
             rxmix_tail: (((0b11 << 4) & value) >> 4) as u8,
             rxmix_vcm: (((0b11 << 2) & value) >> 2) as u8,
@@ -258,28 +258,28 @@ impl From<u16> for ReceiveControlRegister1 {
 }
 
 impl ReceiveControlRegister1Builder {
-    fn validate(&self) -> Result<(), String> {
+    fn validate(&self) -> Result<(), &'static str> {
         if let Some(lna_cap_array) = self.lna_cap_array {
             if lna_cap_array > 3 {
-                return Err("Invalid LNA_CAP_ARRAY. 0<=LNA_CAP_ARRAY<=3".into());
+                return Err("Invalid LNA_CAP_ARRAY. 0<=LNA_CAP_ARRAY<=3");
             }
         }
 
         if let Some(rxmix_tail) = self.rxmix_tail {
             if rxmix_tail > 3 {
-                return Err("Invalid RXMIX_TAIL. 0<=RXMIX_TAIL<=3".into());
+                return Err("Invalid RXMIX_TAIL. 0<=RXMIX_TAIL<=3");
             }
         }
 
         if let Some(rxmix_vcm) = self.rxmix_vcm {
             if rxmix_vcm > 3 {
-                return Err("Invalid RXMIX_VCM. 0<=RXMIX_VCM<=3".into());
+                return Err("Invalid RXMIX_VCM. 0<=RXMIX_VCM<=3");
             }
         }
 
         if let Some(rxmix_current) = self.rxmix_current {
             if rxmix_current > 3 {
-                return Err("Invalid RXMIX_CURRENT. 0<=RXMIX_CURRENT<=3".into());
+                return Err("Invalid RXMIX_CURRENT. 0<=RXMIX_CURRENT<=3");
             }
         }
 
@@ -287,7 +287,9 @@ impl ReceiveControlRegister1Builder {
     }
 }
 
+// The expected register values are grouped by field
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     use super::*;
 
@@ -4,12 +4,12 @@
 
 use super::Register;
 
-use alloc::string::String;
+use crate::error::BuildError;
 
 use derive_builder::Builder;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(error(validation_error = false)))]
+#[builder(no_std, build_fn(error = "BuildError"))]
 pub struct RSSIRegister {
     // Clear Channel Assessment threshold value, signed number on
     // 2's complement for comparison with the RSSI.
@@ -44,7 +44,7 @@ impl Register for RSSIRegister {
     fn address(&self) -> u8 { 0x13 }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -4,12 +4,12 @@
 
 use super::Register;
 
-use alloc::string::String;
+use crate::error::BuildError;
 
 use derive_builder::Builder;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(validate = "Self::validate"))]
+#[builder(no_std, build_fn(validate = "Self::validate", error = "BuildError"))]
 pub struct SecurityControlRegister0 {
     // Protection enable of the RXFIFO, see description in the RXFIFO
     // overflow section on page 33. Should be cleared if MAC level
@@ -92,7 +92,7 @@ impl Register for SecurityControlRegister0 {
     fn address(&self) -> u8 { 0x19 }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -111,16 +111,16 @@ impl From<u16> for SecurityControlRegister0 {
 }
 
 impl SecurityControlRegister0Builder {
-    fn validate(&self) -> Result<(), String> {
+    fn validate(&self) -> Result<(), &'static str> {
         if let Some(sec_m) = self.sec_m {
             if sec_m == 0 || sec_m > 7 {
-                return Err("Invalid SEC_M. Expected 1<=SEC_M<=7".into());
+                return Err("Invalid SEC_M. Expected 1<=SEC_M<=7");
             }
         }
 
         if let Some(sec_mode) = self.sec_mode {
             if sec_mode > 3 {
-                return Err("Invalid SEC_MODE. Expected 0<=SEC_MODE<=3".into());
+                return Err("Invalid SEC_MODE. Expected 0<=SEC_MODE<=3");
             }
         }
 
@@ -129,7 +129,7 @@ impl SecurityControlRegister0Builder {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(validate = "Self::validate"))]
+#[builder(no_std, build_fn(validate = "Self::validate", error = "BuildError"))]
 pub struct SecurityControlRegister1 {
     // Multi-purpose length byte for TX in-line security operations:
     // CTR: Number of cleartext bytes between length byte and the
@@ -167,7 +167,7 @@ impl Register for SecurityControlRegister1 {
     fn address(&self) -> u8 { 0x1A }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -181,16 +181,16 @@ impl From<u16> for SecurityControlRegister1 {
 }
 
 impl SecurityControlRegister1Builder {
-    fn validate(&self) -> Result<(), String> {
+    fn validate(&self) -> Result<(), &'static str> {
         if let Some(sec_txl) = self.sec_txl {
             if sec_txl > 127 {
-                return Err("Invalid SEC_TXL. 0<=SEC_TXL<=127".into());
+                return Err("Invalid SEC_TXL. 0<=SEC_TXL<=127");
             }
         }
 
         if let Some(sec_rxl) = self.sec_rxl {
             if sec_rxl > 127 {
-                return Err("Invalid SEC_RXL. 0<=SEC_RXL<=127".into());
+                return Err("Invalid SEC_RXL. 0<=SEC_RXL<=127");
             }
         }
 
@@ -198,7 +198,9 @@ impl SecurityControlRegister1Builder {
     }
 }
 
+// The expected register values are grouped by field
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     use super::*;
 
@@ -3,10 +3,11 @@
 //! 
 
 use super::Register;
+use crate::error::BuildError;
 use derive_builder::Builder;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(error(validation_error = false)))]
+#[builder(no_std, build_fn(error = "BuildError"))]
 pub struct SyncWordRegister {
     // Synchronization Word.  The SYNCWORD is processed from the
     // least significant nibble (F at reset) to the most significant
@@ -28,7 +29,7 @@ impl Register for SyncWordRegister {
     fn address(&self) -> u8 { 0x14 }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
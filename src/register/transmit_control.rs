@@ -4,12 +4,12 @@
 
 use super::Register;
 
-use alloc::string::String;
+use crate::error::BuildError;
 
 use derive_builder::Builder;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
-#[builder(no_std, build_fn(validate = "Self::validate"))]
+#[builder(no_std, build_fn(validate = "Self::validate", error = "BuildError"))]
 pub struct TransmitControlRegister {
     // TX Mixer buffer bias current.
     // 0: 690 uA
@@ -73,7 +73,7 @@ impl Register for TransmitControlRegister {
     fn address(&self) -> u8 { 0x15 }
 
     fn fill_from_buffer(&mut self, buffer: [u8; 3]) {
-        *self = u16::from_le_bytes(buffer[1..3].try_into().unwrap()).into();
+        *self = u16::from_be_bytes(buffer[1..3].try_into().unwrap()).into();
     }
 }
 
@@ -91,34 +91,34 @@ impl From<u16> for TransmitControlRegister {
 }
 
 impl TransmitControlRegisterBuilder {
-    fn validate(&self) -> Result<(), String> {
+    fn validate(&self) -> Result<(), &'static str> {
         if let Some(tx_mix_buf_current) = self.tx_mix_buffer_current {
             if tx_mix_buf_current > 3 {
-                return Err("Invalid TXMIXBUF_CUR. Expected 0<=TXMIXBUF_CUR<=3".into());
+                return Err("Invalid TXMIXBUF_CUR. Expected 0<=TXMIXBUF_CUR<=3");
             }
         }
 
         if let Some(tx_mix_cap_array) = self.tx_mix_cap_array {
             if tx_mix_cap_array > 3 {
-                return Err("Invalid TXMIX_CAP_ARRAY. Expected 0<=TXMIX_CAP_ARRAY<=3".into());
+                return Err("Invalid TXMIX_CAP_ARRAY. Expected 0<=TXMIX_CAP_ARRAY<=3");
             }
         }
 
         if let Some(tx_mix_current) = self.tx_mix_current {
             if tx_mix_current > 3 {
-                return Err("Invalid TXMIX_CURRENT. Expected 0<=TXMIX_CURRENT<=3".into());
+                return Err("Invalid TXMIX_CURRENT. Expected 0<=TXMIX_CURRENT<=3");
             }
         }
 
         if let Some(pa_current) = self.pa_current {
             if pa_current > 7 {
-                return Err("Invalid PA_CURRENT. Expected 0<=PA_CURRENT<=7".into());
+                return Err("Invalid PA_CURRENT. Expected 0<=PA_CURRENT<=7");
             }
         }
 
         if let Some(pa_level) = self.pa_level {
             if pa_level > 31 {
-                return Err("Invalid PA_LEVEL. Expected 0<=PA_LEVEL<=31".into());
+                return Err("Invalid PA_LEVEL. Expected 0<=PA_LEVEL<=31");
             }
         }
 
@@ -126,7 +126,9 @@ impl TransmitControlRegisterBuilder {
     }
 }
 
+// The expected register values are grouped by field
 #[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
 mod tests {
     use super::*;
 
@@ -0,0 +1,64 @@
+//!
+//! Selection of the CC2420 modules reset by Radio::reset_subsystem
+//! 
+
+use core::ops::{BitOr, BitOrAssign};
+
+/// Set of modules with their own reset bit in the MAIN register
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct ResetMask(u8);
+
+impl ResetMask {
+    /// No modules
+    pub const NONE: ResetMask = ResetMask(0);
+    /// The encryption module (MAIN.ENC_RESETn)
+    pub const ENCRYPTION: ResetMask = ResetMask(1 << 0);
+    /// The demodulator (MAIN.DEMOD_RESETn)
+    pub const DEMODULATOR: ResetMask = ResetMask(1 << 1);
+    /// The modulator (MAIN.MOD_RESETn)
+    pub const MODULATOR: ResetMask = ResetMask(1 << 2);
+    /// The frequency synthesizer (MAIN.FS_RESETn)
+    pub const FREQUENCY_SYNTHESIZER: ResetMask = ResetMask(1 << 3);
+    /// Every module above (but not the whole chip, see Radio::full_reset)
+    pub const ALL: ResetMask = ResetMask(0b1111);
+
+    /// Whether every module in other is also in this mask
+    pub fn contains(&self, other: ResetMask) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether no module is selected
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for ResetMask {
+    type Output = ResetMask;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        ResetMask(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for ResetMask {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_mask() {
+        let mask = ResetMask::ENCRYPTION | ResetMask::MODULATOR;
+        assert!(mask.contains(ResetMask::ENCRYPTION));
+        assert!(mask.contains(ResetMask::MODULATOR));
+        assert!(!mask.contains(ResetMask::DEMODULATOR));
+        assert!(!mask.contains(ResetMask::ENCRYPTION | ResetMask::FREQUENCY_SYNTHESIZER));
+        assert!(ResetMask::ALL.contains(mask));
+        assert!(ResetMask::NONE.is_empty());
+    }
+}
@@ -0,0 +1,292 @@
+//!
+//! Types for the CC2420's in-line security operations
+//! 
+
+use crate::address::IEEEAddress;
+#[cfg(feature = "replay-protection")]
+use crate::address::ShortAddress;
+
+/// The 16-byte Nonce used by in-line authentication and (in CTR mode) as the
+/// encryption counter.
+/// 
+/// Layout used by this driver:
+/// - bytes `[0..4]`: flags / reserved (zero)
+/// - bytes `[4..12]`: IEEE address of the source node
+/// - bytes `[12..16]`: little-endian frame counter
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Nonce([u8; 16]);
+
+impl Nonce {
+    /// Offset of the frame counter bytes in the nonce
+    pub const COUNTER_OFFSET: usize = 12;
+
+    /// Create a nonce from its raw bytes
+    pub fn new(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    /// Create a nonce from the source IEEE address and an initial frame
+    /// counter value
+    pub fn from_ieee_and_counter(addr: IEEEAddress, counter: u32) -> Nonce {
+        let mut bytes = [0u8; 16];
+        bytes[4..Self::COUNTER_OFFSET].copy_from_slice(&addr.0);
+        bytes[Self::COUNTER_OFFSET..].copy_from_slice(&counter.to_le_bytes());
+        Self(bytes)
+    }
+
+    /// The current value of the frame counter (bytes `[12..16]`)
+    pub fn counter(&self) -> u32 {
+        u32::from_le_bytes(self.0[Self::COUNTER_OFFSET..].try_into().unwrap())
+    }
+
+    /// Increment the frame counter, carrying from byte 12 up into byte 15.
+    /// 
+    /// Note: The counter wraps to 0 after 0xFFFF_FFFF, the caller is
+    /// responsible for not reusing a nonce after this happens.
+    pub fn increment_counter(&mut self) {
+        let counter = self.counter().wrapping_add(1);
+        self.0[Self::COUNTER_OFFSET..].copy_from_slice(&counter.to_le_bytes());
+    }
+
+    /// The raw bytes of the nonce
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+impl From<[u8; 16]> for Nonce {
+    fn from(value: [u8; 16]) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Nonce> for [u8; 16] {
+    fn from(value: Nonce) -> Self {
+        value.0
+    }
+}
+
+/// 128-bit AES key
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct EncryptionKey(pub [u8; 16]);
+
+impl From<[u8; 16]> for EncryptionKey {
+    fn from(value: [u8; 16]) -> Self {
+        Self(value)
+    }
+}
+
+impl From<EncryptionKey> for [u8; 16] {
+    fn from(value: EncryptionKey) -> Self {
+        value.0
+    }
+}
+
+/// In-line security mode (SECCTRL0.SEC_MODE)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SecurityMode {
+    #[default]
+    Disabled = 0,
+    CbcMac = 1,
+    Ctr = 2,
+    Ccm = 3,
+}
+
+impl SecurityMode {
+    /// The SEC_MODE field value
+    pub(crate) fn sec_mode(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// One of the two AES key slots in the CC2420's RAM
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum KeySlot {
+    #[default]
+    Key0,
+    Key1,
+}
+
+impl KeySlot {
+    /// The value of the key select bits in SECCTRL0 for this slot
+    pub(crate) fn select_bit(&self) -> bool {
+        matches!(self, Self::Key1)
+    }
+}
+
+/// Length (in bytes) of a 4 byte MIC
+pub const MIC_4_BYTES: u8 = 4;
+/// Length (in bytes) of an 8 byte MIC
+pub const MIC_8_BYTES: u8 = 8;
+/// Length (in bytes) of a 16 byte MIC
+pub const MIC_16_BYTES: u8 = 16;
+
+/// Size of the Message Integrity Code (MIC) produced by CBC-MAC and CCM
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SecurityLevel {
+    #[default]
+    Mic4,
+    Mic8,
+    Mic16,
+}
+
+impl SecurityLevel {
+    /// The number of bytes the MIC (authentication tag) occupies.
+    /// 
+    /// Note: The MIC is appended to the payload, so it must be accounted for
+    /// in the total frame length (at most 127 bytes including the MAC header)
+    pub fn mic_bytes(self) -> u8 {
+        match self {
+            Self::Mic4 => MIC_4_BYTES,
+            Self::Mic8 => MIC_8_BYTES,
+            Self::Mic16 => MIC_16_BYTES,
+        }
+    }
+
+    /// The SEC_M field value, encoded as (M-2)/2
+    pub(crate) fn sec_m(&self) -> u8 {
+        (self.mic_bytes() - 2) / 2
+    }
+}
+
+/// Maximum number of source addresses tracked for replay protection
+#[cfg(feature = "replay-protection")]
+pub const MAX_REPLAY_SOURCES: usize = 16;
+
+/// Highest frame counter received from each source address, used to reject
+/// replayed CTR / CCM frames.
+#[cfg(feature = "replay-protection")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct ReplayCounters {
+    entries: [Option<(ShortAddress, u32)>; MAX_REPLAY_SOURCES],
+}
+
+#[cfg(feature = "replay-protection")]
+impl ReplayCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The last accepted frame counter from a given source
+    pub fn get(&self, src: ShortAddress) -> Option<u32> {
+        self.entries.iter()
+            .flatten()
+            .find(|(address, _)| *address == src)
+            .map(|(_, counter)| *counter)
+    }
+
+    /// Accept a frame counter from a given source if it is newer than the last
+    /// one accepted from that source, storing it when accepted.
+    /// 
+    /// Note: When every slot is in use, frames from new sources are rejected
+    pub fn check_and_update(&mut self, src: ShortAddress, frame_counter: u32) -> bool {
+        if let Some((_, counter)) = self.entries.iter_mut().flatten().find(|(address, _)| *address == src) {
+            if frame_counter <= *counter {
+                return false;
+            }
+            *counter = frame_counter;
+            return true;
+        }
+
+        match self.entries.iter_mut().find(|entry| entry.is_none()) {
+            Some(entry) => {
+                *entry = Some((src, frame_counter));
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Forget every tracked source
+    pub fn clear(&mut self) {
+        self.entries = [None; MAX_REPLAY_SOURCES];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nonce_from_ieee_and_counter() {
+        let address = IEEEAddress([0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0]);
+        let nonce = Nonce::from_ieee_and_counter(address, 0x0403_0201);
+
+        assert_eq!(
+            nonce.as_bytes(),
+            &[
+                0x00, 0x00, 0x00, 0x00,
+                0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0,
+                0x01, 0x02, 0x03, 0x04,
+            ],
+        )
+    }
+
+    #[test]
+    fn test_nonce_increment_counter() {
+        let mut nonce = Nonce::from_ieee_and_counter(IEEEAddress::default(), 1);
+        nonce.increment_counter();
+
+        assert_eq!(nonce.counter(), 2);
+    }
+
+    #[test]
+    fn test_nonce_increment_counter_carry() {
+        let mut nonce = Nonce::from_ieee_and_counter(IEEEAddress::default(), 0xFF);
+        nonce.increment_counter();
+
+        assert_eq!(
+            &nonce.as_bytes()[12..],
+            &[0x00, 0x01, 0x00, 0x00],
+        )
+    }
+
+    #[test]
+    fn test_nonce_increment_counter_wraps() {
+        let mut nonce = Nonce::from_ieee_and_counter(IEEEAddress::default(), u32::MAX);
+        nonce.increment_counter();
+
+        assert_eq!(nonce.counter(), 0);
+        assert_eq!(&nonce.as_bytes()[..12], &[0u8; 12]);
+    }
+
+    #[cfg(feature = "replay-protection")]
+    #[test]
+    fn test_replay_counters_rejects_old_counters() {
+        let mut counters = ReplayCounters::new();
+
+        assert!(counters.check_and_update(ShortAddress(0x0001), 5));
+        assert!(!counters.check_and_update(ShortAddress(0x0001), 5));
+        assert!(!counters.check_and_update(ShortAddress(0x0001), 4));
+        assert!(counters.check_and_update(ShortAddress(0x0001), 6));
+        assert!(counters.check_and_update(ShortAddress(0x0002), 0));
+        assert_eq!(counters.get(ShortAddress(0x0001)), Some(6));
+        assert_eq!(counters.get(ShortAddress(0x0002)), Some(0));
+    }
+
+    #[cfg(feature = "replay-protection")]
+    #[test]
+    fn test_replay_counters_full() {
+        let mut counters = ReplayCounters::new();
+        for address in 0..MAX_REPLAY_SOURCES as u16 {
+            assert!(counters.check_and_update(ShortAddress(address), 1));
+        }
+
+        assert!(!counters.check_and_update(ShortAddress(0x1000), 1));
+        assert!(counters.check_and_update(ShortAddress(0x0000), 2));
+    }
+
+    #[test]
+    fn test_security_level_mic_bytes() {
+        assert_eq!(SecurityLevel::Mic4.mic_bytes(), MIC_4_BYTES);
+        assert_eq!(SecurityLevel::Mic8.mic_bytes(), MIC_8_BYTES);
+        assert_eq!(SecurityLevel::Mic16.mic_bytes(), MIC_16_BYTES);
+    }
+
+    #[test]
+    fn test_security_level_sec_m() {
+        assert_eq!(SecurityLevel::Mic4.sec_m(), 1);
+        assert_eq!(SecurityLevel::Mic8.sec_m(), 3);
+        assert_eq!(SecurityLevel::Mic16.sec_m(), 7);
+    }
+}
@@ -0,0 +1,269 @@
+//!
+//! Type-State Wrapper enforcing the order of Radio operations at compile time
+//! 
+
+// Failed transitions return the whole machine by value so the radio is not
+// lost, and without an allocator there is nothing to box it in
+#![allow(clippy::result_large_err)]
+
+use core::marker::PhantomData;
+
+use embedded_hal::spi::SpiDevice;
+use embedded_hal::digital::InputPin;
+use embedded_hal::delay::DelayNs;
+
+use crate::{Radio, RadioError, RadioStatus, Configuration, NoPin};
+
+/// Result of moving a RadioStateMachine from state S into state T.  On failure
+/// the machine is returned (still in state S) along with the error, so the
+/// radio is not lost.
+pub type Transition<SPI, SPIE, SFD, GPIOE, FIFO, S, T, FIFOP = NoPin<GPIOE>> = Result<
+    RadioStateMachine<SPI, SPIE, SFD, GPIOE, FIFO, T, FIFOP>,
+    (RadioStateMachine<SPI, SPIE, SFD, GPIOE, FIFO, S, FIFOP>, RadioError<SPIE, GPIOE>),
+>;
+
+/// State of the radio as tracked by the Radio at run time (the strobes the
+/// driver has sent), used to reject operations that are invalid in the
+/// current state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum RadioState {
+    // The crystal oscillator is off (or the radio has been reset)
+    #[default]
+    PoweredDown,
+    // The crystal oscillator is running, RX and TX are off
+    Idle,
+    // The frequency synthesizer is calibrating (STXCAL)
+    Calibrating,
+    // RX mode is enabled
+    Receiving,
+    // A transmission has been started (STXON / STXONCCA)
+    Transmitting,
+}
+
+/// The radio has not been configured (or has been powered down)
+pub struct PoweredDown;
+
+/// The crystal oscillator is running and the radio is idle
+pub struct Oscillating;
+
+/// The radio is in RX mode
+pub struct Receiving;
+
+/// The radio is in TX mode
+pub struct Transmitting;
+
+/// Wrapper around a Radio that tracks the state of the radio in its type, so
+/// that methods are only available in the states they are valid in (e.g. a
+/// frame can only be sent once the radio has been configured).
+pub struct RadioStateMachine<SPI, SPIE, SFD, GPIOE, FIFO, S, FIFOP = NoPin<GPIOE>>(Radio<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP>, PhantomData<S>) where
+    SPI: SpiDevice<u8, Error=SPIE>,
+    SFD: InputPin<Error=GPIOE>,
+    FIFO: InputPin<Error=GPIOE>,
+    FIFOP: InputPin<Error=GPIOE>;
+
+impl<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP, S> RadioStateMachine<SPI, SPIE, SFD, GPIOE, FIFO, S, FIFOP> where
+    SPI: SpiDevice<u8, Error=SPIE>,
+    SFD: InputPin<Error=GPIOE>,
+    FIFO: InputPin<Error=GPIOE>,
+    FIFOP: InputPin<Error=GPIOE> {
+    /// Release the underlying Radio, giving up the compile time state checks
+    pub fn into_inner(self) -> Radio<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP> {
+        self.0
+    }
+
+    /// Read the status of the radio
+    pub fn status(&mut self) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.0.status()
+    }
+
+    fn transition<T>(self) -> RadioStateMachine<SPI, SPIE, SFD, GPIOE, FIFO, T, FIFOP> {
+        RadioStateMachine(self.0, PhantomData)
+    }
+
+    /// Move into state T if the operation moving the radio there succeeded
+    fn transition_on<T, R>(self, result: Result<R, RadioError<SPIE, GPIOE>>) -> Transition<SPI, SPIE, SFD, GPIOE, FIFO, S, T, FIFOP> {
+        match result {
+            Ok(_) => Ok(self.transition()),
+            Err(error) => Err((self, error)),
+        }
+    }
+}
+
+impl<SPI, SPIE, SFD, GPIOE, FIFO> RadioStateMachine<SPI, SPIE, SFD, GPIOE, FIFO, PoweredDown> where
+    SPI: SpiDevice<u8, Error=SPIE>,
+    SFD: InputPin<Error=GPIOE>,
+    FIFO: InputPin<Error=GPIOE>,
+    GPIOE: embedded_hal::digital::Error {
+    pub fn new(spi: SPI, sfd: SFD, fifo: FIFO) -> Self {
+        Self(Radio::new(spi, sfd, fifo), PhantomData)
+    }
+}
+
+impl<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP> RadioStateMachine<SPI, SPIE, SFD, GPIOE, FIFO, PoweredDown, FIFOP> where
+    SPI: SpiDevice<u8, Error=SPIE>,
+    SFD: InputPin<Error=GPIOE>,
+    FIFO: InputPin<Error=GPIOE>,
+    FIFOP: InputPin<Error=GPIOE> {
+    /// Construct the radio with the FIFOP pin connected
+    pub fn new_with_fifop(spi: SPI, sfd: SFD, fifo: FIFO, fifop: FIFOP) -> Self {
+        Self(Radio::new_with_fifop(spi, sfd, fifo, fifop), PhantomData)
+    }
+
+    /// Apply a given configuration to the radio and start the crystal
+    /// oscillator, waiting at most timeout_us for it to stabilise.
+    pub fn configure(mut self, config: Configuration, delay: &mut dyn DelayNs, timeout_us: u32) -> Transition<SPI, SPIE, SFD, GPIOE, FIFO, PoweredDown, Oscillating, FIFOP> {
+        let result = self.0.configure(config, delay, timeout_us);
+        self.transition_on(result)
+    }
+}
+
+impl<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP> RadioStateMachine<SPI, SPIE, SFD, GPIOE, FIFO, Oscillating, FIFOP> where
+    SPI: SpiDevice<u8, Error=SPIE>,
+    SFD: InputPin<Error=GPIOE>,
+    FIFO: InputPin<Error=GPIOE>,
+    FIFOP: InputPin<Error=GPIOE> {
+    /// Send a Frame (<=128 Bytes of Data) without CCA (STXON)
+    /// 
+    /// Note: CCA is only valid in RX mode, start receiving to send with CCA.
+    pub fn send_frame(mut self, data: &[u8]) -> Transition<SPI, SPIE, SFD, GPIOE, FIFO, Oscillating, Transmitting, FIFOP> {
+        let result = self.0.send_no_cca(data);
+        self.transition_on(result)
+    }
+
+    /// Start Receiving Data
+    pub fn start_receiving(mut self) -> Transition<SPI, SPIE, SFD, GPIOE, FIFO, Oscillating, Receiving, FIFOP> {
+        let result = self.0.start_receiving();
+        self.transition_on(result)
+    }
+
+    /// Power down the Radio
+    pub fn power_down(mut self) -> Transition<SPI, SPIE, SFD, GPIOE, FIFO, Oscillating, PoweredDown, FIFOP> {
+        let result = self.0.power_down();
+        self.transition_on(result)
+    }
+}
+
+impl<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP> RadioStateMachine<SPI, SPIE, SFD, GPIOE, FIFO, Transmitting, FIFOP> where
+    SPI: SpiDevice<u8, Error=SPIE>,
+    SFD: InputPin<Error=GPIOE>,
+    FIFO: InputPin<Error=GPIOE>,
+    FIFOP: InputPin<Error=GPIOE> {
+    /// Check whether the radio is still transmitting
+    pub fn tx_active(&mut self) -> Result<bool, RadioError<SPIE, GPIOE>> {
+        Ok(self.0.status()?.tx_active)
+    }
+
+    /// Leave TX mode, returning to the idle (oscillating) state
+    pub fn into_idle(mut self) -> Transition<SPI, SPIE, SFD, GPIOE, FIFO, Transmitting, Oscillating, FIFOP> {
+        let result = self.0.disable_rx_tx();
+        self.transition_on(result)
+    }
+}
+
+impl<SPI, SPIE, SFD, GPIOE, FIFO, FIFOP> RadioStateMachine<SPI, SPIE, SFD, GPIOE, FIFO, Receiving, FIFOP> where
+    SPI: SpiDevice<u8, Error=SPIE>,
+    SFD: InputPin<Error=GPIOE>,
+    FIFO: InputPin<Error=GPIOE>,
+    FIFOP: InputPin<Error=GPIOE> {
+    /// Check if Data is Ready
+    pub fn data_ready(&mut self) -> Result<bool, RadioError<SPIE, GPIOE>> {
+        self.0.data_ready()
+    }
+
+    /// Check if the FIFOP pin is asserted (always false with NoPin)
+    pub fn fifop_asserted(&mut self) -> Result<bool, RadioError<SPIE, GPIOE>> {
+        self.0.fifop_asserted()
+    }
+
+    /// Read data from the RX FIFO (equal to the length of the buffer) into a
+    /// given buffer, returning the radio status
+    pub fn receive(&mut self, buffer: &mut [u8]) -> Result<RadioStatus, RadioError<SPIE, GPIOE>> {
+        self.0.receive(buffer)
+    }
+
+    /// Send a Frame (<=128 Bytes of Data) if the channel is clear (STXONCCA)
+    pub fn send_frame(mut self, data: &[u8]) -> Transition<SPI, SPIE, SFD, GPIOE, FIFO, Receiving, Transmitting, FIFOP> {
+        let result = self.0.send_with_cca(data);
+        self.transition_on(result)
+    }
+
+    /// Leave RX mode, returning to the idle (oscillating) state
+    pub fn into_idle(mut self) -> Transition<SPI, SPIE, SFD, GPIOE, FIFO, Receiving, Oscillating, FIFOP> {
+        let result = self.0.disable_rx_tx();
+        self.transition_on(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::convert::Infallible;
+
+    use crate::config::ConfigurationBuilder;
+    use crate::strobe::Strobe;
+    use crate::test_utils::{MockInputPin, MockSpi, NoDelay};
+    use crate::RadioState;
+
+    type MockMachine<S> = RadioStateMachine<MockSpi, Infallible, MockInputPin, Infallible, MockInputPin, S>;
+
+    fn powered_down() -> MockMachine<PoweredDown> {
+        RadioStateMachine::new(MockSpi::new(), MockInputPin::default(), MockInputPin::default())
+    }
+
+    #[test]
+    fn test_configure_failure_returns_machine() {
+        let config = ConfigurationBuilder::default().cca_mode(0).build().unwrap();
+
+        let Err((machine, error)) = powered_down().configure(config, &mut NoDelay, 1_000) else {
+            panic!("invalid configuration was applied");
+        };
+
+        assert!(matches!(error, RadioError::InvalidConfiguration(_)));
+        let radio = machine.into_inner();
+        assert_eq!(radio.state(), RadioState::PoweredDown);
+        assert!(radio.spi().transfers.is_empty());
+    }
+
+    #[test]
+    fn test_transitions() {
+        let oscillating: MockMachine<Oscillating> = powered_down().transition();
+
+        let Ok(receiving) = oscillating.start_receiving() else { panic!() };
+        let Ok(transmitting) = receiving.send_frame(&[0x01]) else { panic!() };
+        let Ok(oscillating) = transmitting.into_idle() else { panic!() };
+        let Ok(transmitting) = oscillating.send_frame(&[0x01, 0x02, 0x03]) else { panic!() };
+        let Ok(oscillating) = transmitting.into_idle() else { panic!() };
+        let Ok(powered_down) = oscillating.power_down() else { panic!() };
+
+        let radio = powered_down.into_inner();
+        assert_eq!(radio.state(), RadioState::PoweredDown);
+        let strobes: alloc::vec::Vec<u8> = radio.spi().transfers.iter()
+            .filter(|transfer| transfer.len() == 1)
+            .map(|transfer| transfer[0])
+            .collect();
+        assert_eq!(strobes, [
+            Strobe::EnableRx.opcode(),
+            Strobe::FlushTx.opcode(),
+            Strobe::EnableTxCCA.opcode(),
+            Strobe::DisableRxTx.opcode(),
+            Strobe::FlushTx.opcode(),
+            Strobe::EnableTx.opcode(),
+            Strobe::DisableRxTx.opcode(),
+            Strobe::DisableRxTx.opcode(),
+            Strobe::XOSCOff.opcode(),
+        ]);
+    }
+
+    #[test]
+    fn test_send_frame_failure_returns_machine() {
+        let oscillating: MockMachine<Oscillating> = powered_down().transition();
+
+        let Err((oscillating, error)) = oscillating.send_frame(&[0x00; 129]) else {
+            panic!("oversized frame was sent");
+        };
+
+        assert!(matches!(error, RadioError::InvalidBufferLenth { expected: 128, found: 129 }));
+        assert!(oscillating.start_receiving().is_ok());
+    }
+}
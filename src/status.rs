@@ -31,7 +31,7 @@ pub struct RadioStatus {
     // 0: The RSSI value is not valid
     // 1: The RSSI value is valid, always true when reception has been
     // enabled at least 8 symbol periods (128 us)
-    pub rssi_valud: bool,
+    pub rssi_valid: bool,
 }
 
 impl From<u8> for RadioStatus {
@@ -42,7 +42,63 @@ impl From<u8> for RadioStatus {
             enc_busy: (value & 1 << 4) != 0,
             tx_active: (value & 1 << 3) != 0,
             lock: (value & 1 << 2) != 0,
-            rssi_valud: (value & 1 << 1) != 0,
+            rssi_valid: (value & 1 << 1) != 0,
         }
     }
-}
\ No newline at end of file
+}
+
+impl RadioStatus {
+    /// Whether the crystal oscillator is running and the PLL is in lock
+    pub fn is_ready(&self) -> bool {
+        self.xosx_stable && self.lock
+    }
+
+    /// Whether no error flag (currently only TX_UNDERFLOW) is set
+    pub fn all_error_free(&self) -> bool {
+        !self.tx_underflow
+    }
+
+    /// Whether the RSSI value is valid
+    pub fn rssi_valid(&self) -> bool {
+        self.rssi_valid
+    }
+
+    /// Whether the TX FIFO has underflowed (cleared by SFLUSHTX)
+    pub fn has_tx_underflow(&self) -> bool {
+        self.tx_underflow
+    }
+
+    /// Whether the encryption module is busy
+    pub fn is_enc_busy(&self) -> bool {
+        self.enc_busy
+    }
+
+    /// Whether RF transmission is active
+    pub fn is_tx_active(&self) -> bool {
+        self.tx_active
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_ready() {
+        assert!(RadioStatus::from(0b0100_0100).is_ready());
+        assert!(!RadioStatus::from(0b0100_0000).is_ready());
+        assert!(!RadioStatus::from(0b0000_0100).is_ready());
+    }
+
+    #[test]
+    fn test_status_accessors() {
+        let status = RadioStatus::from(0b0011_1010);
+        assert!(status.rssi_valid());
+        assert!(status.has_tx_underflow());
+        assert!(status.is_enc_busy());
+        assert!(status.is_tx_active());
+        assert!(!RadioStatus::from(0x00).rssi_valid());
+        assert!(!status.all_error_free());
+        assert!(RadioStatus::from(0b0101_1110).all_error_free());
+    }
+}
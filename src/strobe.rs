@@ -3,6 +3,12 @@
 //! to perform duties
 //! 
 
+use core::fmt;
+
+/// Error returned when a byte is not the opcode of a command strobe
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryFromStrobeError(pub u8);
+
 /// Single Byte Instructions sent to the CC2420 Module
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Strobe {
@@ -75,4 +81,93 @@ impl Strobe {
     pub fn opcode(self) -> u8 {
         self as u8
     }
-}
\ No newline at end of file
+}
+
+impl From<Strobe> for u8 {
+    fn from(value: Strobe) -> Self {
+        value.opcode()
+    }
+}
+
+impl TryFrom<u8> for Strobe {
+    type Error = TryFromStrobeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(Self::ReadStatus),
+            0x01 => Ok(Self::XOSCOn),
+            0x02 => Ok(Self::CalibrateFrequency),
+            0x03 => Ok(Self::EnableRx),
+            0x04 => Ok(Self::EnableTx),
+            0x05 => Ok(Self::EnableTxCCA),
+            0x06 => Ok(Self::DisableRxTx),
+            0x07 => Ok(Self::XOSCOff),
+            0x08 => Ok(Self::FlushRx),
+            0x09 => Ok(Self::FlushTx),
+            0x0A => Ok(Self::Ack),
+            0x0B => Ok(Self::AckPend),
+            0x0C => Ok(Self::RxDecryption),
+            0x0D => Ok(Self::TxEncryption),
+            0x0E => Ok(Self::AesEncryption),
+            0x3E => Ok(Self::TxFifo),
+            0x3F => Ok(Self::RxFifo),
+            _ => Err(TryFromStrobeError(value)),
+        }
+    }
+}
+
+/// The datasheet mnemonic of the strobe (the FIFO registers are displayed
+/// by name)
+impl fmt::Display for Strobe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::ReadStatus => "SNOP",
+            Self::XOSCOn => "SXOSCON",
+            Self::CalibrateFrequency => "STXCAL",
+            Self::EnableRx => "SRXON",
+            Self::EnableTx => "STXON",
+            Self::EnableTxCCA => "STXONCCA",
+            Self::DisableRxTx => "SRFOFF",
+            Self::XOSCOff => "SXOSCOFF",
+            Self::FlushRx => "SFLUSHRX",
+            Self::FlushTx => "SFLUSHTX",
+            Self::Ack => "SACK",
+            Self::AckPend => "SACKPEND",
+            Self::RxDecryption => "SRXDEC",
+            Self::TxEncryption => "STXENC",
+            Self::AesEncryption => "SAES",
+            Self::TxFifo => "TXFIFO",
+            Self::RxFifo => "RXFIFO",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_strobe_round_trip() {
+        for opcode in (0x00..=0x0E).chain([0x3E, 0x3F]) {
+            let strobe = Strobe::try_from(opcode).unwrap();
+            assert_eq!(u8::from(strobe), opcode);
+        }
+    }
+
+    #[test]
+    fn test_strobe_try_from_unknown() {
+        assert_eq!(Strobe::try_from(0x0F), Err(TryFromStrobeError(0x0F)));
+        assert_eq!(Strobe::try_from(0x3D), Err(TryFromStrobeError(0x3D)));
+        assert_eq!(Strobe::try_from(0x40), Err(TryFromStrobeError(0x40)));
+    }
+
+    #[test]
+    fn test_strobe_display() {
+        assert_eq!(Strobe::XOSCOn.to_string(), "SXOSCON");
+        assert_eq!(Strobe::ReadStatus.to_string(), "SNOP");
+        assert_eq!(Strobe::EnableTxCCA.to_string(), "STXONCCA");
+        assert_eq!(Strobe::RxFifo.to_string(), "RXFIFO");
+    }
+}
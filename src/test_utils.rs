@@ -0,0 +1,140 @@
+//!
+//! Mock SPI device, GPIO pin and delay for testing the radio driver without hardware
+//!
+
+use core::convert::Infallible;
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{self, InputPin};
+use embedded_hal::spi::{self, Operation, SpiDevice};
+
+/// SPI device that records every byte written to it and answers with
+/// queued responses.
+#[derive(Debug, Default)]
+pub struct MockSpi {
+    /// Bytes written in each transfer (in order)
+    pub transfers: Vec<Vec<u8>>,
+    /// Bytes clocked back on MISO for upcoming transfers, when empty the
+    /// read buffer is left untouched
+    pub responses: VecDeque<Vec<u8>>,
+    /// Bytes the radio is expected to write next (across transfers), when
+    /// empty the written bytes are not checked
+    pub expected: VecDeque<u8>,
+}
+
+impl MockSpi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue the bytes to return from the next unanswered transfer
+    pub fn respond(&mut self, response: &[u8]) {
+        self.responses.push_back(response.to_vec());
+    }
+
+    /// Queue bytes that the following writes must match
+    pub fn expect(&mut self, bytes: &[u8]) {
+        self.expected.extend(bytes);
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) {
+        for (index, &byte) in write.iter().enumerate() {
+            if let Some(expected) = self.expected.pop_front() {
+                assert_eq!(
+                    byte, expected,
+                    "unexpected byte {} of transfer {} ({:02X?})", index, self.transfers.len(), write,
+                );
+            }
+        }
+        self.transfers.push(write.to_vec());
+        if let Some(response) = self.responses.pop_front() {
+            let len = read.len().min(response.len());
+            read[..len].copy_from_slice(&response[..len]);
+        }
+    }
+}
+
+impl spi::ErrorType for MockSpi {
+    type Error = Infallible;
+}
+
+impl SpiDevice<u8> for MockSpi {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        for operation in operations {
+            match operation {
+                Operation::Read(read) => self.transfer(read, &[]),
+                Operation::Write(write) => self.transfer(&mut [], write),
+                Operation::Transfer(read, write) => self.transfer(read, write),
+                Operation::TransferInPlace(buffer) => {
+                    let write = buffer.to_vec();
+                    self.transfer(buffer, &write);
+                },
+                Operation::DelayNs(_) => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Input pin with a level set by the test
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MockInputPin {
+    /// Level of the pin
+    pub high: bool,
+}
+
+impl digital::ErrorType for MockInputPin {
+    type Error = Infallible;
+}
+
+impl InputPin for MockInputPin {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.high)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.high)
+    }
+}
+
+/// Delay that returns immediately
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoDelay;
+
+impl DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::vec;
+
+    #[test]
+    fn test_mock_spi_expected_bytes() {
+        let mut spi = MockSpi::new();
+        spi.expect(&[0x01, 0x02, 0x03]);
+        spi.respond(&[0xAA, 0xBB]);
+
+        let mut buffer = [0x01, 0x02];
+        spi.transfer_in_place(&mut buffer).unwrap();
+        spi.write(&[0x03, 0x04]).unwrap();
+
+        assert_eq!(buffer, [0xAA, 0xBB]);
+        assert!(spi.expected.is_empty());
+        assert_eq!(spi.transfers, [vec![0x01, 0x02], vec![0x03, 0x04]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected byte 1 of transfer 0")]
+    fn test_mock_spi_unexpected_byte() {
+        let mut spi = MockSpi::new();
+        spi.expect(&[0x01, 0x02]);
+
+        spi.write(&[0x01, 0x03]).unwrap();
+    }
+}